@@ -1,6 +1,8 @@
 pub mod auth;
 pub mod booking;
+pub mod cancellation_policy;
 pub mod comment;
+pub mod email_outbox;
 pub mod error_response;
 pub mod guest;
 pub mod room;
@@ -18,7 +18,7 @@ impl Validate for PromoteInput {
     fn validate(&self, validator: &Validator) -> Result<(), ErrorResponse> {
         validator.validate_email(&self.email)?;
 
-        return Ok(());
+        Ok(())
     }
 }
 impl WithClaims for PromoteInput {
@@ -15,13 +15,18 @@ pub struct RegisterUserInput {
     pub email: String,
     #[schema(example = "12345678", required = true)]
     pub password: String,
+    #[schema(example = "en")]
+    pub language: Option<String>,
 }
 impl Validate for RegisterUserInput {
     fn validate(&self, validator: &Validator) -> Result<(), ErrorResponse> {
         validator.validate_email(&self.email)?;
         validator.validate_password(&self.password)?;
+        if let Some(language) = &self.language {
+            validator.validate_language(language)?;
+        }
 
-        return Ok(());
+        Ok(())
     }
 }
 
@@ -1,5 +1,5 @@
 use ::serde::{Deserialize, Serialize};
-use utoipa::{schema, ToSchema};
+use utoipa::ToSchema;
 
 use crate::{
     api::error_response::ErrorResponse,
@@ -23,7 +23,7 @@ impl Validate for ResetPasswordInput {
         validator.validate_password(&self.new_password)?;
         validator.validate_otp(&self.otp)?;
 
-        return Ok(());
+        Ok(())
     }
 }
 
@@ -17,7 +17,7 @@ impl Validate for SendOtpInput {
     fn validate(&self, validator: &Validator) -> Result<(), ErrorResponse> {
         validator.validate_email(&self.email)?;
 
-        return Ok(());
+        Ok(())
     }
 }
 
@@ -1,6 +1,33 @@
+use actix_web::http::StatusCode;
+use sea_orm::prelude::Date;
+
+use super::error_response::ErrorResponse;
+
 pub mod book_room;
 pub mod cancel_booking;
+pub mod check_in;
+pub mod check_out;
+pub mod confirm_booking;
 pub mod find_unoccupied_rooms;
 pub mod get_booking;
 pub mod get_own_bookings;
+pub mod mark_no_show;
+pub mod modify_booking;
 pub mod pay_booking;
+pub mod quote_booking;
+pub mod update_booking_guests;
+
+pub const MAX_OTHER_GUESTS: usize = 10;
+pub const MAX_STAY_DAYS: i64 = 365;
+
+/// Shared by booking and quoting, so nothing can be quoted that can't be booked
+pub fn validate_stay_length(start_date: Date, end_date: Date) -> Result<(), ErrorResponse> {
+    if (end_date - start_date).num_days() > MAX_STAY_DAYS {
+        Err(ErrorResponse::new(
+            format!("Stays can span at most {} days", MAX_STAY_DAYS),
+            StatusCode::BAD_REQUEST,
+        ))
+    } else {
+        Ok(())
+    }
+}
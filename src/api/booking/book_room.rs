@@ -12,6 +12,8 @@ use crate::{
     validation::{Validate, Validator},
 };
 
+use super::{validate_stay_length, MAX_OTHER_GUESTS};
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 #[schema(rename_all = "camelCase")]
@@ -32,6 +34,9 @@ pub struct BookRoomInput {
     pub other_guests: HashSet<Uuid>,
 
     pub guest_user_id: Option<Uuid>,
+
+    /// Overrides the cancellation policy of the room
+    pub cancellation_policy_id: Option<Uuid>,
 }
 impl Validate for BookRoomInput {
     fn validate(&self, _validator: &Validator) -> Result<(), ErrorResponse> {
@@ -49,14 +54,16 @@ impl Validate for BookRoomInput {
             ));
         }
 
-        if self.start_date > self.end_date {
+        if self.start_date >= self.end_date {
             return Err(ErrorResponse::new(
-                "Start date cannot be after end date".to_string(),
+                "Start date needs to be before end date".to_string(),
                 StatusCode::BAD_REQUEST,
             ));
         }
 
-        if self.other_guests.len() > 10 {
+        validate_stay_length(self.start_date, self.end_date)?;
+
+        if self.other_guests.len() > MAX_OTHER_GUESTS {
             return Err(ErrorResponse::new(
                 "Too many guests".to_string(),
                 StatusCode::BAD_REQUEST,
@@ -13,6 +13,10 @@ use crate::{
 #[schema(rename_all = "camelCase")]
 pub struct CancelBookingInput {
     pub booking_id: Uuid,
+    #[serde(skip)]
+    pub canceled_by: Option<Uuid>,
+    #[serde(skip)]
+    pub owner_user_id: Option<Uuid>,
 }
 impl Validate for CancelBookingInput {
     fn validate(&self, _validator: &Validator) -> Result<(), ErrorResponse> {
@@ -20,12 +24,19 @@ impl Validate for CancelBookingInput {
     }
 }
 impl WithClaims for CancelBookingInput {
-    fn with_claims(self, _claims: crate::security::Claims) -> Self {
-        self
+    fn with_claims(self, claims: crate::security::Claims) -> Self {
+        Self {
+            canceled_by: Some(claims.user_id),
+            owner_user_id: claims.owner_scope(),
+            ..self
+        }
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 #[schema(rename_all = "camelCase")]
-pub struct CancelBookingOutput;
+pub struct CancelBookingOutput {
+    pub penalty: i64,
+    pub refunded_amount: i64,
+}
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    api::error_response::ErrorResponse,
+    security::{Claims, WithClaims},
+    validation::{Validate, Validator},
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct ConfirmBookingInput {
+    pub booking_id: Uuid,
+
+    #[serde(skip)]
+    pub confirmed_by: Option<Uuid>,
+}
+impl Validate for ConfirmBookingInput {
+    fn validate(&self, _validator: &Validator) -> Result<(), ErrorResponse> {
+        Validator::validate_option(&self.confirmed_by, "confirmed_by")?;
+
+        Ok(())
+    }
+}
+impl WithClaims for ConfirmBookingInput {
+    fn with_claims(self, claims: Claims) -> Self {
+        Self {
+            confirmed_by: Some(claims.user_id),
+            ..self
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct ConfirmBookingOutput;
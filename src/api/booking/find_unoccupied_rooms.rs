@@ -5,14 +5,27 @@ use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::{
-    api::error_response::ErrorResponse,
+    api::{error_response::ErrorResponse, room::Bed},
+    persistence::{
+        bed::BedSize,
+        room::{BathroomType, RoomSortField, SortOrder},
+    },
     security::WithClaims,
+    util::deserialize_comma_separated,
     validation::{Validate, Validator},
 };
 
 const MAX_CAPACITY: i16 = 20;
 const MIN_CAPACITY: i16 = 1;
 
+const MIN_PAGE_SIZE: u64 = 1;
+const MAX_PAGE_SIZE: u64 = 100;
+const DEFAULT_PAGE_SIZE: u64 = 20;
+
+fn default_page_size() -> u64 {
+    DEFAULT_PAGE_SIZE
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 #[schema(rename_all = "camelCase")]
@@ -28,6 +41,61 @@ pub struct FindUnoccupiedRoomsInput {
 
     #[schema(required = false)]
     pub maximum_capacity: Option<i16>,
+
+    #[schema(required = false)]
+    pub bathroom_type: Option<BathroomType>,
+
+    #[serde(default, deserialize_with = "deserialize_comma_separated")]
+    #[schema(required = false)]
+    pub bed_sizes: Vec<BedSize>,
+
+    #[schema(required = false)]
+    pub minimum_floor: Option<i16>,
+
+    #[schema(required = false)]
+    pub maximum_floor: Option<i16>,
+
+    #[schema(required = false)]
+    pub minimum_price: Option<i64>,
+
+    #[schema(required = false)]
+    pub maximum_price: Option<i64>,
+
+    #[schema(required = false)]
+    pub sort_by: Option<RoomSortField>,
+
+    #[serde(default)]
+    #[schema(required = false)]
+    pub sort_order: SortOrder,
+
+    #[serde(default)]
+    #[schema(example = "0", required = false)]
+    pub page: u64,
+
+    #[serde(default = "default_page_size")]
+    #[schema(example = "20", required = false)]
+    pub size: u64,
+}
+impl FindUnoccupiedRoomsInput {
+    fn validate_range<T>(
+        minimum: Option<T>,
+        maximum: Option<T>,
+        name: &str,
+    ) -> Result<(), ErrorResponse>
+    where
+        T: PartialOrd,
+    {
+        if let (Some(min), Some(max)) = (minimum, maximum) {
+            if max < min {
+                return Err(ErrorResponse::new(
+                    format!("Maximum {name} cannot be less than minimum {name}"),
+                    StatusCode::BAD_REQUEST,
+                ));
+            }
+        }
+
+        Ok(())
+    }
 }
 impl Validate for FindUnoccupiedRoomsInput {
     fn validate(&self, _validator: &Validator) -> Result<(), ErrorResponse> {
@@ -45,9 +113,9 @@ impl Validate for FindUnoccupiedRoomsInput {
             ));
         }
 
-        if self.start_date > self.end_date {
+        if self.start_date >= self.end_date {
             return Err(ErrorResponse::new(
-                "Start date cannot be after end date".to_string(),
+                "Start date needs to be before end date".to_string(),
                 StatusCode::BAD_REQUEST,
             ));
         }
@@ -85,12 +153,22 @@ impl Validate for FindUnoccupiedRoomsInput {
 
             if self.maximum_capacity.is_some() && self.maximum_capacity.unwrap() < min {
                 return Err(ErrorResponse::new(
-                    format!("Maximum capacity cannot be less than minimum capacity").to_string(),
+                    "Maximum capacity cannot be less than minimum capacity".to_string(),
                     StatusCode::BAD_REQUEST,
                 ));
             }
         }
 
+        Self::validate_range(self.minimum_floor, self.maximum_floor, "floor")?;
+        Self::validate_range(self.minimum_price, self.maximum_price, "price")?;
+
+        if !(MIN_PAGE_SIZE..=MAX_PAGE_SIZE).contains(&self.size) {
+            return Err(ErrorResponse::new(
+                format!("Page size needs to be between {MIN_PAGE_SIZE} and {MAX_PAGE_SIZE}"),
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+
         Ok(())
     }
 }
@@ -104,5 +182,19 @@ impl WithClaims for FindUnoccupiedRoomsInput {
 #[serde(rename_all = "camelCase")]
 #[schema(rename_all = "camelCase")]
 pub struct FindUnoccupiedRoomsOutput {
-    pub room_ids: Vec<Uuid>,
+    pub total_size: u64,
+    pub rooms: Vec<UnoccupiedRoom>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct UnoccupiedRoom {
+    pub id: Uuid,
+    pub price: i64,
+    pub floor: i16,
+    pub room_number: String,
+    pub bathroom_type: BathroomType,
+    pub beds: Vec<Bed>,
+    pub capacity: i16,
 }
@@ -5,7 +5,11 @@ use uuid::Uuid;
 
 use crate::{
     api::{error_response::ErrorResponse, guest::GuestIdCard},
-    persistence::{booking::BookingStatus, user::Role},
+    persistence::{
+        booking::BookingStatus,
+        payment::{PaymentMethod, PaymentType},
+        user::Role,
+    },
     security::WithClaims,
     validation::{Validate, Validator},
 };
@@ -40,7 +44,7 @@ pub struct GetBookingOutput {
     pub main_guest: BookingGuest,
     pub other_guests: Vec<BookingGuest>,
     pub room_id: Uuid,
-    pub admin_id: Uuid,
+    pub admin_id: Option<Uuid>,
     pub user_id: Option<Uuid>,
     pub booking_time: DateTime,
     pub payment_time: Option<DateTime>,
@@ -48,6 +52,27 @@ pub struct GetBookingOutput {
     pub end_date: Date,
     pub total_price: i64,
     pub status: BookingStatus,
+    pub check_in_time: Option<DateTime>,
+    pub check_out_time: Option<DateTime>,
+    pub no_show_time: Option<DateTime>,
+    pub cancellation_policy_id: Option<Uuid>,
+    pub cancellation_penalty: Option<i64>,
+    pub cancellation_refund: Option<i64>,
+    pub paid_amount: i64,
+    pub payments: Vec<BookingPayment>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct BookingPayment {
+    pub id: Uuid,
+    pub payment_type: PaymentType,
+    pub amount: i64,
+    pub currency: String,
+    pub method: PaymentMethod,
+    pub provider_reference: Option<String>,
+    pub payment_time: DateTime,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -0,0 +1,75 @@
+use actix_web::http::StatusCode;
+use sea_orm::{prelude::Date, sqlx::types::chrono::Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    api::error_response::ErrorResponse,
+    persistence::booking::BookingStatus,
+    security::{Claims, WithClaims},
+    validation::{Validate, Validator},
+};
+
+use super::validate_stay_length;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct ModifyBookingInput {
+    #[serde(skip)]
+    pub booking_id: Option<Uuid>,
+
+    #[serde(skip)]
+    pub modified_by: Option<Uuid>,
+
+    #[schema(example = "2025-01-01", required = true)]
+    pub start_date: Date,
+
+    #[schema(example = "2025-01-07", required = true)]
+    pub end_date: Date,
+
+    pub room_id: Option<Uuid>,
+}
+impl Validate for ModifyBookingInput {
+    fn validate(&self, _validator: &Validator) -> Result<(), ErrorResponse> {
+        if self.end_date < Utc::now().date_naive() {
+            return Err(ErrorResponse::new(
+                "End date cannot be a past date".to_string(),
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+
+        if self.start_date >= self.end_date {
+            return Err(ErrorResponse::new(
+                "Start date needs to be before end date".to_string(),
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+
+        validate_stay_length(self.start_date, self.end_date)?;
+
+        Validator::validate_option(&self.booking_id, "booking_id")?;
+        Validator::validate_option(&self.modified_by, "modified_by")?;
+
+        Ok(())
+    }
+}
+impl WithClaims for ModifyBookingInput {
+    fn with_claims(self, claims: Claims) -> Self {
+        Self {
+            modified_by: Some(claims.user_id),
+            ..self
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct ModifyBookingOutput {
+    pub total_price: i64,
+    pub status: BookingStatus,
+    /// Paid amount above the new total price that was refunded
+    pub refunded_amount: i64,
+}
@@ -1,31 +1,89 @@
+use actix_web::http::StatusCode;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::{
     api::error_response::ErrorResponse,
+    persistence::{booking::BookingStatus, payment::PaymentMethod},
     security::WithClaims,
     validation::{Validate, Validator},
 };
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+const MAX_PROVIDER_REFERENCE_LENGTH: usize = 128;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 #[schema(rename_all = "camelCase")]
 pub struct PayBookingInput {
+    #[serde(skip)]
     pub booking_id: Uuid,
+    #[serde(skip)]
+    pub paid_by: Option<Uuid>,
+    #[schema(example = 5000)]
+    pub amount: Option<i64>,
+    #[serde(default)]
+    pub method: PaymentMethod,
+    pub provider_reference: Option<String>,
+    pub card_token: Option<String>,
 }
 impl Validate for PayBookingInput {
     fn validate(&self, _validator: &Validator) -> Result<(), ErrorResponse> {
+        if self.amount.is_some_and(|amount| amount <= 0) {
+            return Err(ErrorResponse::new(
+                "Amount needs to be positive".to_string(),
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+
+        match (&self.method, &self.card_token) {
+            (PaymentMethod::Card, None) => {
+                return Err(ErrorResponse::new(
+                    "Card payments require a card token".to_string(),
+                    StatusCode::BAD_REQUEST,
+                ))
+            }
+            (PaymentMethod::Cash | PaymentMethod::BankTransfer, Some(_)) => {
+                return Err(ErrorResponse::new(
+                    "Card token is only allowed for card payments".to_string(),
+                    StatusCode::BAD_REQUEST,
+                ))
+            }
+            _ => {}
+        }
+
+        if self
+            .provider_reference
+            .as_ref()
+            .is_some_and(|reference| reference.len() > MAX_PROVIDER_REFERENCE_LENGTH)
+        {
+            return Err(ErrorResponse::new(
+                format!(
+                    "Provider reference can be at most {} characters",
+                    MAX_PROVIDER_REFERENCE_LENGTH
+                ),
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+
         Ok(())
     }
 }
 impl WithClaims for PayBookingInput {
-    fn with_claims(self, _claims: crate::security::Claims) -> Self {
-        self
+    fn with_claims(self, claims: crate::security::Claims) -> Self {
+        Self {
+            paid_by: Some(claims.user_id),
+            ..self
+        }
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 #[schema(rename_all = "camelCase")]
-pub struct PayBookingOutput;
+pub struct PayBookingOutput {
+    pub payment_id: Uuid,
+    pub paid_amount: i64,
+    pub remaining_amount: i64,
+    pub status: BookingStatus,
+}
@@ -0,0 +1,78 @@
+use actix_web::http::StatusCode;
+use sea_orm::prelude::Date;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    api::{error_response::ErrorResponse, room::NightPrice},
+    security::WithClaims,
+    util::deserialize_comma_separated,
+    validation::{Validate, Validator},
+};
+
+use super::validate_stay_length;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct QuoteBookingInput {
+    #[serde(skip)]
+    pub quoted_by: Option<Uuid>,
+
+    pub room_id: Uuid,
+
+    #[schema(example = "2025-01-01", required = true)]
+    pub start_date: Date,
+
+    #[schema(example = "2025-01-05", required = true)]
+    pub end_date: Date,
+
+    pub main_guest: Uuid,
+
+    #[serde(default, deserialize_with = "deserialize_comma_separated")]
+    #[schema(required = false)]
+    pub other_guests: Vec<Uuid>,
+
+    pub guest_user_id: Option<Uuid>,
+
+    pub cancellation_policy_id: Option<Uuid>,
+}
+impl Validate for QuoteBookingInput {
+    fn validate(&self, _validator: &Validator) -> Result<(), ErrorResponse> {
+        if self.start_date >= self.end_date {
+            return Err(ErrorResponse::new(
+                "Start date needs to be before end date".to_string(),
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+
+        validate_stay_length(self.start_date, self.end_date)
+    }
+}
+impl WithClaims for QuoteBookingInput {
+    fn with_claims(self, claims: crate::security::Claims) -> Self {
+        Self {
+            quoted_by: Some(claims.user_id),
+            ..self
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct QuoteBookingOutput {
+    pub room_id: Uuid,
+    pub start_date: Date,
+    pub end_date: Date,
+    pub nights: Vec<NightPrice>,
+    pub total_price: i64,
+    pub guest_count: i16,
+    pub room_capacity: i16,
+    pub is_capacity_enough: bool,
+    pub is_room_available: bool,
+    pub cancellation_policy_id: Option<Uuid>,
+    /// Errors that creating the booking would currently fail with, empty if it can be booked
+    pub errors: Vec<String>,
+}
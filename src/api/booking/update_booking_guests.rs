@@ -0,0 +1,76 @@
+use std::collections::HashSet;
+
+use actix_web::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    api::error_response::ErrorResponse,
+    security::{Claims, WithClaims},
+    validation::{Validate, Validator},
+};
+
+use super::MAX_OTHER_GUESTS;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct UpdateBookingGuestsInput {
+    #[serde(skip)]
+    pub booking_id: Option<Uuid>,
+
+    #[serde(default)]
+    pub add_guests: HashSet<Uuid>,
+
+    #[serde(default)]
+    pub remove_guests: HashSet<Uuid>,
+
+    pub main_guest: Option<Uuid>,
+}
+impl Validate for UpdateBookingGuestsInput {
+    fn validate(&self, _validator: &Validator) -> Result<(), ErrorResponse> {
+        if self.add_guests.len() > MAX_OTHER_GUESTS {
+            return Err(ErrorResponse::new(
+                "Too many guests".to_string(),
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+
+        if !self.add_guests.is_disjoint(&self.remove_guests) {
+            return Err(ErrorResponse::new(
+                "Guest can't be both added and removed".to_string(),
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+
+        if let Some(main_guest) = &self.main_guest {
+            if self.remove_guests.contains(main_guest) {
+                return Err(ErrorResponse::new(
+                    "Main guest can't be removed".to_string(),
+                    StatusCode::BAD_REQUEST,
+                ));
+            }
+            if self.add_guests.contains(main_guest) {
+                return Err(ErrorResponse::new(
+                    "Main guest is duplicated".to_string(),
+                    StatusCode::BAD_REQUEST,
+                ));
+            }
+        }
+
+        Validator::validate_option(&self.booking_id, "booking_id")?;
+
+        Ok(())
+    }
+}
+impl WithClaims for UpdateBookingGuestsInput {
+    fn with_claims(self, _claims: Claims) -> Self {
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct UpdateBookingGuestsOutput;
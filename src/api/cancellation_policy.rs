@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::persistence::cancellation_policy::PenaltyType;
+
+pub mod add_cancellation_policy;
+pub mod get_cancellation_policies;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct CancellationPolicy {
+    pub id: Uuid,
+    pub name: String,
+    pub free_cancellation_days: Option<i16>,
+    pub penalty_type: PenaltyType,
+    pub penalty_percentage: i16,
+}
@@ -0,0 +1,91 @@
+use actix_web::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    api::error_response::ErrorResponse,
+    persistence::cancellation_policy::PenaltyType,
+    security::WithClaims,
+    validation::{Validate, Validator},
+};
+
+const MAX_NAME_LENGTH: usize = 64;
+const MAX_FREE_CANCELLATION_DAYS: i16 = 365;
+const MIN_PENALTY_PERCENTAGE: i16 = 1;
+const MAX_PENALTY_PERCENTAGE: i16 = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct AddCancellationPolicyInput {
+    #[schema(example = "Flexible", required = true)]
+    pub name: String,
+    /// Days before arrival until which cancellation is free, never free if not set
+    #[schema(example = 7, minimum = 0, maximum = 365)]
+    pub free_cancellation_days: Option<i16>,
+    #[schema(default = "Percentage", required = true)]
+    pub penalty_type: PenaltyType,
+    /// Percentage of the total price charged, only used by the `Percentage` penalty type
+    #[schema(example = 50, minimum = 1, maximum = 100)]
+    pub penalty_percentage: Option<i16>,
+}
+impl Validate for AddCancellationPolicyInput {
+    fn validate(&self, _validator: &Validator) -> Result<(), ErrorResponse> {
+        let name = self.name.trim();
+        if name.is_empty() || name.len() > MAX_NAME_LENGTH {
+            return Err(ErrorResponse::new(
+                format!(
+                    "Name needs to be between 1 and {} characters",
+                    MAX_NAME_LENGTH
+                ),
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+
+        if self
+            .free_cancellation_days
+            .is_some_and(|days| !(0..=MAX_FREE_CANCELLATION_DAYS).contains(&days))
+        {
+            return Err(ErrorResponse::new(
+                format!(
+                    "Free cancellation days need to be between 0 and {}",
+                    MAX_FREE_CANCELLATION_DAYS
+                ),
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+
+        match (&self.penalty_type, self.penalty_percentage) {
+            (PenaltyType::Percentage, Some(percentage))
+                if (MIN_PENALTY_PERCENTAGE..=MAX_PENALTY_PERCENTAGE).contains(&percentage) =>
+            {
+                Ok(())
+            }
+            (PenaltyType::Percentage, _) => Err(ErrorResponse::new(
+                format!(
+                    "Penalty percentage needs to be between {} and {}",
+                    MIN_PENALTY_PERCENTAGE, MAX_PENALTY_PERCENTAGE
+                ),
+                StatusCode::BAD_REQUEST,
+            )),
+            (_, Some(_)) => Err(ErrorResponse::new(
+                "Penalty percentage is only allowed for percentage penalties".to_string(),
+                StatusCode::BAD_REQUEST,
+            )),
+            (_, None) => Ok(()),
+        }
+    }
+}
+impl WithClaims for AddCancellationPolicyInput {
+    fn with_claims(self, _claims: crate::security::Claims) -> Self {
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct AddCancellationPolicyOutput {
+    pub cancellation_policy_id: Uuid,
+}
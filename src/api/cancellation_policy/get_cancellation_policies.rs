@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    api::error_response::ErrorResponse,
+    validation::{Validate, Validator},
+};
+
+use super::CancellationPolicy;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct GetCancellationPoliciesInput;
+impl Validate for GetCancellationPoliciesInput {
+    fn validate(&self, _validator: &Validator) -> Result<(), ErrorResponse> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct GetCancellationPoliciesOutput {
+    pub policies: Vec<CancellationPolicy>,
+}
@@ -0,0 +1,25 @@
+use sea_orm::prelude::DateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::persistence::email_outbox::EmailStatus;
+
+pub mod get_outbox_emails;
+pub mod retry_email;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct OutboxEmail {
+    pub id: Uuid,
+    pub recipient: String,
+    pub template: String,
+    pub status: EmailStatus,
+    pub attempts: i16,
+    pub next_attempt_time: DateTime,
+    pub last_error: Option<String>,
+    pub created_time: DateTime,
+    pub sent_time: Option<DateTime>,
+    pub expiry_time: Option<DateTime>,
+}
@@ -0,0 +1,51 @@
+use actix_web::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    api::error_response::ErrorResponse,
+    persistence::email_outbox::EmailStatus,
+    security::WithClaims,
+    validation::{Validate, Validator},
+};
+
+use super::OutboxEmail;
+
+const MAX_PAGE_SIZE: u64 = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct GetOutboxEmailsInput {
+    #[schema(required = false)]
+    pub status: Option<EmailStatus>,
+
+    pub page: u64,
+
+    pub size: u64,
+}
+impl Validate for GetOutboxEmailsInput {
+    fn validate(&self, _validator: &Validator) -> Result<(), ErrorResponse> {
+        if self.size == 0 || self.size > MAX_PAGE_SIZE {
+            return Err(ErrorResponse::new(
+                format!("Size needs to be between 1 and {}", MAX_PAGE_SIZE),
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+
+        Ok(())
+    }
+}
+impl WithClaims for GetOutboxEmailsInput {
+    fn with_claims(self, _claims: crate::security::Claims) -> Self {
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct GetOutboxEmailsOutput {
+    pub total_size: u64,
+    pub emails: Vec<OutboxEmail>,
+}
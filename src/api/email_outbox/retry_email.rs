@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    api::error_response::ErrorResponse,
+    security::WithClaims,
+    validation::{Validate, Validator},
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct RetryEmailInput {
+    pub email_id: Uuid,
+}
+impl Validate for RetryEmailInput {
+    fn validate(&self, _validator: &Validator) -> Result<(), ErrorResponse> {
+        Ok(())
+    }
+}
+impl WithClaims for RetryEmailInput {
+    fn with_claims(self, _claims: crate::security::Claims) -> Self {
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct RetryEmailOutput;
@@ -43,9 +43,9 @@ impl From<DbErr> for ErrorResponse {
         handle_db_error(value)
     }
 }
-impl Into<HttpResponse<BoxBody>> for ErrorResponse {
-    fn into(self) -> HttpResponse<BoxBody> {
-        let status = self.status;
-        serialize_output::<()>(Err(self), status)
+impl From<ErrorResponse> for HttpResponse<BoxBody> {
+    fn from(value: ErrorResponse) -> Self {
+        let status = value.status;
+        serialize_output::<()>(Err(value), status)
     }
 }
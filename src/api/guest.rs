@@ -1,7 +1,7 @@
 use actix_web::http::StatusCode;
 use sea_orm::{prelude::Date, sqlx::types::chrono::Utc};
 use serde::{Deserialize, Serialize};
-use utoipa::{schema, ToSchema};
+use utoipa::ToSchema;
 
 use crate::validation::{Validate, Validator};
 
@@ -16,6 +16,9 @@ use super::GuestIdCard;
 #[serde(rename_all = "camelCase")]
 #[schema(rename_all = "camelCase")]
 pub struct AddGuestInput {
+    #[serde(skip)]
+    pub owner_user_id: Option<Uuid>,
+
     #[schema(example = "John", required = true)]
     pub first_name: String,
 
@@ -47,15 +50,18 @@ impl Validate for AddGuestInput {
         }
 
         if let Some(phone_number) = &self.phone_number {
-            validator.validate_phone_number(&phone_number)?;
+            validator.validate_phone_number(phone_number)?;
         }
 
         Ok(())
     }
 }
 impl WithClaims for AddGuestInput {
-    fn with_claims(self, _claims: crate::security::Claims) -> Self {
-        self
+    fn with_claims(self, claims: crate::security::Claims) -> Self {
+        Self {
+            owner_user_id: claims.owner_scope(),
+            ..self
+        }
     }
 }
 
@@ -14,6 +14,9 @@ use crate::{
 #[serde(rename_all = "camelCase")]
 #[schema(rename_all = "camelCase")]
 pub struct FindGuestInput {
+    #[serde(skip)]
+    pub owner_user_id: Option<Uuid>,
+
     #[schema(example = "John", required = false)]
     pub first_name: Option<String>,
 
@@ -56,8 +59,11 @@ impl Validate for FindGuestInput {
     }
 }
 impl WithClaims for FindGuestInput {
-    fn with_claims(self, _claims: crate::security::Claims) -> Self {
-        self
+    fn with_claims(self, claims: crate::security::Claims) -> Self {
+        Self {
+            owner_user_id: claims.owner_scope(),
+            ..self
+        }
     }
 }
 
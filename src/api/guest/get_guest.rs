@@ -15,6 +15,9 @@ use super::GuestIdCard;
 #[serde(rename_all = "camelCase")]
 #[schema(rename_all = "camelCase")]
 pub struct GetGuestInput {
+    #[serde(skip)]
+    pub owner_user_id: Option<Uuid>,
+
     pub guest_id: Uuid,
 }
 impl Validate for GetGuestInput {
@@ -23,8 +26,11 @@ impl Validate for GetGuestInput {
     }
 }
 impl WithClaims for GetGuestInput {
-    fn with_claims(self, _claims: crate::security::Claims) -> Self {
-        self
+    fn with_claims(self, claims: crate::security::Claims) -> Self {
+        Self {
+            owner_user_id: claims.owner_scope(),
+            ..self
+        }
     }
 }
 
@@ -16,6 +16,9 @@ use super::GuestIdCard;
 #[serde(rename_all = "camelCase")]
 #[schema(rename_all = "camelCase")]
 pub struct UpdateGuestInput {
+    #[serde(skip)]
+    pub owner_user_id: Option<Uuid>,
+
     #[serde(skip)]
     pub id: Option<Uuid>,
 
@@ -52,15 +55,18 @@ impl Validate for UpdateGuestInput {
         }
 
         if let Some(phone_number) = &self.phone_number {
-            validator.validate_phone_number(&phone_number)?;
+            validator.validate_phone_number(phone_number)?;
         }
 
         Ok(())
     }
 }
 impl WithClaims for UpdateGuestInput {
-    fn with_claims(self, _claims: crate::security::Claims) -> Self {
-        self
+    fn with_claims(self, claims: crate::security::Claims) -> Self {
+        Self {
+            owner_user_id: claims.owner_scope(),
+            ..self
+        }
     }
 }
 
@@ -1,4 +1,8 @@
+use std::collections::HashSet;
+
 use actix_web::http::StatusCode;
+use chrono::{Datelike, Weekday};
+use sea_orm::prelude::Date;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
@@ -9,13 +13,26 @@ use crate::{
 
 use super::error_response::ErrorResponse;
 
+pub mod add_price_rule;
 pub mod add_room;
+pub mod delete_price_rule;
 pub mod delete_room;
+pub mod get_price_rules;
 pub mod get_room;
+pub mod get_room_quote;
+pub mod get_rooms;
+pub mod restore_room;
+pub mod update_room;
 
 const MIN_BED_COUNT: i16 = 1;
 const MAX_BED_COUNT: i16 = 10;
 
+const MIN_FLOOR: i16 = 1;
+const MAX_FLOOR: i16 = 100;
+
+const MIN_PRICE: i64 = 1;
+const MAX_PRICE: i64 = 10_000_000;
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 #[schema(rename_all = "camelCase")]
@@ -40,3 +57,104 @@ impl Validate for Bed {
         Ok(())
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+pub enum DayOfWeek {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+impl DayOfWeek {
+    pub const ALL: [DayOfWeek; 7] = [
+        DayOfWeek::Monday,
+        DayOfWeek::Tuesday,
+        DayOfWeek::Wednesday,
+        DayOfWeek::Thursday,
+        DayOfWeek::Friday,
+        DayOfWeek::Saturday,
+        DayOfWeek::Sunday,
+    ];
+
+    pub fn from_date(date: Date) -> DayOfWeek {
+        match date.weekday() {
+            Weekday::Mon => DayOfWeek::Monday,
+            Weekday::Tue => DayOfWeek::Tuesday,
+            Weekday::Wed => DayOfWeek::Wednesday,
+            Weekday::Thu => DayOfWeek::Thursday,
+            Weekday::Fri => DayOfWeek::Friday,
+            Weekday::Sat => DayOfWeek::Saturday,
+            Weekday::Sun => DayOfWeek::Sunday,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct NightPrice {
+    #[schema(example = "2025-01-01")]
+    pub date: Date,
+    #[schema(example = 5000)]
+    pub price: i64,
+}
+
+fn validate_price(price: i64) -> Result<(), ErrorResponse> {
+    if !(MIN_PRICE..=MAX_PRICE).contains(&price) {
+        return Err(ErrorResponse::new(
+            format!("Price need to be between {} and {}", MIN_PRICE, MAX_PRICE),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    Ok(())
+}
+
+fn validate_unique(beds: &[Bed]) -> Result<(), ErrorResponse> {
+    let sizes: HashSet<_> = beds.iter().map(|bed| bed.bed_size.clone()).collect();
+
+    if sizes.len() != beds.len() {
+        return Err(ErrorResponse::new(
+            "Bed sizes must not repeat".to_string(),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    Ok(())
+}
+
+fn validate_room(
+    validator: &Validator,
+    beds: &[Bed],
+    price: i64,
+    floor: i16,
+    room_number: &str,
+) -> Result<(), ErrorResponse> {
+    validate_unique(beds)?;
+    if beds.is_empty() {
+        return Err(ErrorResponse::new(
+            "Room needs at least 1 bed".to_string(),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    for bed in beds {
+        bed.validate(validator)?;
+    }
+
+    if !(MIN_FLOOR..=MAX_FLOOR).contains(&floor) {
+        return Err(ErrorResponse::new(
+            format!("Floor need to be between {} and {}", MIN_FLOOR, MAX_FLOOR),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    validate_price(price)?;
+
+    validator.validate_room_number(room_number)?;
+
+    Ok(())
+}
@@ -0,0 +1,100 @@
+use std::collections::HashSet;
+
+use actix_web::http::StatusCode;
+use sea_orm::prelude::Date;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    api::error_response::ErrorResponse,
+    persistence::room_price_rule::PriceRuleType,
+    security::WithClaims,
+    validation::{Validate, Validator},
+};
+
+use super::{validate_price, DayOfWeek};
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct AddPriceRuleInput {
+    #[serde(skip)]
+    pub room_id: Option<Uuid>,
+    #[schema(default = "Season", required = true)]
+    pub rule_type: PriceRuleType,
+    #[schema(example = "2025-06-01")]
+    pub start_date: Option<Date>,
+    #[schema(example = "2025-08-31")]
+    pub end_date: Option<Date>,
+    pub weekdays: Option<HashSet<DayOfWeek>>,
+    #[schema(example = "7000", minimum = 1, maximum = 10_000_000, required = true)]
+    pub price: i64,
+}
+impl AddPriceRuleInput {
+    fn validate_dates(&self) -> Result<(), ErrorResponse> {
+        match (&self.rule_type, self.start_date, self.end_date) {
+            (PriceRuleType::Weekday, None, None) => Ok(()),
+            (PriceRuleType::Weekday, _, _) => Err(ErrorResponse::new(
+                "Weekday rules can't have dates".to_string(),
+                StatusCode::BAD_REQUEST,
+            )),
+            (PriceRuleType::Date, Some(start_date), end_date)
+                if end_date.is_none_or(|end_date| end_date == start_date) =>
+            {
+                Ok(())
+            }
+            (PriceRuleType::Date, _, _) => Err(ErrorResponse::new(
+                "Date rules need a single start date".to_string(),
+                StatusCode::BAD_REQUEST,
+            )),
+            (PriceRuleType::Season, Some(start_date), Some(end_date)) if start_date <= end_date => {
+                Ok(())
+            }
+            (PriceRuleType::Season, _, _) => Err(ErrorResponse::new(
+                "Season rules need a start date that is not after the end date".to_string(),
+                StatusCode::BAD_REQUEST,
+            )),
+        }
+    }
+
+    fn validate_weekdays(&self) -> Result<(), ErrorResponse> {
+        match (&self.rule_type, &self.weekdays) {
+            (_, Some(weekdays)) if weekdays.is_empty() => Err(ErrorResponse::new(
+                "Weekdays can't be empty".to_string(),
+                StatusCode::BAD_REQUEST,
+            )),
+            (PriceRuleType::Weekday, None) => Err(ErrorResponse::new(
+                "Weekday rules need weekdays".to_string(),
+                StatusCode::BAD_REQUEST,
+            )),
+            (PriceRuleType::Date, Some(_)) => Err(ErrorResponse::new(
+                "Date rules can't have weekdays".to_string(),
+                StatusCode::BAD_REQUEST,
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+impl Validate for AddPriceRuleInput {
+    fn validate(&self, _validator: &Validator) -> Result<(), ErrorResponse> {
+        Validator::validate_option(&self.room_id, "room_id")?;
+        validate_price(self.price)?;
+        self.validate_dates()?;
+        self.validate_weekdays()?;
+
+        Ok(())
+    }
+}
+impl WithClaims for AddPriceRuleInput {
+    fn with_claims(self, _claims: crate::security::Claims) -> Self {
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct AddPriceRuleOutput {
+    pub price_rule_id: Uuid,
+}
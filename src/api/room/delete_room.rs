@@ -12,11 +12,20 @@ use crate::{
 #[serde(rename_all = "camelCase")]
 #[schema(rename_all = "camelCase")]
 pub struct DeleteRoomInput {
+    #[serde(skip)]
     pub room_id: Uuid,
+    #[serde(skip)]
+    pub deleted_by: Option<Uuid>,
+    #[serde(default)]
+    #[schema(example = false)]
+    pub force: bool,
 }
 impl WithClaims for DeleteRoomInput {
-    fn with_claims(self, _claims: Claims) -> Self {
-        self
+    fn with_claims(self, claims: Claims) -> Self {
+        Self {
+            deleted_by: Some(claims.user_id),
+            ..self
+        }
     }
 }
 impl Validate for DeleteRoomInput {
@@ -28,4 +37,6 @@ impl Validate for DeleteRoomInput {
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 #[schema(rename_all = "camelCase")]
-pub struct DeleteRoomOutput;
+pub struct DeleteRoomOutput {
+    pub canceled_bookings: Vec<Uuid>,
+}
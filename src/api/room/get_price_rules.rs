@@ -0,0 +1,49 @@
+use sea_orm::prelude::Date;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    api::error_response::ErrorResponse,
+    persistence::room_price_rule::PriceRuleType,
+    security::WithClaims,
+    validation::{Validate, Validator},
+};
+
+use super::DayOfWeek;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct GetPriceRulesInput {
+    pub room_id: Uuid,
+}
+impl Validate for GetPriceRulesInput {
+    fn validate(&self, _validator: &Validator) -> Result<(), ErrorResponse> {
+        Ok(())
+    }
+}
+impl WithClaims for GetPriceRulesInput {
+    fn with_claims(self, _claims: crate::security::Claims) -> Self {
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct PriceRule {
+    pub id: Uuid,
+    pub rule_type: PriceRuleType,
+    pub start_date: Option<Date>,
+    pub end_date: Option<Date>,
+    pub weekdays: Option<Vec<DayOfWeek>>,
+    pub price: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct GetPriceRulesOutput {
+    pub rules: Vec<PriceRule>,
+}
@@ -37,5 +37,7 @@ pub struct GetRoomOutput {
     pub floor: i16,
     pub room_number: String,
     pub bathroom_type: BathroomType,
+    pub is_deleted: bool,
+    pub cancellation_policy_id: Option<Uuid>,
     pub beds: Vec<Bed>,
 }
@@ -0,0 +1,54 @@
+use actix_web::http::StatusCode;
+use sea_orm::prelude::Date;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    api::{booking::validate_stay_length, error_response::ErrorResponse},
+    security::WithClaims,
+    validation::{Validate, Validator},
+};
+
+use super::NightPrice;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct GetRoomQuoteInput {
+    #[serde(skip)]
+    pub room_id: Option<Uuid>,
+    #[schema(example = "2025-01-01", required = true)]
+    pub start_date: Date,
+    #[schema(example = "2025-01-05", required = true)]
+    pub end_date: Date,
+}
+impl Validate for GetRoomQuoteInput {
+    fn validate(&self, _validator: &Validator) -> Result<(), ErrorResponse> {
+        Validator::validate_option(&self.room_id, "room_id")?;
+
+        if self.start_date >= self.end_date {
+            return Err(ErrorResponse::new(
+                "Start date needs to be before end date".to_string(),
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+
+        validate_stay_length(self.start_date, self.end_date)
+    }
+}
+
+impl WithClaims for GetRoomQuoteInput {
+    fn with_claims(self, _claims: crate::security::Claims) -> Self {
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct GetRoomQuoteOutput {
+    pub room_id: Uuid,
+    pub nights: Vec<NightPrice>,
+    pub total_price: i64,
+}
@@ -0,0 +1,111 @@
+use actix_web::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    api::error_response::ErrorResponse,
+    persistence::{bed::BedSize, room::BathroomType, user::Role},
+    security::WithClaims,
+    util::deserialize_comma_separated,
+    validation::{Validate, Validator},
+};
+
+use super::get_room::GetRoomOutput;
+
+const MAX_ROOM_NUMBER_PREFIX_LENGTH: usize = 6;
+const MIN_PAGE_SIZE: u64 = 1;
+const MAX_PAGE_SIZE: u64 = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct GetRoomsInput {
+    #[serde(skip)]
+    pub role: Option<Role>,
+
+    #[schema(example = "0")]
+    pub page: u64,
+
+    #[schema(example = "20", minimum = 1, maximum = 100)]
+    pub size: u64,
+
+    #[schema(required = false)]
+    pub floor: Option<i16>,
+
+    #[schema(required = false)]
+    pub bathroom_type: Option<BathroomType>,
+
+    #[serde(default, deserialize_with = "deserialize_comma_separated")]
+    #[schema(required = false)]
+    pub bed_sizes: Vec<BedSize>,
+
+    #[schema(required = false)]
+    pub minimum_price: Option<i64>,
+
+    #[schema(required = false)]
+    pub maximum_price: Option<i64>,
+
+    #[schema(required = false)]
+    pub room_number_prefix: Option<String>,
+
+    #[serde(default)]
+    #[schema(required = false)]
+    pub include_deleted: bool,
+}
+impl Validate for GetRoomsInput {
+    fn validate(&self, _validator: &Validator) -> Result<(), ErrorResponse> {
+        Validator::validate_option(&self.role, "role")?;
+
+        if self.include_deleted && self.role != Some(Role::Admin) {
+            return Err(ErrorResponse::new(
+                "Only admins can include deleted rooms".to_string(),
+                StatusCode::FORBIDDEN,
+            ));
+        }
+
+        if !(MIN_PAGE_SIZE..=MAX_PAGE_SIZE).contains(&self.size) {
+            return Err(ErrorResponse::new(
+                format!("Page size needs to be between {MIN_PAGE_SIZE} and {MAX_PAGE_SIZE}"),
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+
+        if let (Some(min), Some(max)) = (self.minimum_price, self.maximum_price) {
+            if max < min {
+                return Err(ErrorResponse::new(
+                    "Maximum price cannot be less than minimum price".to_string(),
+                    StatusCode::BAD_REQUEST,
+                ));
+            }
+        }
+
+        if let Some(prefix) = &self.room_number_prefix {
+            let is_valid = prefix.len() <= MAX_ROOM_NUMBER_PREFIX_LENGTH
+                && prefix.chars().all(|c| c.is_ascii_alphanumeric());
+            if !is_valid {
+                return Err(ErrorResponse::new(
+                    format!("Invalid room number prefix '{}'", prefix),
+                    StatusCode::BAD_REQUEST,
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+impl WithClaims for GetRoomsInput {
+    fn with_claims(self, claims: crate::security::Claims) -> Self {
+        Self {
+            role: Some(claims.role),
+            ..self
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct GetRoomsOutput {
+    pub total_size: u64,
+    pub rooms: Vec<GetRoomOutput>,
+}
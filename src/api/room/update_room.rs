@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    api::error_response::ErrorResponse,
+    persistence::room::BathroomType,
+    security::WithClaims,
+    validation::{Validate, Validator},
+};
+
+use super::{validate_room, Bed};
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct UpdateRoomInput {
+    #[serde(skip)]
+    pub room_id: Option<Uuid>,
+    pub beds: Vec<Bed>,
+    #[schema(example = "5000", minimum = 1, maximum = 10_000_000, required = true)]
+    pub price: i64,
+    #[schema(example = "3", minimum = 1, maximum = 100, required = true)]
+    pub floor: i16,
+    #[schema(example = "108A", required = true)]
+    pub room_number: String,
+    #[schema(default = "Private", required = true)]
+    pub bathroom_type: BathroomType,
+    pub cancellation_policy_id: Option<Uuid>,
+}
+impl Validate for UpdateRoomInput {
+    fn validate(&self, validator: &Validator) -> Result<(), ErrorResponse> {
+        Validator::validate_option(&self.room_id, "room_id")?;
+
+        validate_room(
+            validator,
+            &self.beds,
+            self.price,
+            self.floor,
+            &self.room_number,
+        )
+    }
+}
+impl WithClaims for UpdateRoomInput {
+    fn with_claims(self, _claims: crate::security::Claims) -> Self {
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct UpdateRoomOutput;
@@ -1,15 +1,30 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, path::PathBuf, str::FromStr, sync::Arc, time::Duration};
 
+use actix_web::http::KeepAlive;
 use log::{error, info};
+use rustls::pki_types::{pem::PemObject, CertificateDer, PrivateKeyDer};
 use sea_orm::{ConnectOptions, Database, DatabaseConnection};
 
 use crate::{
     constants::{
-        DB_LOGGING_LEVEL, ENV_DATABASE_URL, ENV_JWT_SECRET, ENV_JWT_VALIDITY_SECS,
-        ENV_OTP_VALIDITY_SECS,
+        DB_LOGGING_LEVEL, DEFAULT_BOOKING_REMINDER_CHECK_INTERVAL_SECS, DEFAULT_EMAIL_MAX_ATTEMPTS,
+        DEFAULT_EMAIL_OUTBOX_BATCH_SIZE, DEFAULT_EMAIL_OUTBOX_CHECK_INTERVAL_SECS,
+        DEFAULT_EMAIL_RETRY_BASE_DELAY_SECS, DEFAULT_SERVER_HOST, DEFAULT_SERVER_MAX_BODY_BYTES,
+        DEFAULT_SERVER_PORT, DEFAULT_UNPAID_BOOKING_CHECK_INTERVAL_SECS,
+        ENV_AUTO_CONFIRM_USER_BOOKINGS, ENV_BOOKING_REMINDER_CHECK_INTERVAL_SECS,
+        ENV_BOOKING_REMINDER_DAYS_BEFORE_START, ENV_DATABASE_URL, ENV_EMAIL_MAX_ATTEMPTS,
+        ENV_EMAIL_OUTBOX_CHECK_INTERVAL_SECS, ENV_EMAIL_RETRY_BASE_DELAY_SECS, ENV_JWT_SECRET,
+        ENV_JWT_VALIDITY_SECS, ENV_OTP_VALIDITY_SECS, ENV_SERVER_HOST, ENV_SERVER_KEEP_ALIVE_SECS,
+        ENV_SERVER_MAX_BODY_BYTES, ENV_SERVER_PORT, ENV_SERVER_TLS_CERT_PATH,
+        ENV_SERVER_TLS_KEY_PATH, ENV_SERVER_WORKERS, ENV_UNPAID_BOOKING_CHECK_INTERVAL_SECS,
+        ENV_UNPAID_BOOKING_EXPIRY_DAYS_BEFORE_START, ENV_UNPAID_BOOKING_EXPIRY_HOURS,
+        SQLITE_IN_MEMORY_CONNECTION_LIFETIME_SECS,
     },
     persistence::initialise_db,
-    services::email_service::EmailService,
+    services::{
+        email_service::EmailService,
+        payment_provider::{load_payment_provider, PaymentProvider},
+    },
     validation::Validator,
 };
 
@@ -23,6 +38,16 @@ impl EnvironmentVariables {
         }
     }
 
+    #[cfg(test)]
+    pub fn from_vars(vars: &[(&str, &str)]) -> Self {
+        Self {
+            env: vars
+                .iter()
+                .map(|(key, val)| (key.to_string(), val.to_string()))
+                .collect(),
+        }
+    }
+
     pub fn get(&self, key: &str) -> &str {
         let val = self.env.get(key);
         if val.is_none() {
@@ -33,6 +58,102 @@ impl EnvironmentVariables {
 
         val.unwrap()
     }
+
+    pub fn get_optional<T>(&self, key: &str) -> Option<T>
+    where
+        T: FromStr,
+    {
+        self.env.get(key).map(|val| {
+            val.parse()
+                .unwrap_or_else(|_| panic!("Invalid format for environment variable '{key}'"))
+        })
+    }
+}
+
+pub struct TlsInfo {
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+}
+impl TlsInfo {
+    pub fn load_server_config(&self) -> rustls::ServerConfig {
+        let certs = CertificateDer::pem_file_iter(&self.cert_path)
+            .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+            .unwrap_or_else(|err| {
+                panic!(
+                    "Can't read TLS certificates from '{}': {:?}",
+                    self.cert_path.display(),
+                    err
+                )
+            });
+        let key = PrivateKeyDer::from_pem_file(&self.key_path).unwrap_or_else(|err| {
+            panic!(
+                "Can't read TLS private key from '{}': {:?}",
+                self.key_path.display(),
+                err
+            )
+        });
+
+        rustls::ServerConfig::builder_with_provider(Arc::new(
+            rustls::crypto::ring::default_provider(),
+        ))
+        .with_safe_default_protocol_versions()
+        .expect("Invalid TLS protocol versions")
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .unwrap_or_else(|err| panic!("Invalid TLS certificate or private key: {err}"))
+    }
+}
+
+pub struct ServerInfo {
+    pub host: String,
+    pub port: u16,
+    /// The number of physical CPU cores if not set
+    pub workers: Option<usize>,
+    pub keep_alive: KeepAlive,
+    pub max_body_bytes: usize,
+    /// Serves HTTPS instead of HTTP when set
+    pub tls: Option<TlsInfo>,
+}
+impl ServerInfo {
+    pub fn new(env: &EnvironmentVariables) -> Self {
+        let keep_alive = match env.get_optional(ENV_SERVER_KEEP_ALIVE_SECS) {
+            None => KeepAlive::default(),
+            Some(0) => KeepAlive::Disabled,
+            Some(secs) => KeepAlive::Timeout(Duration::from_secs(secs)),
+        };
+        let tls = match (
+            env.get_optional(ENV_SERVER_TLS_CERT_PATH),
+            env.get_optional(ENV_SERVER_TLS_KEY_PATH),
+        ) {
+            (Some(cert_path), Some(key_path)) => Some(TlsInfo {
+                cert_path,
+                key_path,
+            }),
+            (None, None) => None,
+            _ => panic!(
+                "Both {ENV_SERVER_TLS_CERT_PATH} and {ENV_SERVER_TLS_KEY_PATH} must be set for TLS"
+            ),
+        };
+        let workers = env.get_optional(ENV_SERVER_WORKERS);
+        if workers == Some(0) {
+            panic!("{ENV_SERVER_WORKERS} must be at least 1");
+        }
+
+        Self {
+            host: env
+                .get_optional(ENV_SERVER_HOST)
+                .unwrap_or_else(|| DEFAULT_SERVER_HOST.to_owned()),
+            port: env
+                .get_optional(ENV_SERVER_PORT)
+                .unwrap_or(DEFAULT_SERVER_PORT),
+            workers,
+            keep_alive,
+            max_body_bytes: env
+                .get_optional(ENV_SERVER_MAX_BODY_BYTES)
+                .unwrap_or(DEFAULT_SERVER_MAX_BODY_BYTES),
+            tls,
+        }
+    }
 }
 
 pub struct SecurityInfo {
@@ -43,13 +164,15 @@ pub struct SecurityInfo {
 impl SecurityInfo {
     fn new(env: &EnvironmentVariables) -> Self {
         let jwt_secret = env.get(ENV_JWT_SECRET).to_string();
-        let jwt_validity = env.get(ENV_JWT_VALIDITY_SECS).parse().expect(&format!(
-            "Invalid number format for {ENV_JWT_VALIDITY_SECS}"
-        ));
+        let jwt_validity = env
+            .get(ENV_JWT_VALIDITY_SECS)
+            .parse()
+            .unwrap_or_else(|_| panic!("Invalid number format for {ENV_JWT_VALIDITY_SECS}"));
 
-        let otp_validity = env.get(ENV_OTP_VALIDITY_SECS).parse().expect(&format!(
-            "Invalid number format for {ENV_OTP_VALIDITY_SECS}"
-        ));
+        let otp_validity = env
+            .get(ENV_OTP_VALIDITY_SECS)
+            .parse()
+            .unwrap_or_else(|_| panic!("Invalid number format for {ENV_OTP_VALIDITY_SECS}"));
 
         Self {
             jwt_secret,
@@ -59,41 +182,135 @@ impl SecurityInfo {
     }
 }
 
+pub struct BookingExpiryInfo {
+    pub check_interval: u64,
+    pub hours_after_booking: Option<u64>,
+    pub days_before_start: Option<u64>,
+}
+impl BookingExpiryInfo {
+    fn new(env: &EnvironmentVariables) -> Self {
+        Self {
+            check_interval: env
+                .get_optional(ENV_UNPAID_BOOKING_CHECK_INTERVAL_SECS)
+                .unwrap_or(DEFAULT_UNPAID_BOOKING_CHECK_INTERVAL_SECS),
+            hours_after_booking: env.get_optional(ENV_UNPAID_BOOKING_EXPIRY_HOURS),
+            days_before_start: env.get_optional(ENV_UNPAID_BOOKING_EXPIRY_DAYS_BEFORE_START),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.hours_after_booking.is_some() || self.days_before_start.is_some()
+    }
+}
+
+pub struct EmailOutboxInfo {
+    pub check_interval: u64,
+    pub batch_size: u64,
+    pub max_attempts: i16,
+    pub retry_base_delay: u64,
+}
+impl EmailOutboxInfo {
+    fn new(env: &EnvironmentVariables) -> Self {
+        Self {
+            check_interval: env
+                .get_optional(ENV_EMAIL_OUTBOX_CHECK_INTERVAL_SECS)
+                .unwrap_or(DEFAULT_EMAIL_OUTBOX_CHECK_INTERVAL_SECS),
+            batch_size: DEFAULT_EMAIL_OUTBOX_BATCH_SIZE,
+            max_attempts: env
+                .get_optional(ENV_EMAIL_MAX_ATTEMPTS)
+                .unwrap_or(DEFAULT_EMAIL_MAX_ATTEMPTS),
+            retry_base_delay: env
+                .get_optional(ENV_EMAIL_RETRY_BASE_DELAY_SECS)
+                .unwrap_or(DEFAULT_EMAIL_RETRY_BASE_DELAY_SECS),
+        }
+    }
+}
+
+pub struct BookingReminderInfo {
+    pub check_interval: u64,
+    pub days_before_start: Option<u64>,
+}
+impl BookingReminderInfo {
+    fn new(env: &EnvironmentVariables) -> Self {
+        Self {
+            check_interval: env
+                .get_optional(ENV_BOOKING_REMINDER_CHECK_INTERVAL_SECS)
+                .unwrap_or(DEFAULT_BOOKING_REMINDER_CHECK_INTERVAL_SECS),
+            days_before_start: env.get_optional(ENV_BOOKING_REMINDER_DAYS_BEFORE_START),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct AppState {
     pub db: Arc<DatabaseConnection>,
     pub validator: Arc<Validator>,
     pub security_info: Arc<SecurityInfo>,
     pub email_service: Arc<EmailService>,
+    pub booking_expiry_info: Arc<BookingExpiryInfo>,
+    pub booking_reminder_info: Arc<BookingReminderInfo>,
+    pub email_outbox_info: Arc<EmailOutboxInfo>,
+    pub payment_provider: Arc<dyn PaymentProvider>,
+    /// Bookings made by users skip the admin confirmation
+    pub auto_confirm_user_bookings: bool,
 }
 impl AppState {
-    pub async fn load() -> Self {
-        let env = EnvironmentVariables::load();
-        let security_info = SecurityInfo::new(&env);
-        let email_service = EmailService::new(&env);
+    pub async fn load(env: &EnvironmentVariables) -> Self {
+        let email_service = EmailService::new(env);
+        let db = load_databse(env).await;
+
+        Self::new(env, db, email_service)
+    }
 
-        let state = Self {
-            db: Arc::new(load_databse(&env).await),
+    /// Builds the state around an already initialised database
+    pub fn new(
+        env: &EnvironmentVariables,
+        db: DatabaseConnection,
+        email_service: EmailService,
+    ) -> Self {
+        Self {
+            db: Arc::new(db),
             validator: Arc::new(Validator::new()),
-            security_info: Arc::new(security_info),
+            security_info: Arc::new(SecurityInfo::new(env)),
             email_service: Arc::new(email_service),
-        };
-
-        state
+            booking_expiry_info: Arc::new(BookingExpiryInfo::new(env)),
+            booking_reminder_info: Arc::new(BookingReminderInfo::new(env)),
+            email_outbox_info: Arc::new(EmailOutboxInfo::new(env)),
+            payment_provider: Arc::from(load_payment_provider(env)),
+            auto_confirm_user_bookings: env
+                .get_optional(ENV_AUTO_CONFIRM_USER_BOOKINGS)
+                .unwrap_or(false),
+        }
     }
 }
 
-async fn load_databse(env: &EnvironmentVariables) -> DatabaseConnection {
+fn is_in_memory_sqlite(database_url: &str) -> bool {
+    database_url.starts_with("sqlite:")
+        && (database_url.contains(":memory:") || database_url.contains("mode=memory"))
+}
+
+pub async fn connect_database(env: &EnvironmentVariables) -> DatabaseConnection {
     let database_url = env.get(ENV_DATABASE_URL);
 
     let mut database_config = ConnectOptions::new(database_url);
     database_config.sqlx_logging_level(DB_LOGGING_LEVEL);
+    if is_in_memory_sqlite(database_url) {
+        // The database is gone once the pool recycles its only connection
+        let lifetime = Duration::from_secs(SQLITE_IN_MEMORY_CONNECTION_LIFETIME_SECS);
+        database_config
+            .min_connections(1)
+            .idle_timeout(lifetime)
+            .max_lifetime(lifetime);
+    }
 
-    let db = Database::connect(database_config)
+    Database::connect(database_config)
         .await
-        .expect("Failed to connect to database");
+        .expect("Failed to connect to database")
+}
 
-    initialise_db(&db, &env).await;
+async fn load_databse(env: &EnvironmentVariables) -> DatabaseConnection {
+    let db = connect_database(env).await;
+    initialise_db(&db, env).await;
     info!("Database initilised");
     db
 }
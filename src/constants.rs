@@ -1,23 +1,64 @@
 use log::LevelFilter;
 
-pub const REST_HOST: (&str, u16) = ("127.0.0.1", 8080);
+pub const DEFAULT_SERVER_HOST: &str = "127.0.0.1";
+pub const DEFAULT_SERVER_PORT: u16 = 8080;
+pub const DEFAULT_SERVER_MAX_BODY_BYTES: usize = 2 * 1024 * 1024;
 
 pub const APP_DEFAULT_LOGGING_LEVEL: &str = "info";
 pub const DB_LOGGING_LEVEL: LevelFilter = LevelFilter::Debug;
+pub const SQLITE_IN_MEMORY_CONNECTION_LIFETIME_SECS: u64 = u32::MAX as u64;
 
 pub const BCRYPT_COST: u32 = 10;
 pub const BEARER_PREFIX: &str = "Bearer ";
 pub const OTP_LENGTH: usize = 8;
+pub const DEFAULT_UNPAID_BOOKING_CHECK_INTERVAL_SECS: u64 = 600;
+pub const DEFAULT_BOOKING_REMINDER_CHECK_INTERVAL_SECS: u64 = 3600;
+pub const DEFAULT_EMAIL_OUTBOX_CHECK_INTERVAL_SECS: u64 = 10;
+pub const DEFAULT_EMAIL_OUTBOX_BATCH_SIZE: u64 = 50;
+pub const DEFAULT_EMAIL_MAX_ATTEMPTS: i16 = 5;
+pub const DEFAULT_EMAIL_RETRY_BASE_DELAY_SECS: u64 = 30;
+pub const EMAIL_SEND_LEASE_SECS: u64 = 300;
+pub const DEFAULT_EMAIL_SENDER: &str = "hotel@localhost";
+pub const DEFAULT_EMAIL_FILE_DIR: &str = "emails";
+pub const CURRENCY: &str = "EUR";
 
+pub const ENV_SERVER_HOST: &str = "SERVER_HOST";
+pub const ENV_SERVER_PORT: &str = "SERVER_PORT";
+pub const ENV_SERVER_WORKERS: &str = "SERVER_WORKERS";
+pub const ENV_SERVER_KEEP_ALIVE_SECS: &str = "SERVER_KEEP_ALIVE_SECS";
+pub const ENV_SERVER_MAX_BODY_BYTES: &str = "SERVER_MAX_BODY_BYTES";
+pub const ENV_SERVER_TLS_CERT_PATH: &str = "SERVER_TLS_CERT_PATH";
+pub const ENV_SERVER_TLS_KEY_PATH: &str = "SERVER_TLS_KEY_PATH";
 pub const ENV_INITIAL_ADMIN_EMAIL: &str = "INITIAL_ADMIN_EMAIL";
 pub const ENV_INITIAL_ADMIN_PASSWORD: &str = "INITIAL_ADMIN_PASSWORD";
 pub const ENV_DATABASE_URL: &str = "DATABASE_URL";
+pub const ENV_MIGRATE_ON_STARTUP: &str = "MIGRATE_ON_STARTUP";
 pub const ENV_JWT_SECRET: &str = "JWT_SECRET";
 pub const ENV_JWT_VALIDITY_SECS: &str = "JWT_VALIDITY_SECS";
 pub const ENV_OTP_VALIDITY_SECS: &str = "OTP_VALIDITY_SECS";
 pub const ENV_EMAIL_RELAY: &str = "EMAIL_RELAY";
 pub const ENV_EMAIL_USERNAME: &str = "EMAIL_USERNAME";
 pub const ENV_EMAIL_PASSWORD: &str = "EMAIL_PASSWORD";
+pub const ENV_EMAIL_SENDER: &str = "EMAIL_SENDER";
+pub const ENV_EMAIL_TRANSPORT: &str = "EMAIL_TRANSPORT";
+pub const ENV_EMAIL_FILE_DIR: &str = "EMAIL_FILE_DIR";
+pub const ENV_EMAIL_TEMPLATE_DIR: &str = "EMAIL_TEMPLATE_DIR";
+pub const ENV_EMAIL_DEFAULT_LANGUAGE: &str = "EMAIL_DEFAULT_LANGUAGE";
+pub const ENV_PAYMENT_PROVIDER: &str = "PAYMENT_PROVIDER";
+pub const ENV_UNPAID_BOOKING_CHECK_INTERVAL_SECS: &str = "UNPAID_BOOKING_CHECK_INTERVAL_SECS";
+pub const ENV_UNPAID_BOOKING_EXPIRY_HOURS: &str = "UNPAID_BOOKING_EXPIRY_HOURS";
+pub const ENV_UNPAID_BOOKING_EXPIRY_DAYS_BEFORE_START: &str =
+    "UNPAID_BOOKING_EXPIRY_DAYS_BEFORE_START";
+pub const ENV_AUTO_CONFIRM_USER_BOOKINGS: &str = "AUTO_CONFIRM_USER_BOOKINGS";
+pub const ENV_BOOKING_REMINDER_CHECK_INTERVAL_SECS: &str = "BOOKING_REMINDER_CHECK_INTERVAL_SECS";
+pub const ENV_BOOKING_REMINDER_DAYS_BEFORE_START: &str = "BOOKING_REMINDER_DAYS_BEFORE_START";
+pub const ENV_EMAIL_OUTBOX_CHECK_INTERVAL_SECS: &str = "EMAIL_OUTBOX_CHECK_INTERVAL_SECS";
+pub const ENV_EMAIL_MAX_ATTEMPTS: &str = "EMAIL_MAX_ATTEMPTS";
+pub const ENV_EMAIL_RETRY_BASE_DELAY_SECS: &str = "EMAIL_RETRY_BASE_DELAY_SECS";
+#[cfg(test)]
+pub const ENV_TEST_DATABASE_URL: &str = "TEST_DATABASE_URL";
+#[cfg(test)]
+pub const DEFAULT_TEST_DATABASE_URL: &str = "sqlite::memory:";
 
 pub const API_NAME: &str = "Hotel API";
 pub const API_VERSION: &str = "0.1.0";
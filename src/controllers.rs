@@ -7,7 +7,9 @@ use crate::constants::{API_DESCRIPTION, API_NAME, API_VERSION};
 
 pub mod auth;
 pub mod booking;
+pub mod cancellation_policy;
 pub mod comment;
+pub mod email_outbox;
 pub mod guest;
 pub mod room;
 
@@ -23,25 +25,43 @@ pub mod room;
     auth::logout_controller,
     room::add_room_controller,
     room::get_room_controller,
+    room::get_rooms_controller,
     room::delete_room_controller,
+    room::restore_room_controller,
+    room::update_room_controller,
+    room::add_price_rule_controller,
+    room::get_price_rules_controller,
+    room::delete_price_rule_controller,
+    room::get_room_quote_controller,
     guest::add_guest_controller,
     guest::find_guest_controller,
     guest::get_guest_controller,
     guest::update_guest_controller,
     booking::find_unoccupied_rooms_controller,
+    booking::quote_booking_controller,
     booking::book_room_controller,
     booking::pay_booking_controller,
+    booking::modify_booking_controller,
+    booking::update_booking_guests_controller,
+    booking::confirm_booking_controller,
+    booking::check_in_controller,
+    booking::check_out_controller,
+    booking::mark_no_show_controller,
     booking::get_booking_controller,
     booking::get_own_bookings_controller,
     booking::cancel_booking_controller,
     comment::add_comment_controller,
     comment::get_comments_controller,
-    comment::update_comment_controller
+    comment::update_comment_controller,
+    cancellation_policy::add_cancellation_policy_controller,
+    cancellation_policy::get_cancellation_policies_controller,
+    email_outbox::get_outbox_emails_controller,
+    email_outbox::retry_email_controller
 ))]
 pub struct ApiDoc;
 
 impl ApiDoc {
-    pub fn new() -> OpenApi {
+    pub fn build() -> OpenApi {
         let mut api = <ApiDoc as utoipa::OpenApi>::openapi();
         let mut componenets = Components::new();
         componenets.schemas.insert(
@@ -64,6 +84,8 @@ impl ApiDoc {
         api.merge(<guest::GuestApiDoc as utoipa::OpenApi>::openapi());
         api.merge(<booking::BookingApiDoc as utoipa::OpenApi>::openapi());
         api.merge(<comment::CommentApiDoc as utoipa::OpenApi>::openapi());
+        api.merge(<cancellation_policy::CancellationPolicyApiDoc as utoipa::OpenApi>::openapi());
+        api.merge(<email_outbox::EmailOutboxApiDoc as utoipa::OpenApi>::openapi());
         api.info = Info::new(API_NAME, API_VERSION);
         api.info.description = Some(API_DESCRIPTION.to_string());
 
@@ -62,8 +62,7 @@ use crate::{
         SendOtpOutput,
         ResetPasswordInput,
         ResetPasswordOutput,
-        LogoutInput,
-        LoginOutput
+        LogoutInput
     ))
 )]
 pub struct AuthApiDoc;
@@ -13,19 +13,37 @@ use crate::{
         booking::{
             book_room::{BookRoomInput, BookRoomOutput},
             cancel_booking::{CancelBookingInput, CancelBookingOutput},
-            find_unoccupied_rooms::{FindUnoccupiedRoomsInput, FindUnoccupiedRoomsOutput},
-            get_booking::{BookingGuest, GetBookingInput, GetBookingOutput},
+            check_in::{CheckInInput, CheckInOutput},
+            check_out::{CheckOutInput, CheckOutOutput},
+            confirm_booking::{ConfirmBookingInput, ConfirmBookingOutput},
+            find_unoccupied_rooms::{
+                FindUnoccupiedRoomsInput, FindUnoccupiedRoomsOutput, UnoccupiedRoom,
+            },
+            get_booking::{BookingGuest, BookingPayment, GetBookingInput, GetBookingOutput},
             get_own_bookings::{GetOwnBookingsInput, GetOwnBookingsOutput},
+            mark_no_show::{MarkNoShowInput, MarkNoShowOutput},
+            modify_booking::{ModifyBookingInput, ModifyBookingOutput},
             pay_booking::{PayBookingInput, PayBookingOutput},
+            quote_booking::{QuoteBookingInput, QuoteBookingOutput},
+            update_booking_guests::{UpdateBookingGuestsInput, UpdateBookingGuestsOutput},
         },
         error_response::ErrorResponse,
     },
     app_state::AppState,
-    persistence::{booking::BookingStatus, user::Role},
+    persistence::{
+        booking::BookingStatus,
+        payment::{PaymentMethod, PaymentType},
+        room::{RoomSortField, SortOrder},
+        user::Role,
+    },
     services::booking::{
         book_room::book_room_service, cancel_booking::cancel_booking_service,
+        check_in::check_in_service, check_out::check_out_service,
+        confirm_booking::confirm_booking_service,
         find_unoccupied_rooms::find_unoccupied_rooms_service, get_booking::get_booking_service,
-        get_own_bookings::get_own_bookings_service, pay_booking::pay_booking_service,
+        get_own_bookings::get_own_bookings_service, mark_no_show::mark_no_show_service,
+        modify_booking::modify_booking_service, pay_booking::pay_booking_service,
+        quote_booking::quote_booking_service, update_booking_guests::update_booking_guests_service,
     },
     util::process_request_secured,
 };
@@ -34,8 +52,15 @@ use crate::{
 #[openapi(
     paths(
         find_unoccupied_rooms_controller,
+        quote_booking_controller,
         book_room_controller,
+        confirm_booking_controller,
         pay_booking_controller,
+        modify_booking_controller,
+        update_booking_guests_controller,
+        check_in_controller,
+        check_out_controller,
+        mark_no_show_controller,
         get_booking_controller,
         get_own_bookings_controller,
         cancel_booking_controller
@@ -44,10 +69,30 @@ use crate::{
         ErrorResponse,
         FindUnoccupiedRoomsInput,
         FindUnoccupiedRoomsOutput,
+        UnoccupiedRoom,
+        RoomSortField,
+        SortOrder,
+        QuoteBookingInput,
+        QuoteBookingOutput,
         BookRoomInput,
         BookRoomOutput,
+        ConfirmBookingInput,
+        ConfirmBookingOutput,
         PayBookingInput,
         PayBookingOutput,
+        PaymentMethod,
+        PaymentType,
+        BookingPayment,
+        ModifyBookingInput,
+        ModifyBookingOutput,
+        UpdateBookingGuestsInput,
+        UpdateBookingGuestsOutput,
+        CheckInInput,
+        CheckInOutput,
+        CheckOutInput,
+        CheckOutOutput,
+        MarkNoShowInput,
+        MarkNoShowOutput,
         BookingStatus,
         BookingGuest,
         GetBookingInput,
@@ -62,8 +107,15 @@ pub struct BookingApiDoc;
 
 pub fn config(cfg: &mut ServiceConfig) {
     cfg.service(find_unoccupied_rooms_controller);
+    cfg.service(quote_booking_controller);
     cfg.service(book_room_controller);
+    cfg.service(confirm_booking_controller);
     cfg.service(pay_booking_controller);
+    cfg.service(modify_booking_controller);
+    cfg.service(update_booking_guests_controller);
+    cfg.service(check_in_controller);
+    cfg.service(check_out_controller);
+    cfg.service(mark_no_show_controller);
     cfg.service(get_booking_controller);
     cfg.service(get_own_bookings_controller);
     cfg.service(cancel_booking_controller);
@@ -80,6 +132,16 @@ pub fn config(cfg: &mut ServiceConfig) {
         ("endDate" = Date, Query, description = "End date for booking", example = "2025-01-01"),
         ("minimumCapacity" = Option<i16>, Query, description = "Minimum room capacity", example = "2", nullable),
         ("maximumCapacity" = Option<i16>, Query, description = "Maximum room capacity", example = "3", nullable),
+        ("bathroomType" = Option<BathroomType>, Query, description = "Bathroom type", example = "Private", nullable),
+        ("bedSizes" = Option<String>, Query, description = "Comma separated bed sizes the room needs to have at least one of each", example = "KingSize,Single", nullable),
+        ("minimumFloor" = Option<i16>, Query, description = "Minimum floor", example = "1", nullable),
+        ("maximumFloor" = Option<i16>, Query, description = "Maximum floor", example = "5", nullable),
+        ("minimumPrice" = Option<i64>, Query, description = "Minimum price", example = "1000", nullable),
+        ("maximumPrice" = Option<i64>, Query, description = "Maximum price", example = "10000", nullable),
+        ("sortBy" = Option<RoomSortField>, Query, description = "Field to sort by (room number if not set)", example = "Price", nullable),
+        ("sortOrder" = Option<SortOrder>, Query, description = "Sort order", example = "Ascending", nullable),
+        ("page" = Option<u64>, Query, description = "Page index", example = "0", nullable),
+        ("size" = Option<u64>, Query, description = "Number of rooms to retrieve", example = "20", nullable),
     ),
     security(("bearer_auth" = []))
 )]
@@ -100,12 +162,50 @@ pub async fn find_unoccupied_rooms_controller(
     .await
 }
 
+#[utoipa::path(
+    responses(
+        (status = 200, description = "Successfully calculated quote", body = QuoteBookingOutput),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Invalid credentials", body = ErrorResponse),
+        (status = 404, description = "Room not found", body = ErrorResponse),
+    ),
+    params(
+        ("roomId" = String, Query, description = "Room id"),
+        ("startDate" = Date, Query, description = "Arrival date", example = "2025-01-01"),
+        ("endDate" = Date, Query, description = "Departure date", example = "2025-01-05"),
+        ("mainGuest" = String, Query, description = "Main guest id"),
+        ("otherGuests" = Option<String>, Query, description = "Comma separated ids of the other guests", nullable),
+        ("guestUserId" = Option<String>, Query, description = "User account of the guest", nullable),
+        ("cancellationPolicyId" = Option<String>, Query, description = "Cancellation policy overriding the one of the room", nullable),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[get("/booking/quote")]
+pub async fn quote_booking_controller(
+    req: HttpRequest,
+    state: Data<AppState>,
+    input: Query<QuoteBookingInput>,
+) -> impl Responder {
+    process_request_secured(
+        req,
+        &[Role::Admin],
+        &state,
+        input.into_inner(),
+        quote_booking_service,
+        StatusCode::OK,
+    )
+    .await
+}
+
 #[utoipa::path(
     responses(
         (status = 201, description = "Successfully booked room", body = BookRoomOutput),
         (status = 400, description = "Invalid input", body = ErrorResponse),
         (status = 401, description = "Invalid credentials", body = ErrorResponse),
         (status = 403, description = "Invalid credentials", body = ErrorResponse),
+        (status = 404, description = "Room, guest or user not found", body = ErrorResponse),
+        (status = 409, description = "Room is occupied for period", body = ErrorResponse),
     ),
     request_body(
         content = BookRoomInput,
@@ -122,7 +222,7 @@ pub async fn book_room_controller(
 ) -> impl Responder {
     process_request_secured(
         req,
-        &[Role::Admin],
+        &[Role::User, Role::Admin],
         &state,
         input.into_inner(),
         book_room_service,
@@ -131,14 +231,53 @@ pub async fn book_room_controller(
     .await
 }
 
+#[utoipa::path(
+    responses(
+        (status = 200, description = "Successfully confirmed booking", body = ConfirmBookingOutput),
+        (status = 400, description = "Booking is not pending", body = ErrorResponse),
+        (status = 401, description = "Invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Invalid credentials", body = ErrorResponse),
+        (status = 404, description = "Booking not found", body = ErrorResponse),
+    ),
+    params(
+        ("bookingId" = String, Path, description = "Booking id")
+    ),
+    security(("bearer_auth" = []))
+)]
+#[put("/booking/confirm/{bookingId}")]
+pub async fn confirm_booking_controller(
+    req: HttpRequest,
+    state: Data<AppState>,
+    input: Path<Uuid>,
+) -> impl Responder {
+    process_request_secured(
+        req,
+        &[Role::Admin],
+        &state,
+        ConfirmBookingInput {
+            booking_id: input.into_inner(),
+            confirmed_by: None,
+        },
+        confirm_booking_service,
+        StatusCode::OK,
+    )
+    .await
+}
+
 #[utoipa::path(
     responses(
         (status = 200, description = "Successfully payed room", body = PayBookingOutput),
         (status = 400, description = "Invalid input", body = ErrorResponse),
         (status = 401, description = "Invalid credentials", body = ErrorResponse),
+        (status = 402, description = "Card payment declined", body = ErrorResponse),
         (status = 403, description = "Invalid credentials", body = ErrorResponse),
         (status = 404, description = "Booking not found", body = ErrorResponse),
     ),
+    request_body(
+        content = Option<PayBookingInput>,
+        description = "Payment details, pays the remaining amount in cash if omitted",
+        content_type = "application/json"
+    ),
     params(
         ("bookingId" = String, Path, description = "Booking id")
     ),
@@ -148,14 +287,16 @@ pub async fn book_room_controller(
 pub async fn pay_booking_controller(
     req: HttpRequest,
     state: Data<AppState>,
-    input: Path<Uuid>,
+    path: Path<Uuid>,
+    input: Option<Json<PayBookingInput>>,
 ) -> impl Responder {
     process_request_secured(
         req,
         &[Role::Admin],
         &state,
         PayBookingInput {
-            booking_id: input.into_inner(),
+            booking_id: path.into_inner(),
+            ..input.map(|input| input.into_inner()).unwrap_or_default()
         },
         pay_booking_service,
         StatusCode::OK,
@@ -163,6 +304,185 @@ pub async fn pay_booking_controller(
     .await
 }
 
+#[utoipa::path(
+    responses(
+        (status = 200, description = "Successfully modified booking", body = ModifyBookingOutput),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Invalid credentials", body = ErrorResponse),
+        (status = 404, description = "Booking or room not found", body = ErrorResponse),
+        (status = 409, description = "Room is occupied for period", body = ErrorResponse),
+    ),
+    request_body(
+        content = ModifyBookingInput,
+        description = "New booking dates and optional new room",
+        content_type = "application/json"
+    ),
+    params(
+        ("bookingId" = String, Path, description = "Booking id")
+    ),
+    security(("bearer_auth" = []))
+)]
+#[put("/booking/modify/{bookingId}")]
+pub async fn modify_booking_controller(
+    req: HttpRequest,
+    state: Data<AppState>,
+    path: Path<Uuid>,
+    input: Json<ModifyBookingInput>,
+) -> impl Responder {
+    let input = ModifyBookingInput {
+        booking_id: Some(path.into_inner()),
+        ..input.into_inner()
+    };
+
+    process_request_secured(
+        req,
+        &[Role::Admin],
+        &state,
+        input,
+        modify_booking_service,
+        StatusCode::OK,
+    )
+    .await
+}
+
+#[utoipa::path(
+    responses(
+        (status = 200, description = "Successfully updated booking guests", body = UpdateBookingGuestsOutput),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Invalid credentials", body = ErrorResponse),
+        (status = 404, description = "Booking or guest not found", body = ErrorResponse),
+    ),
+    request_body(
+        content = UpdateBookingGuestsInput,
+        description = "Guests to add and remove and optional new main guest",
+        content_type = "application/json"
+    ),
+    params(
+        ("bookingId" = String, Path, description = "Booking id")
+    ),
+    security(("bearer_auth" = []))
+)]
+#[put("/booking/guests/{bookingId}")]
+pub async fn update_booking_guests_controller(
+    req: HttpRequest,
+    state: Data<AppState>,
+    path: Path<Uuid>,
+    input: Json<UpdateBookingGuestsInput>,
+) -> impl Responder {
+    let input = UpdateBookingGuestsInput {
+        booking_id: Some(path.into_inner()),
+        ..input.into_inner()
+    };
+
+    process_request_secured(
+        req,
+        &[Role::Admin],
+        &state,
+        input,
+        update_booking_guests_service,
+        StatusCode::OK,
+    )
+    .await
+}
+
+#[utoipa::path(
+    responses(
+        (status = 200, description = "Successfully checked in", body = CheckInOutput),
+        (status = 400, description = "Invalid booking status or date", body = ErrorResponse),
+        (status = 401, description = "Invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Invalid credentials", body = ErrorResponse),
+        (status = 404, description = "Booking not found", body = ErrorResponse),
+    ),
+    params(
+        ("bookingId" = String, Path, description = "Booking id")
+    ),
+    security(("bearer_auth" = []))
+)]
+#[put("/booking/check-in/{bookingId}")]
+pub async fn check_in_controller(
+    req: HttpRequest,
+    state: Data<AppState>,
+    input: Path<Uuid>,
+) -> impl Responder {
+    process_request_secured(
+        req,
+        &[Role::Admin],
+        &state,
+        CheckInInput {
+            booking_id: input.into_inner(),
+        },
+        check_in_service,
+        StatusCode::OK,
+    )
+    .await
+}
+
+#[utoipa::path(
+    responses(
+        (status = 200, description = "Successfully checked out", body = CheckOutOutput),
+        (status = 400, description = "Invalid booking status or date", body = ErrorResponse),
+        (status = 401, description = "Invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Invalid credentials", body = ErrorResponse),
+        (status = 404, description = "Booking not found", body = ErrorResponse),
+    ),
+    params(
+        ("bookingId" = String, Path, description = "Booking id")
+    ),
+    security(("bearer_auth" = []))
+)]
+#[put("/booking/check-out/{bookingId}")]
+pub async fn check_out_controller(
+    req: HttpRequest,
+    state: Data<AppState>,
+    input: Path<Uuid>,
+) -> impl Responder {
+    process_request_secured(
+        req,
+        &[Role::Admin],
+        &state,
+        CheckOutInput {
+            booking_id: input.into_inner(),
+        },
+        check_out_service,
+        StatusCode::OK,
+    )
+    .await
+}
+
+#[utoipa::path(
+    responses(
+        (status = 200, description = "Successfully marked as no-show", body = MarkNoShowOutput),
+        (status = 400, description = "Invalid booking status or date", body = ErrorResponse),
+        (status = 401, description = "Invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Invalid credentials", body = ErrorResponse),
+        (status = 404, description = "Booking not found", body = ErrorResponse),
+    ),
+    params(
+        ("bookingId" = String, Path, description = "Booking id")
+    ),
+    security(("bearer_auth" = []))
+)]
+#[put("/booking/no-show/{bookingId}")]
+pub async fn mark_no_show_controller(
+    req: HttpRequest,
+    state: Data<AppState>,
+    input: Path<Uuid>,
+) -> impl Responder {
+    process_request_secured(
+        req,
+        &[Role::Admin],
+        &state,
+        MarkNoShowInput {
+            booking_id: input.into_inner(),
+        },
+        mark_no_show_service,
+        StatusCode::OK,
+    )
+    .await
+}
+
 #[utoipa::path(
     responses(
         (status = 200, description = "Successfully canceled booking", body = CancelBookingOutput),
@@ -184,10 +504,12 @@ pub async fn cancel_booking_controller(
 ) -> impl Responder {
     process_request_secured(
         req,
-        &[Role::Admin],
+        &[Role::User, Role::Admin],
         &state,
         CancelBookingInput {
             booking_id: input.into_inner(),
+            canceled_by: None,
+            owner_user_id: None,
         },
         cancel_booking_service,
         StatusCode::OK,
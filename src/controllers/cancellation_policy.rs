@@ -0,0 +1,98 @@
+use actix_web::{
+    get,
+    http::StatusCode,
+    post,
+    web::{Data, Json, ServiceConfig},
+    HttpRequest, Responder,
+};
+use utoipa::OpenApi;
+
+use crate::{
+    api::{
+        cancellation_policy::{
+            add_cancellation_policy::{AddCancellationPolicyInput, AddCancellationPolicyOutput},
+            get_cancellation_policies::{
+                GetCancellationPoliciesInput, GetCancellationPoliciesOutput,
+            },
+            CancellationPolicy,
+        },
+        error_response::ErrorResponse,
+    },
+    app_state::AppState,
+    persistence::{cancellation_policy::PenaltyType, user::Role},
+    services::cancellation_policy::{
+        add_cancellation_policy::add_cancellation_policy_service,
+        get_cancellation_policies::get_cancellation_policies_service,
+    },
+    util::{process_request, process_request_secured},
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        add_cancellation_policy_controller,
+        get_cancellation_policies_controller
+    ),
+    components(schemas(
+        ErrorResponse,
+        AddCancellationPolicyInput,
+        AddCancellationPolicyOutput,
+        GetCancellationPoliciesInput,
+        GetCancellationPoliciesOutput,
+        CancellationPolicy,
+        PenaltyType
+    ))
+)]
+pub struct CancellationPolicyApiDoc;
+
+pub fn config(cfg: &mut ServiceConfig) {
+    cfg.service(add_cancellation_policy_controller);
+    cfg.service(get_cancellation_policies_controller);
+}
+
+#[utoipa::path(
+    responses(
+        (status = 201, description = "Successfully added cancellation policy", body = AddCancellationPolicyOutput),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Invalid credentials", body = ErrorResponse),
+    ),
+    request_body(
+        content = AddCancellationPolicyInput,
+        description = "Cancellation policy data",
+        content_type = "application/json"
+    ),
+    security(("bearer_auth" = []))
+)]
+#[post("/cancellation-policy")]
+pub async fn add_cancellation_policy_controller(
+    req: HttpRequest,
+    state: Data<AppState>,
+    input: Json<AddCancellationPolicyInput>,
+) -> impl Responder {
+    process_request_secured(
+        req,
+        &[Role::Admin],
+        &state,
+        input.into_inner(),
+        add_cancellation_policy_service,
+        StatusCode::CREATED,
+    )
+    .await
+}
+
+#[utoipa::path(
+    responses(
+        (status = 200, description = "Successfully fetched cancellation policies", body = GetCancellationPoliciesOutput),
+    ),
+)]
+#[get("/cancellation-policy")]
+pub async fn get_cancellation_policies_controller(state: Data<AppState>) -> impl Responder {
+    process_request(
+        &state,
+        GetCancellationPoliciesInput,
+        get_cancellation_policies_service,
+        StatusCode::OK,
+    )
+    .await
+}
@@ -0,0 +1,109 @@
+use actix_web::{
+    get,
+    http::StatusCode,
+    put,
+    web::{Data, Path, Query, ServiceConfig},
+    HttpRequest, Responder,
+};
+use utoipa::OpenApi;
+use uuid::Uuid;
+
+use crate::{
+    api::{
+        email_outbox::{
+            get_outbox_emails::{GetOutboxEmailsInput, GetOutboxEmailsOutput},
+            retry_email::{RetryEmailInput, RetryEmailOutput},
+            OutboxEmail,
+        },
+        error_response::ErrorResponse,
+    },
+    app_state::AppState,
+    persistence::{email_outbox::EmailStatus, user::Role},
+    services::email_outbox::{
+        get_outbox_emails::get_outbox_emails_service, retry_email::retry_email_service,
+    },
+    util::process_request_secured,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(get_outbox_emails_controller, retry_email_controller),
+    components(schemas(
+        ErrorResponse,
+        GetOutboxEmailsInput,
+        GetOutboxEmailsOutput,
+        OutboxEmail,
+        EmailStatus,
+        RetryEmailInput,
+        RetryEmailOutput
+    ))
+)]
+pub struct EmailOutboxApiDoc;
+
+pub fn config(cfg: &mut ServiceConfig) {
+    cfg.service(get_outbox_emails_controller);
+    cfg.service(retry_email_controller);
+}
+
+#[utoipa::path(
+    responses(
+        (status = 200, description = "Successfully fetched emails", body = GetOutboxEmailsOutput),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Invalid credentials", body = ErrorResponse),
+    ),
+    params(
+        ("status" = Option<EmailStatus>, Query, description = "Email status", example = "Failed", nullable),
+        ("page" = u64, Query, description = "Page index", example = "0"),
+        ("size" = u64, Query, description = "Number of emails to retrieve", example = "20"),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[get("/email-outbox")]
+pub async fn get_outbox_emails_controller(
+    req: HttpRequest,
+    state: Data<AppState>,
+    input: Query<GetOutboxEmailsInput>,
+) -> impl Responder {
+    process_request_secured(
+        req,
+        &[Role::Admin],
+        &state,
+        input.into_inner(),
+        get_outbox_emails_service,
+        StatusCode::OK,
+    )
+    .await
+}
+
+#[utoipa::path(
+    responses(
+        (status = 200, description = "Successfully scheduled email for sending", body = RetryEmailOutput),
+        (status = 400, description = "Email has not failed", body = ErrorResponse),
+        (status = 401, description = "Invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Invalid credentials", body = ErrorResponse),
+        (status = 404, description = "Email not found", body = ErrorResponse),
+    ),
+    params(
+        ("emailId" = String, Path, description = "Email id")
+    ),
+    security(("bearer_auth" = []))
+)]
+#[put("/email-outbox/retry/{emailId}")]
+pub async fn retry_email_controller(
+    req: HttpRequest,
+    state: Data<AppState>,
+    input: Path<Uuid>,
+) -> impl Responder {
+    process_request_secured(
+        req,
+        &[Role::Admin],
+        &state,
+        RetryEmailInput {
+            email_id: input.into_inner(),
+        },
+        retry_email_service,
+        StatusCode::OK,
+    )
+    .await
+}
@@ -80,7 +80,7 @@ pub async fn add_guest_controller(
 ) -> impl Responder {
     process_request_secured(
         req,
-        &[Role::Admin],
+        &[Role::User, Role::Admin],
         &state,
         input.into_inner(),
         add_guest_service,
@@ -113,7 +113,7 @@ pub async fn find_guest_controller(
 ) -> impl Responder {
     process_request_secured(
         req,
-        &[Role::Admin],
+        &[Role::User, Role::Admin],
         &state,
         input.into_inner(),
         find_guest_service,
@@ -143,9 +143,10 @@ pub async fn get_guest_controller(
 ) -> impl Responder {
     process_request_secured(
         req,
-        &[Role::Admin],
+        &[Role::User, Role::Admin],
         &state,
         GetGuestInput {
+            owner_user_id: None,
             guest_id: path.into_inner(),
         },
         get_guest_service,
@@ -181,7 +182,7 @@ pub async fn update_guest_controller(
 ) -> impl Responder {
     process_request_secured(
         req,
-        &[Role::Admin],
+        &[Role::User, Role::Admin],
         &state,
         UpdateGuestInput {
             id: Some(path.into_inner()),
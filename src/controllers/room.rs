@@ -1,8 +1,8 @@
 use actix_web::{
     delete, get,
     http::StatusCode,
-    post,
-    web::{Data, Json, Path, ServiceConfig},
+    post, put,
+    web::{Data, Json, Path, Query, ServiceConfig},
     HttpRequest, Responder,
 };
 use utoipa::OpenApi;
@@ -12,23 +12,45 @@ use crate::{
     api::{
         error_response::ErrorResponse,
         room::{
+            add_price_rule::{AddPriceRuleInput, AddPriceRuleOutput},
             add_room::{AddRoomInput, AddRoomOutput},
+            delete_price_rule::{DeletePriceRuleInput, DeletePriceRuleOutput},
             delete_room::{DeleteRoomInput, DeleteRoomOutput},
+            get_price_rules::{GetPriceRulesInput, GetPriceRulesOutput, PriceRule},
             get_room::{GetRoomInput, GetRoomOutput},
-            Bed,
+            get_room_quote::{GetRoomQuoteInput, GetRoomQuoteOutput},
+            get_rooms::{GetRoomsInput, GetRoomsOutput},
+            restore_room::{RestoreRoomInput, RestoreRoomOutput},
+            update_room::{UpdateRoomInput, UpdateRoomOutput},
+            Bed, DayOfWeek, NightPrice,
         },
     },
     app_state::AppState,
-    persistence::{bed::BedSize, room::BathroomType, user::Role},
+    persistence::{bed::BedSize, room::BathroomType, room_price_rule::PriceRuleType, user::Role},
     services::room::{
-        add_room::add_room_service, delete_room::delete_room_service, get_room::get_room_service,
+        add_price_rule::add_price_rule_service, add_room::add_room_service,
+        delete_price_rule::delete_price_rule_service, delete_room::delete_room_service,
+        get_price_rules::get_price_rules_service, get_room::get_room_service,
+        get_room_quote::get_room_quote_service, get_rooms::get_rooms_service,
+        restore_room::restore_room_service, update_room::update_room_service,
     },
     util::process_request_secured,
 };
 
 #[derive(OpenApi)]
 #[openapi(
-    paths(add_room_controller, get_room_controller, delete_room_controller),
+    paths(
+        add_room_controller,
+        get_room_controller,
+        get_rooms_controller,
+        delete_room_controller,
+        restore_room_controller,
+        update_room_controller,
+        add_price_rule_controller,
+        get_price_rules_controller,
+        delete_price_rule_controller,
+        get_room_quote_controller
+    ),
     components(schemas(
         ErrorResponse,
         Bed,
@@ -36,8 +58,26 @@ use crate::{
         AddRoomOutput,
         GetRoomInput,
         GetRoomOutput,
+        GetRoomsInput,
+        GetRoomsOutput,
         DeleteRoomInput,
         DeleteRoomOutput,
+        RestoreRoomInput,
+        RestoreRoomOutput,
+        UpdateRoomInput,
+        UpdateRoomOutput,
+        AddPriceRuleInput,
+        AddPriceRuleOutput,
+        GetPriceRulesInput,
+        GetPriceRulesOutput,
+        PriceRule,
+        DeletePriceRuleInput,
+        DeletePriceRuleOutput,
+        GetRoomQuoteInput,
+        GetRoomQuoteOutput,
+        NightPrice,
+        DayOfWeek,
+        PriceRuleType,
         BathroomType,
         BedSize
     ))
@@ -47,7 +87,14 @@ pub struct RoomApiDoc;
 pub fn config(cfg: &mut ServiceConfig) {
     cfg.service(add_room_controller);
     cfg.service(get_room_controller);
+    cfg.service(get_rooms_controller);
     cfg.service(delete_room_controller);
+    cfg.service(restore_room_controller);
+    cfg.service(update_room_controller);
+    cfg.service(add_price_rule_controller);
+    cfg.service(get_price_rules_controller);
+    cfg.service(delete_price_rule_controller);
+    cfg.service(get_room_quote_controller);
 }
 
 #[utoipa::path(
@@ -114,6 +161,43 @@ pub async fn get_room_controller(
     .await
 }
 
+#[utoipa::path(
+    responses(
+        (status = 200, description = "Successfully fetched rooms", body = GetRoomsOutput),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Only admins can include deleted rooms", body = ErrorResponse),
+    ),
+    params(
+        ("page" = u64, Query, description = "Page index", example = "0"),
+        ("size" = u64, Query, description = "Number of rooms to retrieve", example = "10"),
+        ("floor" = Option<i16>, Query, description = "Floor", example = "2", nullable),
+        ("bathroomType" = Option<BathroomType>, Query, description = "Bathroom type", example = "Private", nullable),
+        ("bedSizes" = Option<String>, Query, description = "Comma separated bed sizes the room needs to have at least one of each", example = "KingSize", nullable),
+        ("minimumPrice" = Option<i64>, Query, description = "Minimum price", example = "1000", nullable),
+        ("maximumPrice" = Option<i64>, Query, description = "Maximum price", example = "10000", nullable),
+        ("roomNumberPrefix" = Option<String>, Query, description = "Start of the room number", example = "1", nullable),
+        ("includeDeleted" = Option<bool>, Query, description = "Include deleted rooms (admin only)", example = "false", nullable),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[get("/room")]
+pub async fn get_rooms_controller(
+    req: HttpRequest,
+    state: Data<AppState>,
+    input: Query<GetRoomsInput>,
+) -> impl Responder {
+    process_request_secured(
+        req,
+        &[Role::User, Role::Admin],
+        &state,
+        input.into_inner(),
+        get_rooms_service,
+        StatusCode::OK,
+    )
+    .await
+}
+
 #[utoipa::path(
     responses(
         (status = 200, description = "Successfully deleted room", body = DeleteRoomOutput),
@@ -121,9 +205,11 @@ pub async fn get_room_controller(
         (status = 401, description = "Invalid credentials", body = ErrorResponse),
         (status = 403, description = "Invalid credentials", body = ErrorResponse),
         (status = 404, description = "Room not found", body = ErrorResponse),
+        (status = 409, description = "Room has active bookings or checked in guests", body = ErrorResponse),
     ),
     params(
-        ("roomId" = String, Path, description = "Room id")
+        ("roomId" = String, Path, description = "Room id"),
+        ("force" = Option<bool>, Query, description = "Cancel active bookings for the room, except checked in ones", example = "false", nullable),
     ),
     security(("bearer_auth" = []))
 )]
@@ -132,9 +218,11 @@ pub async fn delete_room_controller(
     req: HttpRequest,
     state: Data<AppState>,
     path: Path<Uuid>,
+    query: Query<DeleteRoomInput>,
 ) -> impl Responder {
     let input = DeleteRoomInput {
         room_id: path.into_inner(),
+        ..query.into_inner()
     };
 
     process_request_secured(
@@ -147,3 +235,217 @@ pub async fn delete_room_controller(
     )
     .await
 }
+
+#[utoipa::path(
+    responses(
+        (status = 200, description = "Successfully restored room", body = RestoreRoomOutput),
+        (status = 400, description = "Room is not deleted", body = ErrorResponse),
+        (status = 401, description = "Invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Invalid credentials", body = ErrorResponse),
+        (status = 404, description = "Room not found", body = ErrorResponse),
+        (status = 409, description = "Room number is used by another room", body = ErrorResponse),
+    ),
+    params(
+        ("roomId" = String, Path, description = "Room id")
+    ),
+    security(("bearer_auth" = []))
+)]
+#[put("/room/{roomId}/restore")]
+pub async fn restore_room_controller(
+    req: HttpRequest,
+    state: Data<AppState>,
+    path: Path<Uuid>,
+) -> impl Responder {
+    let input = RestoreRoomInput {
+        room_id: path.into_inner(),
+    };
+
+    process_request_secured(
+        req,
+        &[Role::Admin],
+        &state,
+        input,
+        restore_room_service,
+        StatusCode::OK,
+    )
+    .await
+}
+
+#[utoipa::path(
+    responses(
+        (status = 200, description = "Successfully updated room", body = UpdateRoomOutput),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Invalid credentials", body = ErrorResponse),
+        (status = 404, description = "Room not found", body = ErrorResponse),
+        (status = 409, description = "Room number in use or capacity too small for existing bookings", body = ErrorResponse),
+    ),
+    request_body(
+        content = UpdateRoomInput,
+        description = "Room data",
+        content_type = "application/json"
+    ),
+    params(
+        ("roomId" = String, Path, description = "Room id")
+    ),
+    security(("bearer_auth" = []))
+)]
+#[put("/room/{roomId}")]
+pub async fn update_room_controller(
+    req: HttpRequest,
+    state: Data<AppState>,
+    input: Json<UpdateRoomInput>,
+    path: Path<Uuid>,
+) -> impl Responder {
+    process_request_secured(
+        req,
+        &[Role::Admin],
+        &state,
+        UpdateRoomInput {
+            room_id: Some(path.into_inner()),
+            ..input.into_inner()
+        },
+        update_room_service,
+        StatusCode::OK,
+    )
+    .await
+}
+
+#[utoipa::path(
+    responses(
+        (status = 201, description = "Successfully added price rule", body = AddPriceRuleOutput),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Invalid credentials", body = ErrorResponse),
+        (status = 404, description = "Room not found", body = ErrorResponse),
+    ),
+    request_body(
+        content = AddPriceRuleInput,
+        description = "Price rule data",
+        content_type = "application/json"
+    ),
+    params(
+        ("roomId" = String, Path, description = "Room id")
+    ),
+    security(("bearer_auth" = []))
+)]
+#[post("/room/{roomId}/price-rule")]
+pub async fn add_price_rule_controller(
+    req: HttpRequest,
+    state: Data<AppState>,
+    input: Json<AddPriceRuleInput>,
+    path: Path<Uuid>,
+) -> impl Responder {
+    process_request_secured(
+        req,
+        &[Role::Admin],
+        &state,
+        AddPriceRuleInput {
+            room_id: Some(path.into_inner()),
+            ..input.into_inner()
+        },
+        add_price_rule_service,
+        StatusCode::CREATED,
+    )
+    .await
+}
+
+#[utoipa::path(
+    responses(
+        (status = 200, description = "Successfully fetched price rules", body = GetPriceRulesOutput),
+        (status = 401, description = "Invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Invalid credentials", body = ErrorResponse),
+    ),
+    params(
+        ("roomId" = String, Path, description = "Room id")
+    ),
+    security(("bearer_auth" = []))
+)]
+#[get("/room/{roomId}/price-rule")]
+pub async fn get_price_rules_controller(
+    req: HttpRequest,
+    state: Data<AppState>,
+    path: Path<Uuid>,
+) -> impl Responder {
+    let input = GetPriceRulesInput {
+        room_id: path.into_inner(),
+    };
+
+    process_request_secured(
+        req,
+        &[Role::Admin],
+        &state,
+        input,
+        get_price_rules_service,
+        StatusCode::OK,
+    )
+    .await
+}
+
+#[utoipa::path(
+    responses(
+        (status = 200, description = "Successfully deleted price rule", body = DeletePriceRuleOutput),
+        (status = 401, description = "Invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Invalid credentials", body = ErrorResponse),
+        (status = 404, description = "Price rule not found", body = ErrorResponse),
+    ),
+    params(
+        ("priceRuleId" = String, Path, description = "Price rule id")
+    ),
+    security(("bearer_auth" = []))
+)]
+#[delete("/room/price-rule/{priceRuleId}")]
+pub async fn delete_price_rule_controller(
+    req: HttpRequest,
+    state: Data<AppState>,
+    path: Path<Uuid>,
+) -> impl Responder {
+    let input = DeletePriceRuleInput {
+        price_rule_id: path.into_inner(),
+    };
+
+    process_request_secured(
+        req,
+        &[Role::Admin],
+        &state,
+        input,
+        delete_price_rule_service,
+        StatusCode::OK,
+    )
+    .await
+}
+
+#[utoipa::path(
+    responses(
+        (status = 200, description = "Successfully calculated price", body = GetRoomQuoteOutput),
+        (status = 400, description = "Invalid input", body = ErrorResponse),
+        (status = 401, description = "Invalid credentials", body = ErrorResponse),
+        (status = 404, description = "Room not found", body = ErrorResponse),
+    ),
+    params(
+        ("roomId" = String, Path, description = "Room id"),
+        ("startDate" = String, Query, description = "Start date", example = "2025-01-01"),
+        ("endDate" = String, Query, description = "End date", example = "2025-01-05"),
+    ),
+    security(("bearer_auth" = []))
+)]
+#[get("/room/{roomId}/quote")]
+pub async fn get_room_quote_controller(
+    req: HttpRequest,
+    state: Data<AppState>,
+    input: Query<GetRoomQuoteInput>,
+    path: Path<Uuid>,
+) -> impl Responder {
+    process_request_secured(
+        req,
+        &[Role::User, Role::Admin],
+        &state,
+        GetRoomQuoteInput {
+            room_id: Some(path.into_inner()),
+            ..input.into_inner()
+        },
+        get_room_quote_service,
+        StatusCode::OK,
+    )
+    .await
+}
@@ -1,9 +1,27 @@
 use std::time::Duration;
 
-use actix_web::rt::time::interval;
+use actix_web::{http::StatusCode, rt::time::interval};
 use log::{error, info};
+use sea_orm::{
+    sqlx::types::chrono::Utc, ActiveModelTrait, ActiveValue, IntoActiveModel, TransactionTrait,
+};
 
-use crate::{app_state::AppState, persistence::invalidated_token};
+use crate::{
+    api::error_response::ErrorResponse,
+    app_state::AppState,
+    constants::EMAIL_SEND_LEASE_SECS,
+    persistence::{
+        booking,
+        email_outbox::{self, EmailStatus},
+        invalidated_token,
+    },
+    services::{
+        booking_email::{queue_booking_email, BookingEmail},
+        email_outbox::{parse_params, retry_delay_secs, CLEARED_PARAMS},
+        email_template::EmailTemplateName,
+    },
+    util::{add_days, require_some},
+};
 
 pub struct InvalidatedJwtRemover {
     app_state: AppState,
@@ -35,7 +53,297 @@ impl InvalidatedJwtRemover {
     }
 }
 
+pub struct UnpaidBookingExpirer {
+    app_state: AppState,
+}
+impl UnpaidBookingExpirer {
+    async fn find_expired_bookings(
+        app_state: &AppState,
+    ) -> Result<Vec<booking::Model>, ErrorResponse> {
+        let expiry_info = &app_state.booking_expiry_info;
+        let now = Utc::now();
+        let booked_before = expiry_info
+            .hours_after_booking
+            .map(|hours| (now - Duration::from_secs(hours * 3600)).naive_utc());
+        let starting_until = expiry_info
+            .days_before_start
+            .map(|days| add_days(now.date_naive(), days))
+            .transpose()?;
+
+        Ok(booking::find_expired_unpaid_bookings(
+            app_state.db.as_ref(),
+            booked_before,
+            starting_until,
+        )
+        .await?)
+    }
+
+    async fn expire_booking(
+        app_state: &AppState,
+        booking: &booking::Model,
+    ) -> Result<bool, ErrorResponse> {
+        let transaction = app_state.db.begin().await?;
+        let is_canceled = booking::cancel_unpaid_booking(&transaction, booking.id).await?;
+        if !is_canceled {
+            return Ok(false);
+        }
+        queue_booking_email(&transaction, booking.id, BookingEmail::Expired).await?;
+        transaction.commit().await?;
+
+        info!("Canceled expired unpaid booking '{}'", booking.id);
+        Ok(true)
+    }
+
+    async fn expire_unpaid_bookings(app_state: AppState) {
+        let bookings = match Self::find_expired_bookings(&app_state).await {
+            Ok(bookings) => bookings,
+            Err(err) => {
+                error!("Error finding expired unpaid bookings: {err}");
+                return;
+            }
+        };
+
+        let mut expired = 0;
+        for booking in &bookings {
+            match Self::expire_booking(&app_state, booking).await {
+                Ok(true) => expired += 1,
+                Ok(false) => {}
+                Err(err) => error!("Error canceling expired booking '{}': {err}", booking.id),
+            }
+        }
+
+        info!("Canceled {expired} expired unpaid bookings");
+    }
+
+    fn start(app_state: AppState) {
+        if !app_state.booking_expiry_info.is_enabled() {
+            info!("Unpaid booking expiry is disabled");
+            return;
+        }
+
+        actix_web::rt::spawn(async move {
+            let expirer = Self { app_state };
+            let mut interval = interval(Duration::from_secs(
+                expirer.app_state.booking_expiry_info.check_interval,
+            ));
+            loop {
+                interval.tick().await;
+                Self::expire_unpaid_bookings(expirer.app_state.clone()).await;
+            }
+        });
+    }
+}
+
+pub struct BookingReminderSender {
+    app_state: AppState,
+}
+impl BookingReminderSender {
+    async fn send_reminder(
+        app_state: &AppState,
+        booking: &booking::Model,
+    ) -> Result<bool, ErrorResponse> {
+        let transaction = app_state.db.begin().await?;
+        let is_marked = booking::mark_reminder_sent(&transaction, booking.id).await?;
+        if !is_marked {
+            return Ok(false);
+        }
+        queue_booking_email(&transaction, booking.id, BookingEmail::Reminder).await?;
+        transaction.commit().await?;
+
+        Ok(true)
+    }
+
+    async fn send_reminders(app_state: AppState, days_before_start: u64) {
+        let starting_until = match add_days(Utc::now().date_naive(), days_before_start) {
+            Ok(date) => date,
+            Err(err) => {
+                error!("Error calculating the reminder date: {err}");
+                return;
+            }
+        };
+        let bookings =
+            match booking::find_bookings_to_remind(app_state.db.as_ref(), starting_until).await {
+                Ok(bookings) => bookings,
+                Err(err) => {
+                    error!("Error finding bookings to remind: {err}");
+                    return;
+                }
+            };
+
+        let mut reminded = 0;
+        for booking in &bookings {
+            match Self::send_reminder(&app_state, booking).await {
+                Ok(true) => reminded += 1,
+                Ok(false) => {}
+                Err(err) => error!("Error sending reminder for booking '{}': {err}", booking.id),
+            }
+        }
+
+        info!("Sent {reminded} booking reminders");
+    }
+
+    fn start(app_state: AppState) {
+        let Some(days_before_start) = app_state.booking_reminder_info.days_before_start else {
+            info!("Booking reminders are disabled");
+            return;
+        };
+
+        actix_web::rt::spawn(async move {
+            let sender = Self { app_state };
+            let mut interval = interval(Duration::from_secs(
+                sender.app_state.booking_reminder_info.check_interval,
+            ));
+            loop {
+                interval.tick().await;
+                Self::send_reminders(sender.app_state.clone(), days_before_start).await;
+            }
+        });
+    }
+}
+
+pub struct EmailOutboxSender {
+    app_state: AppState,
+}
+impl EmailOutboxSender {
+    async fn try_send(
+        app_state: &AppState,
+        email: &email_outbox::Model,
+    ) -> Result<(), ErrorResponse> {
+        let template = require_some(
+            EmailTemplateName::from_dir_name(&email.template),
+            || format!("Unknown email template '{}'", email.template),
+            StatusCode::INTERNAL_SERVER_ERROR,
+        )?;
+        let params = parse_params(&email.params);
+        let params: Vec<_> = params
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.clone()))
+            .collect();
+
+        app_state
+            .email_service
+            .send_mail(
+                email.recipient.clone(),
+                email.language.as_deref(),
+                template,
+                &params,
+            )
+            .await
+    }
+
+    async fn send_email(
+        app_state: &AppState,
+        email: email_outbox::Model,
+    ) -> Result<bool, ErrorResponse> {
+        let outbox_info = &app_state.email_outbox_info;
+        let now = Utc::now().naive_utc();
+        let lease_until = now + Duration::from_secs(EMAIL_SEND_LEASE_SECS);
+        if !email_outbox::claim_email(app_state.db.as_ref(), &email, lease_until).await? {
+            return Ok(false);
+        }
+
+        if email
+            .expiry_time
+            .is_some_and(|expiry_time| expiry_time <= now)
+        {
+            email_outbox::ActiveModel {
+                status: ActiveValue::Set(EmailStatus::Failed),
+                params: ActiveValue::Set(CLEARED_PARAMS.to_owned()),
+                last_error: ActiveValue::Set(Some("Expired before it was sent".to_owned())),
+                ..email.into_active_model()
+            }
+            .update(app_state.db.as_ref())
+            .await?;
+            return Ok(false);
+        }
+
+        let result = Self::try_send(app_state, &email).await;
+        let attempts = email.attempts + 1;
+        let expiry_time = email.expiry_time;
+        let mut update = email_outbox::ActiveModel {
+            attempts: ActiveValue::Set(attempts),
+            ..email.into_active_model()
+        };
+        let is_sent = match result {
+            Ok(_) => {
+                update.status = ActiveValue::Set(EmailStatus::Sent);
+                update.sent_time = ActiveValue::Set(Some(Utc::now().naive_utc()));
+                update.params = ActiveValue::Set(CLEARED_PARAMS.to_owned());
+                update.last_error = ActiveValue::Set(None);
+                true
+            }
+            Err(err) => {
+                let delay = retry_delay_secs(outbox_info.retry_base_delay, attempts);
+                let next_attempt_time = now + Duration::from_secs(delay);
+                let is_expired_before_retry =
+                    expiry_time.is_some_and(|expiry_time| expiry_time <= next_attempt_time);
+                if is_expired_before_retry {
+                    update.status = ActiveValue::Set(EmailStatus::Failed);
+                    update.params = ActiveValue::Set(CLEARED_PARAMS.to_owned());
+                } else if attempts >= outbox_info.max_attempts {
+                    update.status = ActiveValue::Set(EmailStatus::Failed);
+                } else {
+                    update.next_attempt_time = ActiveValue::Set(next_attempt_time);
+                }
+                update.last_error = ActiveValue::Set(Some(err.to_string()));
+                false
+            }
+        };
+        update.update(app_state.db.as_ref()).await?;
+
+        Ok(is_sent)
+    }
+
+    pub async fn send_due_emails(app_state: AppState) {
+        let now = Utc::now().naive_utc();
+        let emails = match email_outbox::find_due_emails(
+            app_state.db.as_ref(),
+            now,
+            app_state.email_outbox_info.batch_size,
+        )
+        .await
+        {
+            Ok(emails) => emails,
+            Err(err) => {
+                error!("Error finding emails to send: {err}");
+                return;
+            }
+        };
+        if emails.is_empty() {
+            return;
+        }
+
+        let mut sent = 0;
+        for email in emails {
+            let email_id = email.id;
+            match Self::send_email(&app_state, email).await {
+                Ok(true) => sent += 1,
+                Ok(false) => {}
+                Err(err) => error!("Error sending email '{}': {err}", email_id),
+            }
+        }
+
+        info!("Sent {sent} emails from the outbox");
+    }
+
+    fn start(app_state: AppState) {
+        actix_web::rt::spawn(async move {
+            let sender = Self { app_state };
+            let mut interval = interval(Duration::from_secs(
+                sender.app_state.email_outbox_info.check_interval,
+            ));
+            loop {
+                interval.tick().await;
+                Self::send_due_emails(sender.app_state.clone()).await;
+            }
+        });
+    }
+}
+
 pub fn start_cronjobs(app_state: AppState) {
-    InvalidatedJwtRemover::start(app_state);
+    InvalidatedJwtRemover::start(app_state.clone());
+    UnpaidBookingExpirer::start(app_state.clone());
+    BookingReminderSender::start(app_state.clone());
+    EmailOutboxSender::start(app_state);
     info!("Initialised cronjobs");
 }
@@ -0,0 +1,309 @@
+use std::sync::{Arc, LazyLock};
+
+use actix_http::Request;
+use actix_web::{
+    dev::{Service, ServiceResponse},
+    http::{header, Method, StatusCode},
+    test::{self, TestRequest},
+    App,
+};
+use sea_orm::sqlx::types::chrono::{NaiveDate, Utc};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::{
+    app_state::{AppState, EnvironmentVariables, ServerInfo},
+    configure_app,
+    constants::{
+        BEARER_PREFIX, DEFAULT_EMAIL_SENDER, ENV_INITIAL_ADMIN_EMAIL, ENV_INITIAL_ADMIN_PASSWORD,
+        ENV_JWT_SECRET, ENV_JWT_VALIDITY_SECS, ENV_OTP_VALIDITY_SECS,
+    },
+    cronjobs::EmailOutboxSender,
+    persistence::{connect_test_db, initialise_db},
+    services::{
+        email_service::EmailService,
+        email_template::{EmailTemplates, DEFAULT_LANGUAGE},
+        email_transport::InMemoryEmailTransport,
+    },
+    util::add_days,
+};
+
+mod auth;
+mod booking;
+mod comment;
+mod guest;
+mod room;
+
+pub const ADMIN_PASSWORD: &str = "Admin1234";
+pub const USER_PASSWORD: &str = "User12345";
+
+const TEST_ENV: &[(&str, &str)] = &[
+    (ENV_JWT_SECRET, "e2e-test-secret"),
+    (ENV_JWT_VALIDITY_SECS, "3600"),
+    (ENV_OTP_VALIDITY_SECS, "300"),
+    (ENV_INITIAL_ADMIN_PASSWORD, ADMIN_PASSWORD),
+];
+
+/// Shared by all apps since any app's outbox sender may deliver another's emails on postgres,
+/// the recipients are unique per test
+static EMAILS: LazyLock<Arc<InMemoryEmailTransport>> = LazyLock::new(Default::default);
+
+pub struct TestResponse {
+    pub status: StatusCode,
+    pub body: Value,
+}
+impl TestResponse {
+    /// Panics with the response body if the status is not the expected one
+    pub fn expect(self, status: StatusCode) -> Value {
+        assert_eq!(self.status, status, "unexpected response: {}", self.body);
+        self.body
+    }
+}
+
+/// The app as `main` builds it, with a test database and emails kept in memory
+pub struct TestApp<S> {
+    service: S,
+    pub state: AppState,
+    pub emails: Arc<InMemoryEmailTransport>,
+    /// Every app has its own initial admin, the tests may share a postgres database
+    pub admin_email: String,
+}
+
+pub async fn spawn_app(
+    extra_env: &[(&str, &str)],
+) -> TestApp<impl Service<Request, Response = ServiceResponse, Error = actix_web::Error>> {
+    let admin_email = unique_email();
+    let vars: Vec<_> = TEST_ENV
+        .iter()
+        .chain(extra_env)
+        .copied()
+        .chain([(ENV_INITIAL_ADMIN_EMAIL, admin_email.as_str())])
+        .collect();
+    let env = EnvironmentVariables::from_vars(&vars);
+    let db = connect_test_db().await;
+    initialise_db(&db, &env).await;
+
+    let emails = EMAILS.clone();
+    let email_service = EmailService::with_transport(
+        DEFAULT_EMAIL_SENDER.parse().unwrap(),
+        emails.clone(),
+        EmailTemplates::load(None, DEFAULT_LANGUAGE),
+    );
+    let server_info = ServerInfo::new(&env);
+    let state = AppState::new(&env, db, email_service);
+    let service = test::init_service(
+        App::new().configure(configure_app(state.clone(), server_info.max_body_bytes)),
+    )
+    .await;
+
+    TestApp {
+        service,
+        state,
+        emails,
+        admin_email,
+    }
+}
+
+impl<S> TestApp<S>
+where
+    S: Service<Request, Response = ServiceResponse, Error = actix_web::Error>,
+{
+    pub async fn send(
+        &self,
+        method: Method,
+        uri: &str,
+        token: Option<&str>,
+        body: Option<Value>,
+    ) -> TestResponse {
+        let mut request = TestRequest::default().method(method).uri(uri);
+        if let Some(token) = token {
+            request =
+                request.insert_header((header::AUTHORIZATION, format!("{BEARER_PREFIX}{token}")));
+        }
+        if let Some(body) = body {
+            request = request.set_json(body);
+        }
+
+        let response = test::call_service(&self.service, request.to_request()).await;
+        let status = response.status();
+        let body = test::read_body(response).await;
+        let body = serde_json::from_slice(&body)
+            .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&body).into_owned()));
+
+        TestResponse { status, body }
+    }
+
+    pub async fn get(&self, uri: &str, token: Option<&str>) -> TestResponse {
+        self.send(Method::GET, uri, token, None).await
+    }
+
+    pub async fn post(&self, uri: &str, token: Option<&str>, body: Value) -> TestResponse {
+        self.send(Method::POST, uri, token, Some(body)).await
+    }
+
+    pub async fn put(&self, uri: &str, token: Option<&str>, body: Value) -> TestResponse {
+        self.send(Method::PUT, uri, token, Some(body)).await
+    }
+
+    pub async fn patch(&self, uri: &str, token: Option<&str>, body: Value) -> TestResponse {
+        self.send(Method::PATCH, uri, token, Some(body)).await
+    }
+
+    pub async fn delete(&self, uri: &str, token: Option<&str>) -> TestResponse {
+        self.send(Method::DELETE, uri, token, None).await
+    }
+
+    pub async fn register(&self, email: &str, password: &str) -> Uuid {
+        let body = self
+            .post(
+                "/auth/register",
+                None,
+                json!({ "email": email, "password": password }),
+            )
+            .await
+            .expect(StatusCode::CREATED);
+
+        id(&body["userId"])
+    }
+
+    pub async fn login(&self, email: &str, password: &str) -> String {
+        let body = self
+            .post(
+                "/auth/login",
+                None,
+                json!({ "email": email, "password": password }),
+            )
+            .await
+            .expect(StatusCode::OK);
+
+        body["token"].as_str().unwrap().to_owned()
+    }
+
+    /// Registers a user with a unique email and returns its id, email and JWT
+    pub async fn register_and_login(&self) -> (Uuid, String, String) {
+        let email = unique_email();
+        let user_id = self.register(&email, USER_PASSWORD).await;
+        let token = self.login(&email, USER_PASSWORD).await;
+
+        (user_id, email, token)
+    }
+
+    /// JWT of the initial admin
+    pub async fn admin_token(&self) -> String {
+        self.login(&self.admin_email, ADMIN_PASSWORD).await
+    }
+
+    /// Sends the queued emails like the outbox cronjob does
+    pub async fn deliver_emails(&self) {
+        EmailOutboxSender::send_due_emails(self.state.clone()).await;
+    }
+
+    pub async fn add_room(&self, admin_token: &str, price: i64) -> Uuid {
+        let body = self
+            .post("/room", Some(admin_token), room_json(price))
+            .await
+            .expect(StatusCode::CREATED);
+
+        id(&body["roomId"])
+    }
+
+    /// A guest with an id card and phone number, so it can be the main guest of a booking
+    pub async fn add_guest(&self, token: &str) -> Uuid {
+        let mut guest = guest_json("John", "Smith");
+        guest["idCard"] = id_card_json();
+        guest["phoneNumber"] = unique_phone_number().into();
+        let body = self
+            .post("/guest", Some(token), guest)
+            .await
+            .expect(StatusCode::CREATED);
+
+        id(&body["guestId"])
+    }
+
+    pub async fn book_room(&self, token: &str, room_id: Uuid, guest_id: Uuid) -> TestResponse {
+        let (start_date, end_date) = future_dates();
+        self.post(
+            "/booking",
+            Some(token),
+            json!({
+                "roomId": room_id,
+                "startDate": start_date,
+                "endDate": end_date,
+                "mainGuest": guest_id,
+                "otherGuests": []
+            }),
+        )
+        .await
+    }
+}
+
+pub fn id(value: &Value) -> Uuid {
+    value
+        .as_str()
+        .and_then(|id| id.parse().ok())
+        .unwrap_or_else(|| panic!("'{value}' is not an id"))
+}
+
+pub fn unique_email() -> String {
+    format!("{}@example.com", Uuid::new_v4().simple())
+}
+
+/// Only letters, so it passes the name validation
+pub fn unique_name() -> String {
+    Uuid::new_v4()
+        .simple()
+        .to_string()
+        .chars()
+        .take(12)
+        .map(|c| (b'a' + c.to_digit(16).unwrap() as u8) as char)
+        .collect()
+}
+
+/// Room numbers must be unique, which matters when the tests share a postgres database
+pub fn unique_room_number() -> String {
+    let number = Uuid::new_v4().as_u128();
+    let letter = (b'A' + (number / 100_000 % 26) as u8) as char;
+    format!("{}{}", number % 100_000, letter)
+}
+
+/// A private room for two with a unique room number
+pub fn room_json(price: i64) -> Value {
+    json!({
+        "price": price,
+        "floor": 1,
+        "roomNumber": unique_room_number(),
+        "bathroomType": "Private",
+        "beds": [{ "bedSize": "Double", "count": 1 }]
+    })
+}
+
+pub fn guest_json(first_name: &str, last_name: &str) -> Value {
+    json!({
+        "firstName": first_name,
+        "lastName": last_name,
+        "dateOfBirth": "1990-01-15"
+    })
+}
+
+pub fn unique_phone_number() -> String {
+    format!("+359{:09}", Uuid::new_v4().as_u128() % 1_000_000_000)
+}
+
+/// The ucn and card number must be unique
+pub fn id_card_json() -> Value {
+    let number = Uuid::new_v4().as_u128();
+    json!({
+        "ucn": format!("{:010}", number % 10_000_000_000),
+        "idCardNumber": format!("{:09}", (number >> 64) % 1_000_000_000),
+        "issueAuthority": "Sofia",
+        "issueDate": "2020-01-15",
+        "validity": "2030-01-15"
+    })
+}
+
+/// Three nights a year from now
+pub fn future_dates() -> (NaiveDate, NaiveDate) {
+    let start_date = add_days(Utc::now().date_naive(), 365).unwrap();
+
+    (start_date, add_days(start_date, 3).unwrap())
+}
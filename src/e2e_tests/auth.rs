@@ -0,0 +1,179 @@
+use actix_web::http::StatusCode;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use serde_json::json;
+
+use crate::{
+    constants::ENV_OTP_VALIDITY_SECS,
+    persistence::email_outbox::{self, EmailStatus},
+};
+
+use super::*;
+
+#[actix_web::test]
+async fn test_register_login_and_refresh() {
+    let app = spawn_app(&[]).await;
+    let email = unique_email();
+
+    app.register(&email, USER_PASSWORD).await;
+    app.post(
+        "/auth/register",
+        None,
+        json!({ "email": email, "password": USER_PASSWORD }),
+    )
+    .await
+    .expect(StatusCode::BAD_REQUEST);
+
+    app.post(
+        "/auth/login",
+        None,
+        json!({ "email": email, "password": "Wrong1234" }),
+    )
+    .await
+    .expect(StatusCode::UNAUTHORIZED);
+    let token = app.login(&email, USER_PASSWORD).await;
+
+    let body = app
+        .get("/auth/refresh", Some(&token))
+        .await
+        .expect(StatusCode::OK);
+    assert!(body["token"].is_string());
+    app.get("/auth/refresh", None)
+        .await
+        .expect(StatusCode::UNAUTHORIZED);
+}
+
+#[actix_web::test]
+async fn test_register_invalid_input() {
+    let app = spawn_app(&[]).await;
+
+    app.post(
+        "/auth/register",
+        None,
+        json!({ "email": "not-an-email", "password": USER_PASSWORD }),
+    )
+    .await
+    .expect(StatusCode::BAD_REQUEST);
+    app.post(
+        "/auth/register",
+        None,
+        json!({ "email": unique_email(), "password": "short" }),
+    )
+    .await
+    .expect(StatusCode::BAD_REQUEST);
+    app.post("/auth/register", None, json!({ "email": unique_email() }))
+        .await
+        .expect(StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn test_reset_password_with_emailed_otp() {
+    let app = spawn_app(&[]).await;
+    let (_, email, _) = app.register_and_login().await;
+
+    app.post("/auth/send-otp", None, json!({ "email": email }))
+        .await
+        .expect(StatusCode::OK);
+    app.deliver_emails().await;
+    let otp_email = app.emails.last_email_to(&email).unwrap();
+    let outbox_email = find_outbox_email(&app, &email).await;
+    assert_eq!(outbox_email.status, EmailStatus::Sent);
+    assert_eq!(outbox_email.params, "{}");
+    let otp = otp_email
+        .content
+        .text
+        .split('\'')
+        .nth(1)
+        .unwrap()
+        .to_owned();
+
+    let new_password = "NewPassword1";
+    app.post(
+        "/auth/reset-password",
+        None,
+        json!({ "email": email, "otp": "00000000", "newPassword": new_password }),
+    )
+    .await
+    .expect(StatusCode::BAD_REQUEST);
+    app.post(
+        "/auth/reset-password",
+        None,
+        json!({ "email": email, "otp": otp, "newPassword": new_password }),
+    )
+    .await
+    .expect(StatusCode::OK);
+
+    app.login(&email, new_password).await;
+    app.post(
+        "/auth/login",
+        None,
+        json!({ "email": email, "password": USER_PASSWORD }),
+    )
+    .await
+    .expect(StatusCode::UNAUTHORIZED);
+}
+
+#[actix_web::test]
+async fn test_expired_otp_email_is_not_sent() {
+    let app = spawn_app(&[(ENV_OTP_VALIDITY_SECS, "0")]).await;
+    let (_, email, _) = app.register_and_login().await;
+
+    app.post("/auth/send-otp", None, json!({ "email": email }))
+        .await
+        .expect(StatusCode::OK);
+    app.deliver_emails().await;
+
+    assert!(app.emails.last_email_to(&email).is_none());
+    let outbox_email = find_outbox_email(&app, &email).await;
+    assert_eq!(outbox_email.status, EmailStatus::Failed);
+    assert_eq!(outbox_email.params, "{}");
+}
+
+async fn find_outbox_email<S>(app: &TestApp<S>, recipient: &str) -> email_outbox::Model {
+    email_outbox::Entity::find()
+        .filter(email_outbox::Column::Recipient.eq(recipient))
+        .one(app.state.db.as_ref())
+        .await
+        .unwrap()
+        .unwrap()
+}
+
+#[actix_web::test]
+async fn test_logout_invalidates_token() {
+    let app = spawn_app(&[]).await;
+    let (_, _, token) = app.register_and_login().await;
+
+    app.send(Method::POST, "/auth/logout", Some(&token), None)
+        .await
+        .expect(StatusCode::OK);
+
+    app.get("/auth/refresh", Some(&token))
+        .await
+        .expect(StatusCode::UNAUTHORIZED);
+}
+
+#[actix_web::test]
+async fn test_only_admins_promote_users() {
+    let app = spawn_app(&[]).await;
+    let (_, email, token) = app.register_and_login().await;
+
+    app.put("/auth/promote", Some(&token), json!({ "email": email }))
+        .await
+        .expect(StatusCode::FORBIDDEN);
+    app.post("/room", Some(&token), room_json(100))
+        .await
+        .expect(StatusCode::FORBIDDEN);
+
+    let admin_token = app.admin_token().await;
+    app.put(
+        "/auth/promote",
+        Some(&admin_token),
+        json!({ "email": email }),
+    )
+    .await
+    .expect(StatusCode::OK);
+
+    let token = app.login(&email, USER_PASSWORD).await;
+    app.post("/room", Some(&token), room_json(100))
+        .await
+        .expect(StatusCode::CREATED);
+}
@@ -0,0 +1,227 @@
+use actix_web::http::StatusCode;
+
+use crate::constants::ENV_AUTO_CONFIRM_USER_BOOKINGS;
+
+use super::*;
+
+#[actix_web::test]
+async fn test_user_booking_is_confirmed_paid_and_canceled() {
+    let app = spawn_app(&[]).await;
+    let admin_token = app.admin_token().await;
+    let (user_id, email, user_token) = app.register_and_login().await;
+    let room_id = app.add_room(&admin_token, 100).await;
+    let guest_id = app.add_guest(&user_token).await;
+
+    let body = app
+        .book_room(&user_token, room_id, guest_id)
+        .await
+        .expect(StatusCode::CREATED);
+    let booking_id = id(&body["bookingId"]);
+    let booking_uri = format!("/booking/{booking_id}");
+
+    let body = app
+        .get(&booking_uri, Some(&user_token))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(body["status"], "Pending");
+    assert_eq!(body["userId"], user_id.to_string());
+    assert_eq!(body["totalPrice"], 300);
+    assert_eq!(body["mainGuest"]["first_name"], "John");
+
+    let body = app
+        .get(
+            "/booking?includeCanceled=false&includePaid=true",
+            Some(&user_token),
+        )
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(body["ids"], json!([booking_id]));
+
+    app.send(
+        Method::PUT,
+        &format!("/booking/pay/{booking_id}"),
+        Some(&admin_token),
+        None,
+    )
+    .await
+    .expect(StatusCode::BAD_REQUEST);
+    app.send(
+        Method::PUT,
+        &format!("/booking/confirm/{booking_id}"),
+        Some(&admin_token),
+        None,
+    )
+    .await
+    .expect(StatusCode::OK);
+
+    let body = app
+        .send(
+            Method::PUT,
+            &format!("/booking/pay/{booking_id}"),
+            Some(&admin_token),
+            None,
+        )
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(body["status"], "Paid");
+    assert_eq!(body["paidAmount"], 300);
+    assert_eq!(body["remainingAmount"], 0);
+
+    app.send(
+        Method::PUT,
+        &format!("/booking/cancel/{booking_id}"),
+        Some(&admin_token),
+        None,
+    )
+    .await
+    .expect(StatusCode::OK);
+    let body = app
+        .get(&booking_uri, Some(&user_token))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(body["status"], "Canceled");
+
+    app.deliver_emails().await;
+    let subjects: Vec<_> = app
+        .emails
+        .sent_emails()
+        .into_iter()
+        .filter(|sent| sent.to == email)
+        .map(|sent| sent.content.subject)
+        .collect();
+    assert_eq!(
+        subjects,
+        vec!["Booking created", "Booking paid", "Booking canceled"]
+    );
+}
+
+#[actix_web::test]
+async fn test_auto_confirmed_user_booking_is_unpaid() {
+    let app = spawn_app(&[(ENV_AUTO_CONFIRM_USER_BOOKINGS, "true")]).await;
+    let admin_token = app.admin_token().await;
+    let (_, _, user_token) = app.register_and_login().await;
+    let room_id = app.add_room(&admin_token, 100).await;
+    let guest_id = app.add_guest(&user_token).await;
+
+    let body = app
+        .book_room(&user_token, room_id, guest_id)
+        .await
+        .expect(StatusCode::CREATED);
+
+    let body = app
+        .get(
+            &format!("/booking/{}", id(&body["bookingId"])),
+            Some(&user_token),
+        )
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(body["status"], "Unpaid");
+}
+
+#[actix_web::test]
+async fn test_booking_errors() {
+    let app = spawn_app(&[]).await;
+    let admin_token = app.admin_token().await;
+    let (_, _, user_token) = app.register_and_login().await;
+    let (_, _, other_token) = app.register_and_login().await;
+    let room_id = app.add_room(&admin_token, 100).await;
+    let guest_id = app.add_guest(&user_token).await;
+
+    app.book_room(&user_token, Uuid::new_v4(), guest_id)
+        .await
+        .expect(StatusCode::NOT_FOUND);
+    app.book_room(&other_token, room_id, guest_id)
+        .await
+        .expect(StatusCode::FORBIDDEN);
+
+    let (start_date, end_date) = future_dates();
+    app.post(
+        "/booking",
+        Some(&user_token),
+        json!({
+            "roomId": room_id,
+            "startDate": end_date,
+            "endDate": start_date,
+            "mainGuest": guest_id,
+            "otherGuests": []
+        }),
+    )
+    .await
+    .expect(StatusCode::BAD_REQUEST);
+
+    let body = app
+        .book_room(&user_token, room_id, guest_id)
+        .await
+        .expect(StatusCode::CREATED);
+    let booking_id = id(&body["bookingId"]);
+    app.book_room(&user_token, room_id, guest_id)
+        .await
+        .expect(StatusCode::CONFLICT);
+
+    app.get(&format!("/booking/{booking_id}"), Some(&other_token))
+        .await
+        .expect(StatusCode::FORBIDDEN);
+    app.send(
+        Method::PUT,
+        &format!("/booking/confirm/{booking_id}"),
+        Some(&user_token),
+        None,
+    )
+    .await
+    .expect(StatusCode::FORBIDDEN);
+    app.get(&format!("/booking/{}", Uuid::new_v4()), Some(&admin_token))
+        .await
+        .expect(StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn test_user_cancels_own_pending_booking() {
+    let app = spawn_app(&[]).await;
+    let admin_token = app.admin_token().await;
+    let (_, _, user_token) = app.register_and_login().await;
+    let (_, _, other_token) = app.register_and_login().await;
+    let room_id = app.add_room(&admin_token, 100).await;
+    let guest_id = app.add_guest(&user_token).await;
+
+    let body = app
+        .book_room(&user_token, room_id, guest_id)
+        .await
+        .expect(StatusCode::CREATED);
+    let booking_id = id(&body["bookingId"]);
+    let cancel_uri = format!("/booking/cancel/{booking_id}");
+    app.send(Method::PUT, &cancel_uri, Some(&other_token), None)
+        .await
+        .expect(StatusCode::FORBIDDEN);
+    let body = app
+        .send(Method::PUT, &cancel_uri, Some(&user_token), None)
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(body["penalty"], 0);
+    let body = app
+        .get(&format!("/booking/{booking_id}"), Some(&user_token))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(body["status"], "Canceled");
+
+    let body = app
+        .book_room(&user_token, room_id, guest_id)
+        .await
+        .expect(StatusCode::CREATED);
+    let booking_id = id(&body["bookingId"]);
+    app.send(
+        Method::PUT,
+        &format!("/booking/confirm/{booking_id}"),
+        Some(&admin_token),
+        None,
+    )
+    .await
+    .expect(StatusCode::OK);
+    app.send(
+        Method::PUT,
+        &format!("/booking/cancel/{booking_id}"),
+        Some(&user_token),
+        None,
+    )
+    .await
+    .expect(StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,99 @@
+use actix_web::http::StatusCode;
+
+use super::*;
+
+#[actix_web::test]
+async fn test_guests_of_a_room_comment_on_it() {
+    let app = spawn_app(&[]).await;
+    let admin_token = app.admin_token().await;
+    let (user_id, _, user_token) = app.register_and_login().await;
+    let room_id = app.add_room(&admin_token, 100).await;
+    let comment = json!({ "roomId": room_id, "contents": "Great view" });
+
+    app.post("/comment", Some(&user_token), comment.clone())
+        .await
+        .expect(StatusCode::UNAUTHORIZED);
+
+    let guest_id = app.add_guest(&user_token).await;
+    app.book_room(&user_token, room_id, guest_id)
+        .await
+        .expect(StatusCode::CREATED);
+    let body = app
+        .post("/comment", Some(&user_token), comment)
+        .await
+        .expect(StatusCode::CREATED);
+    let comment_id = id(&body["commentId"]);
+
+    app.patch(
+        &format!("/comment/{comment_id}"),
+        Some(&user_token),
+        json!({ "contents": "Great view, noisy street" }),
+    )
+    .await
+    .expect(StatusCode::OK);
+
+    let body = app
+        .get(
+            &format!("/comment/{room_id}?page=0&size=10"),
+            Some(&user_token),
+        )
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(body["totalSize"], 1);
+    assert_eq!(body["comments"][0]["user_id"], user_id.to_string());
+    assert_eq!(body["comments"][0]["content"], "Great view, noisy street");
+    assert!(body["comments"][0]["updated_time"].is_string());
+}
+
+#[actix_web::test]
+async fn test_comment_errors() {
+    let app = spawn_app(&[]).await;
+    let admin_token = app.admin_token().await;
+    let (_, _, user_token) = app.register_and_login().await;
+    let room_id = app.add_room(&admin_token, 100).await;
+
+    app.post(
+        "/comment",
+        None,
+        json!({ "roomId": room_id, "contents": "Hello" }),
+    )
+    .await
+    .expect(StatusCode::UNAUTHORIZED);
+    app.post(
+        "/comment",
+        Some(&admin_token),
+        json!({ "roomId": room_id, "contents": "" }),
+    )
+    .await
+    .expect(StatusCode::BAD_REQUEST);
+    app.post(
+        "/comment",
+        Some(&admin_token),
+        json!({ "roomId": Uuid::new_v4(), "contents": "Hello" }),
+    )
+    .await
+    .expect(StatusCode::NOT_FOUND);
+
+    let body = app
+        .post(
+            "/comment",
+            Some(&admin_token),
+            json!({ "roomId": room_id, "contents": "Welcome" }),
+        )
+        .await
+        .expect(StatusCode::CREATED);
+    app.patch(
+        &format!("/comment/{}", id(&body["commentId"])),
+        Some(&user_token),
+        json!({ "contents": "Changed" }),
+    )
+    .await
+    .expect(StatusCode::FORBIDDEN);
+
+    app.get(
+        &format!("/comment/{}?page=0&size=10", Uuid::new_v4()),
+        Some(&user_token),
+    )
+    .await
+    .expect(StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,107 @@
+use actix_web::http::StatusCode;
+
+use super::*;
+
+#[actix_web::test]
+async fn test_user_manages_own_guests() {
+    let app = spawn_app(&[]).await;
+    let (_, _, token) = app.register_and_login().await;
+    let last_name = unique_name();
+
+    let body = app
+        .post("/guest", Some(&token), guest_json("Jane", &last_name))
+        .await
+        .expect(StatusCode::CREATED);
+    let guest_id = id(&body["guestId"]);
+
+    let body = app
+        .get(&format!("/guest/{guest_id}"), Some(&token))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(body["firstName"], "Jane");
+    assert_eq!(body["dateOfBirth"], "1990-01-15");
+
+    let phone_number = unique_phone_number();
+    let mut update = guest_json("Janet", &last_name);
+    update["phoneNumber"] = phone_number.clone().into();
+    app.put(&format!("/guest/{guest_id}"), Some(&token), update)
+        .await
+        .expect(StatusCode::OK);
+    let body = app
+        .get(&format!("/guest/{guest_id}"), Some(&token))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(body["firstName"], "Janet");
+    assert_eq!(body["phoneNumber"], phone_number.as_str());
+
+    let body = app
+        .get(&format!("/guest?lastName={last_name}"), Some(&token))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(body["guestIds"], json!([guest_id]));
+}
+
+#[actix_web::test]
+async fn test_guests_are_private_to_their_owner() {
+    let app = spawn_app(&[]).await;
+    let (_, _, owner_token) = app.register_and_login().await;
+    let (_, _, other_token) = app.register_and_login().await;
+    let phone_number = unique_phone_number();
+    let mut guest = guest_json("John", "Smith");
+    guest["phoneNumber"] = phone_number.clone().into();
+    let body = app
+        .post("/guest", Some(&owner_token), guest.clone())
+        .await
+        .expect(StatusCode::CREATED);
+    let guest_id = id(&body["guestId"]);
+
+    let body = app
+        .post("/guest", Some(&other_token), guest.clone())
+        .await
+        .expect(StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "Guest details are already in use");
+    let body = app
+        .post("/guest", Some(&owner_token), guest)
+        .await
+        .expect(StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "Phone number is already in use");
+
+    app.get(&format!("/guest/{guest_id}"), Some(&other_token))
+        .await
+        .expect(StatusCode::FORBIDDEN);
+    app.put(
+        &format!("/guest/{guest_id}"),
+        Some(&other_token),
+        guest_json("Eve", "Smith"),
+    )
+    .await
+    .expect(StatusCode::FORBIDDEN);
+
+    let admin_token = app.admin_token().await;
+    app.get(&format!("/guest/{guest_id}"), Some(&admin_token))
+        .await
+        .expect(StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn test_guest_errors() {
+    let app = spawn_app(&[]).await;
+    let (_, _, token) = app.register_and_login().await;
+
+    app.post("/guest", None, guest_json("John", "Smith"))
+        .await
+        .expect(StatusCode::UNAUTHORIZED);
+    app.post("/guest", Some(&token), guest_json("J", "Smith"))
+        .await
+        .expect(StatusCode::BAD_REQUEST);
+
+    let mut guest = guest_json("John", "Smith");
+    guest["phoneNumber"] = "123".into();
+    app.post("/guest", Some(&token), guest)
+        .await
+        .expect(StatusCode::BAD_REQUEST);
+
+    app.get(&format!("/guest/{}", Uuid::new_v4()), Some(&token))
+        .await
+        .expect(StatusCode::NOT_FOUND);
+}
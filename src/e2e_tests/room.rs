@@ -0,0 +1,244 @@
+use actix_web::http::StatusCode;
+
+use crate::constants::ENV_SERVER_MAX_BODY_BYTES;
+
+use super::*;
+
+#[actix_web::test]
+async fn test_admin_manages_rooms() {
+    let app = spawn_app(&[]).await;
+    let admin_token = app.admin_token().await;
+    let (_, _, user_token) = app.register_and_login().await;
+
+    let room = room_json(12000);
+    let room_number = room["roomNumber"].as_str().unwrap().to_owned();
+    let body = app
+        .post("/room", Some(&admin_token), room)
+        .await
+        .expect(StatusCode::CREATED);
+    let room_id = id(&body["roomId"]);
+
+    let body = app
+        .get(&format!("/room/{room_id}"), Some(&user_token))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(body["price"], 12000);
+    assert_eq!(body["roomNumber"], room_number.as_str());
+    assert_eq!(body["beds"][0]["bedSize"], "Double");
+
+    let body = app
+        .get(
+            &format!("/room?page=0&size=10&roomNumberPrefix={room_number}"),
+            Some(&user_token),
+        )
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(body["rooms"][0]["id"], room_id.to_string());
+
+    let mut update = room_json(15000);
+    update["roomNumber"] = room_number.clone().into();
+    app.put(&format!("/room/{room_id}"), Some(&admin_token), update)
+        .await
+        .expect(StatusCode::OK);
+    let body = app
+        .get(&format!("/room/{room_id}"), Some(&user_token))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(body["price"], 15000);
+
+    app.delete(&format!("/room/{room_id}"), Some(&admin_token))
+        .await
+        .expect(StatusCode::OK);
+    app.get(&format!("/room/{room_id}"), Some(&user_token))
+        .await
+        .expect(StatusCode::NOT_FOUND);
+
+    app.send(
+        Method::PUT,
+        &format!("/room/{room_id}/restore"),
+        Some(&admin_token),
+        None,
+    )
+    .await
+    .expect(StatusCode::OK);
+    app.get(&format!("/room/{room_id}"), Some(&user_token))
+        .await
+        .expect(StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn test_add_room_errors() {
+    let app = spawn_app(&[]).await;
+    let admin_token = app.admin_token().await;
+    let (_, _, user_token) = app.register_and_login().await;
+
+    app.post("/room", None, room_json(100))
+        .await
+        .expect(StatusCode::UNAUTHORIZED);
+    app.post("/room", Some(&user_token), room_json(100))
+        .await
+        .expect(StatusCode::FORBIDDEN);
+
+    let mut room = room_json(100);
+    room["roomNumber"] = "not a number".into();
+    app.post("/room", Some(&admin_token), room)
+        .await
+        .expect(StatusCode::BAD_REQUEST);
+
+    let mut room = room_json(100);
+    room["beds"] = json!([]);
+    app.post("/room", Some(&admin_token), room)
+        .await
+        .expect(StatusCode::BAD_REQUEST);
+
+    let room = room_json(100);
+    app.post("/room", Some(&admin_token), room.clone())
+        .await
+        .expect(StatusCode::CREATED);
+    app.post("/room", Some(&admin_token), room)
+        .await
+        .expect(StatusCode::BAD_REQUEST);
+
+    app.get(&format!("/room/{}", Uuid::new_v4()), Some(&user_token))
+        .await
+        .expect(StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn test_get_rooms_validates_page_size() {
+    let app = spawn_app(&[]).await;
+    let (_, _, user_token) = app.register_and_login().await;
+
+    for size in [0, 101] {
+        app.get(&format!("/room?page=0&size={size}"), Some(&user_token))
+            .await
+            .expect(StatusCode::BAD_REQUEST);
+    }
+    app.get("/room?page=0&size=100", Some(&user_token))
+        .await
+        .expect(StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn test_update_room_errors() {
+    let app = spawn_app(&[]).await;
+    let admin_token = app.admin_token().await;
+    let (_, _, user_token) = app.register_and_login().await;
+    let room_id = app.add_room(&admin_token, 100).await;
+    let other_room = room_json(100);
+    app.post("/room", Some(&admin_token), other_room.clone())
+        .await
+        .expect(StatusCode::CREATED);
+    let room_uri = format!("/room/{room_id}");
+
+    app.put(&room_uri, None, room_json(100))
+        .await
+        .expect(StatusCode::UNAUTHORIZED);
+    app.put(&room_uri, Some(&user_token), room_json(100))
+        .await
+        .expect(StatusCode::FORBIDDEN);
+    app.put(
+        &format!("/room/{}", Uuid::new_v4()),
+        Some(&admin_token),
+        room_json(100),
+    )
+    .await
+    .expect(StatusCode::NOT_FOUND);
+
+    let mut room = room_json(100);
+    room["beds"] = json!([]);
+    app.put(&room_uri, Some(&admin_token), room)
+        .await
+        .expect(StatusCode::BAD_REQUEST);
+
+    let mut room = room_json(100);
+    room["roomNumber"] = other_room["roomNumber"].clone();
+    app.put(&room_uri, Some(&admin_token), room)
+        .await
+        .expect(StatusCode::CONFLICT);
+}
+
+#[actix_web::test]
+async fn test_update_room_refuses_capacity_reduction_that_breaks_future_bookings() {
+    let app = spawn_app(&[]).await;
+    let admin_token = app.admin_token().await;
+    let (_, _, user_token) = app.register_and_login().await;
+    let room = room_json(100);
+    let body = app
+        .post("/room", Some(&admin_token), room.clone())
+        .await
+        .expect(StatusCode::CREATED);
+    let room_uri = format!("/room/{}", id(&body["roomId"]));
+    let main_guest = app.add_guest(&user_token).await;
+    let other_guest = app.add_guest(&user_token).await;
+    let (start_date, end_date) = future_dates();
+    app.post(
+        "/booking",
+        Some(&user_token),
+        json!({
+            "roomId": id(&body["roomId"]),
+            "startDate": start_date,
+            "endDate": end_date,
+            "mainGuest": main_guest,
+            "otherGuests": [other_guest]
+        }),
+    )
+    .await
+    .expect(StatusCode::CREATED);
+
+    let mut single_bed = room.clone();
+    single_bed["beds"] = json!([{ "bedSize": "Single", "count": 1 }]);
+    app.put(&room_uri, Some(&admin_token), single_bed)
+        .await
+        .expect(StatusCode::CONFLICT);
+    let body = app
+        .get(&room_uri, Some(&admin_token))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(body["beds"][0]["bedSize"], "Double");
+
+    let mut two_single_beds = room;
+    two_single_beds["beds"] = json!([{ "bedSize": "Single", "count": 2 }]);
+    app.put(&room_uri, Some(&admin_token), two_single_beds)
+        .await
+        .expect(StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn test_delete_booked_room_requires_force() {
+    let app = spawn_app(&[]).await;
+    let admin_token = app.admin_token().await;
+    let (_, email, user_token) = app.register_and_login().await;
+    let room_id = app.add_room(&admin_token, 100).await;
+    let guest_id = app.add_guest(&user_token).await;
+    let body = app
+        .book_room(&user_token, room_id, guest_id)
+        .await
+        .expect(StatusCode::CREATED);
+    let booking_id = body["bookingId"].clone();
+
+    app.delete(&format!("/room/{room_id}"), Some(&admin_token))
+        .await
+        .expect(StatusCode::CONFLICT);
+
+    let body = app
+        .delete(&format!("/room/{room_id}?force=true"), Some(&admin_token))
+        .await
+        .expect(StatusCode::OK);
+    assert_eq!(body["canceledBookings"], json!([booking_id]));
+
+    app.deliver_emails().await;
+    let email = app.emails.last_email_to(&email).unwrap();
+    assert_eq!(email.content.subject, "Booking canceled");
+}
+
+#[actix_web::test]
+async fn test_request_body_limit() {
+    let app = spawn_app(&[(ENV_SERVER_MAX_BODY_BYTES, "100")]).await;
+    // A login fits in the limit, a room with its beds doesn't
+    let admin_token = app.admin_token().await;
+
+    app.post("/room", Some(&admin_token), room_json(100))
+        .await
+        .expect(StatusCode::PAYLOAD_TOO_LARGE);
+}
@@ -1,9 +1,14 @@
-use actix_web::{middleware::Logger, web, App, HttpServer};
-use app_state::AppState;
-use constants::{APP_DEFAULT_LOGGING_LEVEL, REST_HOST};
-use controllers::{auth, booking, comment, guest, room};
+use actix_web::{
+    middleware::Logger,
+    web::{self, JsonConfig, PayloadConfig, ServiceConfig},
+    App, HttpServer,
+};
+use app_state::{connect_database, AppState, EnvironmentVariables, ServerInfo};
+use constants::APP_DEFAULT_LOGGING_LEVEL;
+use controllers::{auth, booking, cancellation_policy, comment, email_outbox, guest, room};
 use cronjobs::start_cronjobs;
-use utoipa::OpenApi;
+use log::info;
+use persistence::migration::{run_migrations, MigrationCommand};
 use utoipa_swagger_ui::{Config, SwaggerUi};
 
 mod api;
@@ -11,34 +16,85 @@ mod app_state;
 mod constants;
 mod controllers;
 mod cronjobs;
+#[cfg(test)]
+mod e2e_tests;
 mod persistence;
 mod security;
 mod services;
 mod util;
 mod validation;
 
-#[actix_web::main]
-async fn main() -> std::io::Result<()> {
-    env_logger::init_from_env(env_logger::Env::new().default_filter_or(APP_DEFAULT_LOGGING_LEVEL));
-    let app_state: AppState = AppState::load().await;
-    start_cronjobs(app_state.clone());
+/// `migrate <up [steps] | down [steps] | status>` runs the schema migrations without the server
+async fn migrate(args: &[String]) -> std::io::Result<()> {
+    let command = MigrationCommand::parse(args).map_err(std::io::Error::other)?;
+    let env = EnvironmentVariables::load();
+    let db = connect_database(&env).await;
 
-    HttpServer::new(move || {
-        App::new()
-            .wrap(Logger::default())
-            .app_data(web::Data::new(app_state.clone()))
+    run_migrations(&db, &command)
+        .await
+        .map_err(std::io::Error::other)
+}
+
+/// The app data, swagger and every controller, also used to build the app in the end-to-end tests
+fn configure_app(app_state: AppState, max_body_bytes: usize) -> impl FnOnce(&mut ServiceConfig) {
+    move |cfg| {
+        cfg.app_data(web::Data::new(app_state))
+            .app_data(JsonConfig::default().limit(max_body_bytes))
+            .app_data(PayloadConfig::new(max_body_bytes))
             .service(
                 SwaggerUi::new("/swagger-ui/{_:.*}")
                     .config(Config::default())
-                    .url("/api-doc/openapi.json", controllers::ApiDoc::new()),
+                    .url("/api-doc/openapi.json", controllers::ApiDoc::build()),
             )
             .configure(auth::config)
             .configure(room::config)
             .configure(guest::config)
             .configure(booking::config)
             .configure(comment::config)
+            .configure(cancellation_policy::config)
+            .configure(email_outbox::config);
+    }
+}
+
+#[actix_web::main]
+async fn main() -> std::io::Result<()> {
+    env_logger::init_from_env(env_logger::Env::new().default_filter_or(APP_DEFAULT_LOGGING_LEVEL));
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().is_some_and(|arg| arg == "migrate") {
+        return migrate(&args[1..]).await;
+    }
+
+    let env = EnvironmentVariables::load();
+    let server_info = ServerInfo::new(&env);
+    let app_state: AppState = AppState::load(&env).await;
+    start_cronjobs(app_state.clone());
+
+    let max_body_bytes = server_info.max_body_bytes;
+    let mut server = HttpServer::new(move || {
+        App::new()
+            .wrap(Logger::default())
+            .configure(configure_app(app_state.clone(), max_body_bytes))
     })
-    .bind(REST_HOST)?
-    .run()
-    .await
+    .keep_alive(server_info.keep_alive);
+    if let Some(workers) = server_info.workers {
+        server = server.workers(workers);
+    }
+
+    let address = (server_info.host.as_str(), server_info.port);
+    server = match &server_info.tls {
+        Some(tls) => server.bind_rustls_0_23(address, tls.load_server_config())?,
+        None => server.bind(address)?,
+    };
+    info!(
+        "Listening on {}://{}:{}",
+        if server_info.tls.is_some() {
+            "https"
+        } else {
+            "http"
+        },
+        server_info.host,
+        server_info.port
+    );
+
+    server.run().await
 }
@@ -1,7 +1,9 @@
 use actix_web::http::StatusCode;
-use log::{error, info};
+use log::{error, info, warn};
+use migration::{find_pending_migrations, run_migrations, MigrationCommand};
 use sea_orm::{
-    ActiveModelTrait, ActiveValue, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, Schema,
+    sqlx::{self, error::DatabaseError, sqlite::SqliteError},
+    ActiveModelTrait, ActiveValue, DatabaseConnection, DbErr, RuntimeErr,
 };
 use user::find_user_by_email;
 use uuid::Uuid;
@@ -9,20 +11,34 @@ use uuid::Uuid;
 use crate::{
     api::error_response::ErrorResponse,
     app_state::EnvironmentVariables,
-    constants::{ENV_INITIAL_ADMIN_EMAIL, ENV_INITIAL_ADMIN_PASSWORD},
+    constants::{ENV_INITIAL_ADMIN_EMAIL, ENV_INITIAL_ADMIN_PASSWORD, ENV_MIGRATE_ON_STARTUP},
     security::hash_password,
 };
 
 pub mod bed;
 pub mod booking;
+pub mod booking_change;
 pub mod booking_guest;
+pub mod cancellation_policy;
 pub mod comment;
+pub mod data_migration;
+pub mod email_outbox;
 pub mod guest;
 pub mod invalidated_token;
+pub mod migration;
 pub mod one_time_password;
+pub mod payment;
 pub mod room;
+pub mod room_price_rule;
+#[cfg(test)]
+pub mod test_data;
 pub mod user;
 
+const SQLITE_BUSY: i32 = 5;
+const SQLITE_LOCKED: i32 = 6;
+#[cfg(test)]
+const CONCURRENT_TEST_DB_CONNECTIONS: u32 = 10;
+
 fn db_error_to_string(error: DbErr) -> String {
     match error {
         DbErr::ConnectionAcquire(conn_acquire_err) => {
@@ -72,7 +88,31 @@ fn db_error_to_string(error: DbErr) -> String {
     }
 }
 
+/// SQLite has a single writer, a transaction that can't take the write lock fails with
+/// SQLITE_BUSY or SQLITE_LOCKED instead of waiting when that could deadlock
+fn is_sqlite_busy(error: &DbErr) -> bool {
+    let (DbErr::Exec(RuntimeErr::SqlxError(sqlx::Error::Database(database_error)))
+    | DbErr::Query(RuntimeErr::SqlxError(sqlx::Error::Database(database_error)))) = error
+    else {
+        return false;
+    };
+
+    database_error
+        .try_downcast_ref::<SqliteError>()
+        .and_then(|sqlite_error| sqlite_error.code())
+        .and_then(|code| code.parse::<i32>().ok())
+        .is_some_and(|code| matches!(code & 0xff, SQLITE_BUSY | SQLITE_LOCKED))
+}
+
 pub fn handle_db_error(error: DbErr) -> ErrorResponse {
+    if is_sqlite_busy(&error) {
+        warn!("Database is busy: {error}");
+        return ErrorResponse::new(
+            "Database is busy, try again".to_string(),
+            StatusCode::CONFLICT,
+        );
+    }
+
     error!("Database error: {error}");
     ErrorResponse::new(db_error_to_string(error), StatusCode::INTERNAL_SERVER_ERROR)
 }
@@ -90,13 +130,14 @@ async fn initialise_admin(db: &DatabaseConnection, env: &EnvironmentVariables) {
     }
 
     let raw_password = env.get(ENV_INITIAL_ADMIN_PASSWORD);
-    let password = hash_password(&raw_password);
+    let password = hash_password(raw_password);
 
     let intital_user = user::ActiveModel {
         id: ActiveValue::Set(Uuid::new_v4()),
         email: ActiveValue::Set(email.clone()),
         password: ActiveValue::Set(password),
         role: ActiveValue::Set(user::Role::Admin),
+        language: ActiveValue::NotSet,
     };
 
     intital_user
@@ -107,30 +148,111 @@ async fn initialise_admin(db: &DatabaseConnection, env: &EnvironmentVariables) {
     info!("Initialised admin user with email: '{email}' and password: '{raw_password}' (change password immediately)");
 }
 
-async fn create_table<E>(db: &DatabaseConnection, entity: E)
-where
-    E: EntityTrait,
-{
-    let builder = db.get_database_backend();
-    let schema = Schema::new(builder);
-    let statement = builder.build(schema.create_table_from_entity(entity).if_not_exists());
-    let result = db.execute(statement).await;
-    if let Err(err) = result {
-        error!("Can't create enitiy:{}", err);
-        panic!("Can't create enitiy:{}", err);
+async fn migrate_schema(db: &DatabaseConnection, env: &EnvironmentVariables) {
+    let migrate_on_startup = env.get_optional(ENV_MIGRATE_ON_STARTUP).unwrap_or(true);
+    if !migrate_on_startup {
+        let pending = find_pending_migrations(db)
+            .await
+            .expect("Can't check pending migrations");
+        if !pending.is_empty() {
+            warn!(
+                "Database has pending migrations: {} (run 'migrate up')",
+                pending.join(", ")
+            );
+        }
+        return;
+    }
+
+    if let Err(err) = run_migrations(db, &MigrationCommand::Up(None)).await {
+        error!("Can't migrate database:{}", err);
+        panic!("Can't migrate database:{}", err);
     }
 }
 
 pub async fn initialise_db(db: &DatabaseConnection, env: &EnvironmentVariables) {
-    create_table(db, user::Entity).await;
-    create_table(db, room::Entity).await;
-    create_table(db, bed::Entity).await;
-    create_table(db, one_time_password::Entity).await;
-    create_table(db, guest::Entity).await;
-    create_table(db, booking::Entity).await;
-    create_table(db, booking_guest::Entity).await;
-    create_table(db, invalidated_token::Entity).await;
-    create_table(db, comment::Entity).await;
-
+    migrate_schema(db, env).await;
     initialise_admin(db, env).await;
 }
+
+#[cfg(test)]
+/// Every call gets its own in-memory SQLite database unless `TEST_DATABASE_URL` is set
+pub async fn connect_test_db() -> DatabaseConnection {
+    let database_url = std::env::var(crate::constants::ENV_TEST_DATABASE_URL)
+        .unwrap_or_else(|_| crate::constants::DEFAULT_TEST_DATABASE_URL.to_owned());
+
+    connect_and_migrate_test_db(database_url).await
+}
+
+#[cfg(test)]
+/// Like `connect_test_db`, but with a pool of several connections to a SQLite file instead of an
+/// in-memory database, so that transactions really run at the same time
+pub async fn connect_concurrent_test_db() -> DatabaseConnection {
+    let database_url =
+        std::env::var(crate::constants::ENV_TEST_DATABASE_URL).unwrap_or_else(|_| {
+            let path = std::env::temp_dir().join(format!("actix_hotel_test_{}.db", Uuid::new_v4()));
+            format!("sqlite://{}?mode=rwc", path.display())
+        });
+    let mut options = sea_orm::ConnectOptions::new(database_url);
+    // SQLite pools default to a single connection
+    options.max_connections(CONCURRENT_TEST_DB_CONNECTIONS);
+
+    connect_and_migrate_test_db(options).await
+}
+
+#[cfg(test)]
+async fn connect_and_migrate_test_db(
+    options: impl Into<sea_orm::ConnectOptions>,
+) -> DatabaseConnection {
+    let db = sea_orm::Database::connect(options)
+        .await
+        .expect("Failed to connect to test database");
+    run_migrations(&db, &MigrationCommand::Up(None))
+        .await
+        .expect("Failed to migrate test database");
+
+    db
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::{
+        ConnectionTrait, DatabaseTransaction, DbBackend, EntityTrait, IntoActiveModel,
+        TransactionTrait,
+    };
+
+    use super::*;
+
+    async fn insert_user(transaction: &DatabaseTransaction) -> Result<user::Model, DbErr> {
+        let id = Uuid::new_v4();
+        user::Model {
+            id,
+            email: format!("{}@example.com", id),
+            password: "password".to_owned(),
+            role: user::Role::User,
+            language: None,
+        }
+        .into_active_model()
+        .insert(transaction)
+        .await
+    }
+
+    #[actix_web::test]
+    async fn test_sqlite_busy_is_a_conflict() {
+        let db = connect_concurrent_test_db().await;
+        if db.get_database_backend() != DbBackend::Sqlite {
+            return;
+        }
+
+        let first = db.begin().await.unwrap();
+        let second = db.begin().await.unwrap();
+        user::Entity::find().all(&first).await.unwrap();
+        user::Entity::find().all(&second).await.unwrap();
+        insert_user(&first).await.unwrap();
+        let err = insert_user(&second).await.unwrap_err();
+        assert!(is_sqlite_busy(&err), "{err}");
+        assert_eq!(handle_db_error(err).status, StatusCode::CONFLICT);
+
+        second.rollback().await.unwrap();
+        first.commit().await.unwrap();
+    }
+}
@@ -4,10 +4,8 @@ use sea_orm::ConnectionTrait;
 use sea_orm::DbErr;
 use sea_orm::DerivePrimaryKey;
 use sea_orm::EntityTrait;
-use sea_orm::FromQueryResult;
 use sea_orm::PrimaryKeyTrait;
 use sea_orm::QueryFilter;
-use sea_orm::QuerySelect;
 use sea_orm::Related;
 use sea_orm::RelationDef;
 use sea_orm::RelationTrait;
@@ -78,24 +76,24 @@ impl Related<super::room::Entity> for Entity {
     }
 }
 
-pub async fn find_total_bed_capacity_for_room<T>(db: &T, room_id: Uuid) -> Result<i16, DbErr>
+pub async fn find_beds_for_rooms<T>(db: &T, room_ids: &[Uuid]) -> Result<Vec<Model>, DbErr>
 where
     T: ConnectionTrait,
 {
-    #[derive(Debug, FromQueryResult)]
-    struct BedTotalCapacity {
-        sum_capacity: i64,
-    }
+    Entity::find()
+        .filter(Column::RoomId.is_in(room_ids.iter().copied()))
+        .all(db)
+        .await
+}
 
-    let result = Entity::find()
+pub async fn delete_beds_for_room<T>(db: &T, room_id: Uuid) -> Result<u64, DbErr>
+where
+    T: ConnectionTrait,
+{
+    let result = Entity::delete_many()
         .filter(Column::RoomId.eq(room_id))
-        .select_only()
-        .column_as(Column::TotalCapacity.sum(), "sum_capacity")
-        .into_model::<BedTotalCapacity>()
-        .one(db)
-        .await?
-        .unwrap_or(BedTotalCapacity { sum_capacity: 0 })
-        .sum_capacity;
+        .exec(db)
+        .await?;
 
-    Ok(result as i16)
+    Ok(result.rows_affected)
 }
@@ -1,7 +1,11 @@
 use sea_orm::prelude::Date;
 use sea_orm::prelude::DateTime;
 use sea_orm::prelude::StringLen;
-use sea_orm::sea_query::any;
+use sea_orm::sea_query::Expr;
+use sea_orm::sea_query::Query;
+use sea_orm::sea_query::SimpleExpr;
+use sea_orm::sqlx::types::chrono::NaiveTime;
+use sea_orm::sqlx::types::chrono::Utc;
 use sea_orm::ActiveModelBehavior;
 use sea_orm::ColumnTrait;
 use sea_orm::Condition;
@@ -14,8 +18,11 @@ use sea_orm::DeriveRelation;
 use sea_orm::EntityTrait;
 use sea_orm::EnumIter;
 use sea_orm::FromQueryResult;
+use sea_orm::JoinType;
+use sea_orm::Order;
 use sea_orm::PrimaryKeyTrait;
 use sea_orm::QueryFilter;
+use sea_orm::QueryOrder;
 use sea_orm::QuerySelect;
 use sea_orm::Related;
 use sea_orm::RelationTrait;
@@ -48,6 +55,23 @@ pub enum BookingStatus {
     Paid,
     #[sea_orm(string_value = "Canceled")]
     Canceled,
+    #[sea_orm(string_value = "CheckedIn")]
+    CheckedIn,
+    #[sea_orm(string_value = "CheckedOut")]
+    CheckedOut,
+    #[sea_orm(string_value = "NoShow")]
+    NoShow,
+    /// Booked by a user and waiting for an admin to confirm it
+    #[sea_orm(string_value = "Pending")]
+    Pending,
+}
+impl BookingStatus {
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            BookingStatus::Canceled | BookingStatus::CheckedOut | BookingStatus::NoShow
+        )
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Default, DeriveEntityModel)]
@@ -57,7 +81,7 @@ pub struct Model {
     pub id: Uuid,
     pub main_guest_id: Uuid,
     pub room_id: Uuid,
-    pub admin_id: Uuid,
+    pub admin_id: Option<Uuid>,
     pub user_id: Option<Uuid>,
     pub booking_time: DateTime,
     pub payment_time: Option<DateTime>,
@@ -65,6 +89,15 @@ pub struct Model {
     pub end_date: Date,
     pub total_price: i64,
     pub status: BookingStatus,
+    pub check_in_time: Option<DateTime>,
+    pub check_out_time: Option<DateTime>,
+    pub no_show_time: Option<DateTime>,
+    pub cancellation_policy_id: Option<Uuid>,
+    pub cancellation_penalty: Option<i64>,
+    pub cancellation_refund: Option<i64>,
+    pub reminder_sent_time: Option<DateTime>,
+    /// Price of the first night when booked or last modified, not known for older bookings
+    pub first_night_price: Option<i64>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -97,6 +130,12 @@ pub enum Relation {
     Guest,
     #[sea_orm(has_many = "super::booking_guest::Entity")]
     BookingGuest,
+    #[sea_orm(
+        belongs_to = "super::cancellation_policy::Entity",
+        from = "Column::CancellationPolicyId",
+        to = "super::cancellation_policy::Column::Id"
+    )]
+    CancellationPolicy,
 }
 impl Related<super::room::Entity> for Entity {
     fn to() -> sea_orm::RelationDef {
@@ -125,18 +164,42 @@ impl Related<super::user::Entity> for Entity {
 
 impl ActiveModelBehavior for ActiveModel {}
 
+/// Stays are half-open `[start_date, end_date)` ranges, the departure day is free for a new arrival
 fn check_room_is_occupied_in_range(start_date: Date, end_date: Date) -> Condition {
-    any![
-        Condition::all()
-            .add(booking::Column::StartDate.gte(start_date))
-            .add(booking::Column::StartDate.lte(end_date)),
-        Condition::all()
-            .add(booking::Column::EndDate.gte(start_date))
-            .add(booking::Column::EndDate.lte(end_date)),
-        Condition::all()
-            .add(booking::Column::StartDate.lte(start_date))
-            .add(booking::Column::EndDate.gte(end_date)),
-    ]
+    Condition::all()
+        .add(booking::Column::StartDate.lt(end_date))
+        .add(booking::Column::EndDate.gt(start_date))
+}
+
+fn is_occupying_room_in_range(start_date: Date, end_date: Date) -> Condition {
+    // The range is never empty, so the day after the start is at most the end date
+    let first_night_after_start = start_date
+        .succ_opt()
+        .unwrap_or(end_date)
+        .and_time(NaiveTime::MIN);
+
+    Condition::all()
+        .add(Column::Status.is_not_in([BookingStatus::Canceled, BookingStatus::NoShow]))
+        .add(check_room_is_occupied_in_range(start_date, end_date))
+        .add(
+            Condition::any()
+                .add(Column::Status.ne(BookingStatus::CheckedOut))
+                .add(Column::CheckOutTime.gte(first_night_after_start)),
+        )
+}
+
+pub fn room_occupied_for_period_expr(start_date: Date, end_date: Date) -> SimpleExpr {
+    Expr::exists(
+        Query::select()
+            .expr(Expr::val(1))
+            .from(Entity)
+            .and_where(
+                Expr::col((Entity, Column::RoomId))
+                    .equals((super::room::Entity, super::room::Column::Id)),
+            )
+            .cond_where(is_occupying_room_in_range(start_date, end_date))
+            .to_owned(),
+    )
 }
 
 pub async fn is_room_occupied_for_period<T>(
@@ -144,17 +207,30 @@ pub async fn is_room_occupied_for_period<T>(
     room_id: Uuid,
     start_date: Date,
     end_date: Date,
+    excluded_booking_id: Option<Uuid>,
 ) -> Result<bool, DbErr>
 where
     T: ConnectionTrait,
 {
-    Ok(booking::Entity::find()
+    let mut query = booking::Entity::find()
         .filter(booking::Column::RoomId.eq(room_id))
-        .filter(booking::Column::Status.eq(BookingStatus::Canceled).not())
-        .filter(check_room_is_occupied_in_range(start_date, end_date))
+        .filter(is_occupying_room_in_range(start_date, end_date));
+
+    if let Some(booking_id) = excluded_booking_id {
+        query = query.filter(booking::Column::Id.ne(booking_id));
+    }
+
+    Ok(query.one(db).await?.is_some())
+}
+
+pub async fn lock_booking_by_id<T>(db: &T, booking_id: Uuid) -> Result<Option<Model>, DbErr>
+where
+    T: ConnectionTrait,
+{
+    Entity::find_by_id(booking_id)
+        .lock_exclusive()
         .one(db)
-        .await?
-        .is_some())
+        .await
 }
 
 pub async fn get_bookings_for_user<T>(
@@ -207,3 +283,378 @@ where
         .await?
         .is_some())
 }
+
+/// Bookings that still hold the room after `from_date`, a booking ending on `from_date` is over.
+/// Checked in guests hold the room until they check out.
+fn is_active_from(from_date: Date) -> Condition {
+    Condition::any()
+        .add(Column::Status.eq(BookingStatus::CheckedIn))
+        .add(
+            Condition::all()
+                .add(Column::Status.is_in([
+                    BookingStatus::Pending,
+                    BookingStatus::Unpaid,
+                    BookingStatus::Paid,
+                ]))
+                .add(Column::EndDate.gt(from_date)),
+        )
+}
+
+pub async fn find_max_guest_count_for_room_from<T>(
+    db: &T,
+    room_id: Uuid,
+    from_date: Date,
+) -> Result<Option<i64>, DbErr>
+where
+    T: ConnectionTrait,
+{
+    #[derive(Debug, FromQueryResult)]
+    struct GuestCount {
+        guest_count: i64,
+    }
+
+    let guest_count = super::booking_guest::Column::GuestId.count();
+
+    Ok(super::booking_guest::Entity::find()
+        .select_only()
+        .column_as(guest_count.clone(), "guest_count")
+        .join(
+            JoinType::InnerJoin,
+            super::booking_guest::Relation::Booking.def(),
+        )
+        .filter(Column::RoomId.eq(room_id))
+        .filter(is_active_from(from_date))
+        .group_by(super::booking_guest::Column::BookingId)
+        .order_by(guest_count, Order::Desc)
+        .into_model::<GuestCount>()
+        .one(db)
+        .await?
+        .map(|count| count.guest_count))
+}
+
+/// Locks the bookings, so check-ins and payments of them wait for the caller's transaction
+pub async fn find_active_bookings_for_room_from<T>(
+    db: &T,
+    room_id: Uuid,
+    from_date: Date,
+) -> Result<Vec<Model>, DbErr>
+where
+    T: ConnectionTrait,
+{
+    Entity::find()
+        .filter(Column::RoomId.eq(room_id))
+        .filter(is_active_from(from_date))
+        .order_by_asc(Column::StartDate)
+        .lock_exclusive()
+        .all(db)
+        .await
+}
+
+/// Only bookings that haven't been checked in or finished are canceled
+pub async fn cancel_bookings<T>(db: &T, booking_ids: Vec<Uuid>) -> Result<u64, DbErr>
+where
+    T: ConnectionTrait,
+{
+    let result = Entity::update_many()
+        .col_expr(Column::Status, Expr::value(BookingStatus::Canceled))
+        .filter(Column::Id.is_in(booking_ids))
+        .filter(Column::Status.is_in([
+            BookingStatus::Pending,
+            BookingStatus::Unpaid,
+            BookingStatus::Paid,
+        ]))
+        .exec(db)
+        .await?;
+
+    Ok(result.rows_affected)
+}
+
+/// Pending bookings hold the room too, so they expire like the unpaid ones
+pub async fn find_expired_unpaid_bookings<T>(
+    db: &T,
+    booked_before: Option<DateTime>,
+    starting_until: Option<Date>,
+) -> Result<Vec<Model>, DbErr>
+where
+    T: ConnectionTrait,
+{
+    let mut deadline_condition = Condition::any();
+    if let Some(booked_before) = booked_before {
+        deadline_condition = deadline_condition.add(Column::BookingTime.lte(booked_before));
+    }
+    if let Some(starting_until) = starting_until {
+        deadline_condition = deadline_condition.add(Column::StartDate.lte(starting_until));
+    }
+    if deadline_condition.is_empty() {
+        return Ok(vec![]);
+    }
+
+    Entity::find()
+        .filter(Column::Status.is_in([BookingStatus::Pending, BookingStatus::Unpaid]))
+        .filter(super::payment::booking_has_payments_expr().not())
+        .filter(deadline_condition)
+        .all(db)
+        .await
+}
+
+pub async fn cancel_unpaid_booking<T>(db: &T, booking_id: Uuid) -> Result<bool, DbErr>
+where
+    T: ConnectionTrait,
+{
+    let result = Entity::update_many()
+        .col_expr(Column::Status, Expr::value(BookingStatus::Canceled))
+        .filter(Column::Id.eq(booking_id))
+        .filter(Column::Status.is_in([BookingStatus::Pending, BookingStatus::Unpaid]))
+        .filter(super::payment::booking_has_payments_expr().not())
+        .exec(db)
+        .await?;
+
+    Ok(result.rows_affected == 1)
+}
+
+/// Active bookings of users starting between today and `starting_until` without a sent reminder
+pub async fn find_bookings_to_remind<T>(db: &T, starting_until: Date) -> Result<Vec<Model>, DbErr>
+where
+    T: ConnectionTrait,
+{
+    Entity::find()
+        .filter(Column::Status.is_in([BookingStatus::Unpaid, BookingStatus::Paid]))
+        .filter(Column::UserId.is_not_null())
+        .filter(Column::ReminderSentTime.is_null())
+        .filter(Column::StartDate.gte(Utc::now().date_naive()))
+        .filter(Column::StartDate.lte(starting_until))
+        .all(db)
+        .await
+}
+
+pub async fn mark_reminder_sent<T>(db: &T, booking_id: Uuid) -> Result<bool, DbErr>
+where
+    T: ConnectionTrait,
+{
+    let result = Entity::update_many()
+        .col_expr(
+            Column::ReminderSentTime,
+            Expr::value(Utc::now().naive_utc()),
+        )
+        .filter(Column::Id.eq(booking_id))
+        .filter(Column::ReminderSentTime.is_null())
+        .exec(db)
+        .await?;
+
+    Ok(result.rows_affected == 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        persistence::{
+            connect_test_db,
+            test_data::{insert_room_booking, new_booking},
+        },
+        util::{add_days, sub_days},
+    };
+
+    use super::*;
+
+    #[actix_web::test]
+    async fn test_early_check_out_frees_remaining_nights() {
+        let db = connect_test_db().await;
+
+        let start_date = add_days(Utc::now().date_naive(), 365).unwrap();
+        let check_out_date = add_days(start_date, 2).unwrap();
+        let booking = insert_room_booking(
+            &db,
+            Model {
+                payment_time: Some(Utc::now().naive_utc()),
+                check_in_time: Some(start_date.and_hms_opt(14, 0, 0).unwrap()),
+                check_out_time: Some(check_out_date.and_hms_opt(10, 0, 0).unwrap()),
+                ..new_booking(
+                    BookingStatus::CheckedOut,
+                    start_date,
+                    add_days(start_date, 5).unwrap(),
+                )
+            },
+        )
+        .await;
+
+        let is_occupied = |start: Date, end: Date| {
+            let db = db.clone();
+            async move {
+                is_room_occupied_for_period(&db, booking.room_id, start, end, None)
+                    .await
+                    .unwrap()
+            }
+        };
+
+        assert!(is_occupied(start_date, add_days(start_date, 1).unwrap()).await);
+        assert!(
+            is_occupied(
+                add_days(start_date, 1).unwrap(),
+                add_days(start_date, 3).unwrap()
+            )
+            .await
+        );
+        assert!(!is_occupied(check_out_date, add_days(start_date, 5).unwrap()).await);
+    }
+
+    #[actix_web::test]
+    async fn test_back_to_back_stays_do_not_overlap() {
+        let db = connect_test_db().await;
+
+        let start_date = add_days(Utc::now().date_naive(), 365).unwrap();
+        let end_date = add_days(start_date, 3).unwrap();
+        let booking =
+            insert_room_booking(&db, new_booking(BookingStatus::Paid, start_date, end_date)).await;
+
+        let is_occupied = |start: Date, end: Date| {
+            let db = db.clone();
+            async move {
+                is_room_occupied_for_period(&db, booking.room_id, start, end, None)
+                    .await
+                    .unwrap()
+            }
+        };
+
+        assert!(!is_occupied(end_date, add_days(end_date, 2).unwrap()).await);
+        assert!(!is_occupied(sub_days(start_date, 2).unwrap(), start_date).await);
+        assert!(
+            is_occupied(
+                sub_days(end_date, 1).unwrap(),
+                add_days(end_date, 1).unwrap()
+            )
+            .await
+        );
+        assert!(
+            is_occupied(
+                sub_days(start_date, 1).unwrap(),
+                add_days(start_date, 1).unwrap()
+            )
+            .await
+        );
+        assert!(
+            is_occupied(
+                add_days(start_date, 1).unwrap(),
+                add_days(start_date, 2).unwrap()
+            )
+            .await
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_expired_unpaid_booking_is_canceled_once() {
+        let db = connect_test_db().await;
+
+        let start_date = add_days(Utc::now().date_naive(), 2).unwrap();
+        let booking = insert_room_booking(
+            &db,
+            new_booking(
+                BookingStatus::Unpaid,
+                start_date,
+                add_days(start_date, 1).unwrap(),
+            ),
+        )
+        .await;
+
+        let expired =
+            find_expired_unpaid_bookings(&db, None, Some(sub_days(start_date, 1).unwrap()))
+                .await
+                .unwrap();
+        assert!(expired.iter().all(|expired| expired.id != booking.id));
+
+        let expired = find_expired_unpaid_bookings(&db, None, Some(start_date))
+            .await
+            .unwrap();
+        assert!(expired.iter().any(|expired| expired.id == booking.id));
+
+        assert!(cancel_unpaid_booking(&db, booking.id).await.unwrap());
+        assert!(!cancel_unpaid_booking(&db, booking.id).await.unwrap());
+        let booking = Entity::find_by_id(booking.id).one(&db).await.unwrap();
+        assert_eq!(booking.unwrap().status, BookingStatus::Canceled);
+    }
+
+    #[actix_web::test]
+    async fn test_cancel_bookings_keeps_checked_in_bookings() {
+        let db = connect_test_db().await;
+
+        let start_date = Utc::now().date_naive();
+        let end_date = add_days(start_date, 2).unwrap();
+        let paid =
+            insert_room_booking(&db, new_booking(BookingStatus::Paid, start_date, end_date)).await;
+        let checked_in = insert_room_booking(
+            &db,
+            new_booking(BookingStatus::CheckedIn, start_date, end_date),
+        )
+        .await;
+
+        let canceled = cancel_bookings(&db, vec![paid.id, checked_in.id])
+            .await
+            .unwrap();
+        assert_eq!(canceled, 1);
+        let checked_in = Entity::find_by_id(checked_in.id).one(&db).await.unwrap();
+        assert_eq!(checked_in.unwrap().status, BookingStatus::CheckedIn);
+    }
+
+    #[actix_web::test]
+    async fn test_expired_pending_booking_is_canceled() {
+        let db = connect_test_db().await;
+
+        let start_date = add_days(Utc::now().date_naive(), 30).unwrap();
+        let booking = insert_room_booking(
+            &db,
+            Model {
+                booking_time: (Utc::now() - Duration::from_secs(2 * 3600)).naive_utc(),
+                ..new_booking(
+                    BookingStatus::Pending,
+                    start_date,
+                    add_days(start_date, 1).unwrap(),
+                )
+            },
+        )
+        .await;
+
+        let booked_before = (Utc::now() - Duration::from_secs(3 * 3600)).naive_utc();
+        let expired = find_expired_unpaid_bookings(&db, Some(booked_before), None)
+            .await
+            .unwrap();
+        assert!(expired.iter().all(|expired| expired.id != booking.id));
+
+        let booked_before = (Utc::now() - Duration::from_secs(3600)).naive_utc();
+        let expired = find_expired_unpaid_bookings(&db, Some(booked_before), None)
+            .await
+            .unwrap();
+        assert!(expired.iter().any(|expired| expired.id == booking.id));
+
+        assert!(cancel_unpaid_booking(&db, booking.id).await.unwrap());
+        let booking = Entity::find_by_id(booking.id).one(&db).await.unwrap();
+        assert_eq!(booking.unwrap().status, BookingStatus::Canceled);
+    }
+
+    #[actix_web::test]
+    async fn test_booking_reminder_is_sent_once() {
+        let db = connect_test_db().await;
+
+        let start_date = add_days(Utc::now().date_naive(), 3).unwrap();
+        let mut booking = new_booking(
+            BookingStatus::Paid,
+            start_date,
+            add_days(start_date, 2).unwrap(),
+        );
+        booking.user_id = booking.admin_id;
+        let booking = insert_room_booking(&db, booking).await;
+
+        let to_remind = find_bookings_to_remind(&db, sub_days(start_date, 1).unwrap())
+            .await
+            .unwrap();
+        assert!(to_remind.iter().all(|to_remind| to_remind.id != booking.id));
+
+        let to_remind = find_bookings_to_remind(&db, start_date).await.unwrap();
+        assert!(to_remind.iter().any(|to_remind| to_remind.id == booking.id));
+
+        assert!(mark_reminder_sent(&db, booking.id).await.unwrap());
+        assert!(!mark_reminder_sent(&db, booking.id).await.unwrap());
+        let to_remind = find_bookings_to_remind(&db, start_date).await.unwrap();
+        assert!(to_remind.iter().all(|to_remind| to_remind.id != booking.id));
+    }
+}
@@ -0,0 +1,53 @@
+use sea_orm::prelude::Date;
+use sea_orm::prelude::DateTime;
+use sea_orm::DerivePrimaryKey;
+use sea_orm::EntityTrait;
+use sea_orm::PrimaryKeyTrait;
+use sea_orm::Related;
+use sea_orm::RelationDef;
+use sea_orm::RelationTrait;
+use sea_orm::{ActiveModelBehavior, DeriveEntityModel, DeriveRelation, EnumIter};
+use uuid::Uuid;
+
+#[derive(Clone, Debug, PartialEq, Eq, Default, DeriveEntityModel)]
+#[sea_orm(table_name = "booking_changes")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub booking_id: Uuid,
+    pub admin_id: Uuid,
+    pub change_time: DateTime,
+    pub previous_room_id: Uuid,
+    pub previous_start_date: Date,
+    pub previous_end_date: Date,
+    pub previous_total_price: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::booking::Entity",
+        from = "Column::BookingId",
+        to = "super::booking::Column::Id"
+    )]
+    Booking,
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::AdminId",
+        to = "super::user::Column::Id"
+    )]
+    Admin,
+}
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Related<super::booking::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Booking.def()
+    }
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Admin.def()
+    }
+}
@@ -0,0 +1,72 @@
+use sea_orm::prelude::StringLen;
+use sea_orm::ActiveModelBehavior;
+use sea_orm::ColumnTrait;
+use sea_orm::ConnectionTrait;
+use sea_orm::DbErr;
+use sea_orm::DeriveActiveEnum;
+use sea_orm::DeriveEntityModel;
+use sea_orm::DerivePrimaryKey;
+use sea_orm::DeriveRelation;
+use sea_orm::EntityTrait;
+use sea_orm::EnumIter;
+use sea_orm::PrimaryKeyTrait;
+use sea_orm::QueryFilter;
+use sea_orm::QueryOrder;
+use serde::Deserialize;
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    Default,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(16))")]
+pub enum PenaltyType {
+    #[default]
+    #[sea_orm(string_value = "Percentage")]
+    Percentage,
+    #[sea_orm(string_value = "FirstNight")]
+    FirstNight,
+    #[sea_orm(string_value = "FullPrice")]
+    FullPrice,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default, DeriveEntityModel)]
+#[sea_orm(table_name = "cancellation_policies")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    #[sea_orm(unique, column_type = "String(StringLen::N(64))")]
+    pub name: String,
+    pub free_cancellation_days: Option<i16>,
+    pub penalty_type: PenaltyType,
+    pub penalty_percentage: i16,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+pub async fn find_all_policies<T>(db: &T) -> Result<Vec<Model>, DbErr>
+where
+    T: ConnectionTrait,
+{
+    Entity::find().order_by_asc(Column::Name).all(db).await
+}
+
+pub async fn find_first_by_name<T>(db: &T, name: &str) -> Result<Option<Model>, DbErr>
+where
+    T: ConnectionTrait,
+{
+    Entity::find().filter(Column::Name.eq(name)).one(db).await
+}
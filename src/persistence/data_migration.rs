@@ -0,0 +1,64 @@
+use log::info;
+use sea_orm::prelude::DateTime;
+use sea_orm::prelude::StringLen;
+use sea_orm::sea_query::OnConflict;
+use sea_orm::sqlx::types::chrono::Utc;
+use sea_orm::ActiveModelBehavior;
+use sea_orm::ConnectionTrait;
+use sea_orm::DbErr;
+use sea_orm::DeriveEntityModel;
+use sea_orm::DerivePrimaryKey;
+use sea_orm::DeriveRelation;
+use sea_orm::EntityTrait;
+use sea_orm::EnumIter;
+use sea_orm::IntoActiveModel;
+use sea_orm::PrimaryKeyTrait;
+use sea_orm::Statement;
+use sea_orm::TransactionTrait;
+
+/// Marks one-off data fixes that were already applied to the database
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "data_migrations")]
+pub struct Model {
+    #[sea_orm(
+        primary_key,
+        auto_increment = false,
+        column_type = "String(StringLen::N(128))"
+    )]
+    pub name: String,
+    pub applied_time: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// Executes the statements in a single transaction unless a migration with the same name was
+/// already applied. Returns whether the statements were executed.
+pub async fn apply_once<T>(db: &T, name: &str, statements: Vec<Statement>) -> Result<bool, DbErr>
+where
+    T: ConnectionTrait + TransactionTrait,
+{
+    let transaction = db.begin().await?;
+    let marker = Model {
+        name: name.to_owned(),
+        applied_time: Utc::now().naive_utc(),
+    };
+    let inserted = Entity::insert(marker.into_active_model())
+        .on_conflict(OnConflict::column(Column::Name).do_nothing().to_owned())
+        .exec_without_returning(&transaction)
+        .await?;
+    if inserted == 0 {
+        transaction.commit().await?;
+        return Ok(false);
+    }
+
+    for statement in statements {
+        transaction.execute(statement).await?;
+    }
+    transaction.commit().await?;
+    info!("Applied data migration '{}'", name);
+
+    Ok(true)
+}
@@ -0,0 +1,179 @@
+use sea_orm::prelude::DateTime;
+use sea_orm::prelude::StringLen;
+use sea_orm::sea_query::Expr;
+use sea_orm::ActiveModelBehavior;
+use sea_orm::ActiveModelTrait;
+use sea_orm::ColumnTrait;
+use sea_orm::ConnectionTrait;
+use sea_orm::DbErr;
+use sea_orm::DeriveActiveEnum;
+use sea_orm::DeriveEntityModel;
+use sea_orm::DerivePrimaryKey;
+use sea_orm::DeriveRelation;
+use sea_orm::EntityTrait;
+use sea_orm::EnumIter;
+use sea_orm::IntoActiveModel;
+use sea_orm::PaginatorTrait;
+use sea_orm::PrimaryKeyTrait;
+use sea_orm::QueryFilter;
+use sea_orm::QueryOrder;
+use sea_orm::QuerySelect;
+use serde::Deserialize;
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    Default,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(16))")]
+pub enum EmailStatus {
+    #[default]
+    #[sea_orm(string_value = "Pending")]
+    Pending,
+    #[sea_orm(string_value = "Sent")]
+    Sent,
+    /// Gave up after the maximum number of attempts
+    #[sea_orm(string_value = "Failed")]
+    Failed,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default, DeriveEntityModel)]
+#[sea_orm(table_name = "email_outbox")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    #[sea_orm(column_type = "String(StringLen::N(255))")]
+    pub recipient: String,
+    #[sea_orm(column_type = "String(StringLen::N(8))", nullable)]
+    pub language: Option<String>,
+    #[sea_orm(column_type = "String(StringLen::N(32))")]
+    pub template: String,
+    /// Template placeholders as a JSON object of strings
+    #[sea_orm(column_type = "Text")]
+    pub params: String,
+    pub status: EmailStatus,
+    pub attempts: i16,
+    pub next_attempt_time: DateTime,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub last_error: Option<String>,
+    pub created_time: DateTime,
+    pub sent_time: Option<DateTime>,
+    /// Not sent after this time, for emails that are useless once expired like one time passwords
+    pub expiry_time: Option<DateTime>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+pub async fn insert_email<T>(db: &T, email: Model) -> Result<Model, DbErr>
+where
+    T: ConnectionTrait,
+{
+    email.into_active_model().insert(db).await
+}
+
+pub async fn find_due_emails<T>(db: &T, now: DateTime, limit: u64) -> Result<Vec<Model>, DbErr>
+where
+    T: ConnectionTrait,
+{
+    Entity::find()
+        .filter(Column::Status.eq(EmailStatus::Pending))
+        .filter(Column::NextAttemptTime.lte(now))
+        .order_by_asc(Column::NextAttemptTime)
+        .limit(limit)
+        .all(db)
+        .await
+}
+
+/// Moves the next attempt to `lease_until` if nobody else did it first, so the email is sent once
+pub async fn claim_email<T>(db: &T, email: &Model, lease_until: DateTime) -> Result<bool, DbErr>
+where
+    T: ConnectionTrait,
+{
+    let result = Entity::update_many()
+        .col_expr(Column::NextAttemptTime, Expr::value(lease_until))
+        .filter(Column::Id.eq(email.id))
+        .filter(Column::Status.eq(EmailStatus::Pending))
+        .filter(Column::NextAttemptTime.eq(email.next_attempt_time))
+        .exec(db)
+        .await?;
+
+    Ok(result.rows_affected == 1)
+}
+
+pub async fn find_paged_emails<T>(
+    db: &T,
+    status: Option<EmailStatus>,
+    page: u64,
+    size: u64,
+) -> Result<(u64, Vec<Model>), DbErr>
+where
+    T: ConnectionTrait,
+{
+    let mut query = Entity::find().order_by_desc(Column::CreatedTime);
+    if let Some(status) = status {
+        query = query.filter(Column::Status.eq(status));
+    }
+
+    let paginator = query.paginate(db, size);
+    let total_size = paginator.num_items().await?;
+    let emails = paginator.fetch_page(page).await?;
+
+    Ok((total_size, emails))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use sea_orm::sqlx::types::chrono::Utc;
+
+    use crate::persistence::connect_test_db;
+
+    use super::*;
+
+    #[actix_web::test]
+    async fn test_due_email_is_claimed_once() {
+        let db = connect_test_db().await;
+
+        let now = Utc::now().naive_utc();
+        let email = insert_email(
+            &db,
+            Model {
+                id: Uuid::new_v4(),
+                recipient: "user@example.com".to_owned(),
+                template: "otp".to_owned(),
+                params: "{}".to_owned(),
+                next_attempt_time: now - Duration::from_secs(1),
+                created_time: now,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let due = find_due_emails(&db, now, 1000).await.unwrap();
+        assert!(due.iter().any(|due| due.id == email.id));
+
+        let lease_until = now + Duration::from_secs(60);
+        assert!(claim_email(&db, &email, lease_until).await.unwrap());
+        assert!(!claim_email(&db, &email, lease_until).await.unwrap());
+
+        let due = find_due_emails(&db, now, 1000).await.unwrap();
+        assert!(due.iter().all(|due| due.id != email.id));
+
+        Entity::delete_by_id(email.id).exec(&db).await.unwrap();
+    }
+}
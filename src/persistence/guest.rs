@@ -48,6 +48,9 @@ pub struct Model {
 
     #[sea_orm(column_type = "String(StringLen::N(16))", unique)]
     pub phone_number: Option<String>,
+
+    /// User that registered the guest for their own bookings, unset for guests added by admins
+    pub owner_user_id: Option<Uuid>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -56,6 +59,12 @@ pub enum Relation {
     Booking,
     #[sea_orm(has_many = "super::booking_guest::Entity")]
     BookingGuest,
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::OwnerUserId",
+        to = "super::user::Column::Id"
+    )]
+    Owner,
 }
 impl Related<super::booking_guest::Entity> for Entity {
     fn to() -> RelationDef {
@@ -109,6 +118,7 @@ pub async fn find_all_ids_by_criteria<T>(
     phone_number: Option<String>,
     date_of_birth: Option<Date>,
     ucn: Option<String>,
+    owner_user_id: Option<Uuid>,
 ) -> Result<Vec<Uuid>, DbErr>
 where
     T: ConnectionTrait,
@@ -137,6 +147,9 @@ where
     if let Some(some) = ucn {
         query = query.filter(crate::persistence::guest::Column::Ucn.eq(some))
     }
+    if let Some(some) = owner_user_id {
+        query = query.filter(crate::persistence::guest::Column::OwnerUserId.eq(some))
+    }
 
     let result = query
         .into_model::<GuestId>()
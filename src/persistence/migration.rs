@@ -0,0 +1,203 @@
+use log::info;
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, DbErr, Statement, TransactionTrait};
+use sea_orm_migration::{
+    prelude::{ForeignKey, ForeignKeyCreateStatement, IntoIden},
+    MigrationTrait, MigratorTrait,
+};
+
+mod m20261018_000001_initial_schema;
+mod m20261018_000002_indexes_and_foreign_keys;
+mod m20261018_000003_payment_status;
+mod m20261018_000004_booking_first_night_price;
+mod m20261018_000005_unique_room_number;
+mod m20261018_000006_email_outbox_expiry;
+
+/// Arbitrary key of the postgres advisory lock held while migrating
+const MIGRATION_LOCK_KEY: i64 = 0x686f74656c;
+
+pub struct Migrator;
+impl MigratorTrait for Migrator {
+    fn migrations() -> Vec<Box<dyn MigrationTrait>> {
+        vec![
+            Box::new(m20261018_000001_initial_schema::Migration),
+            Box::new(m20261018_000002_indexes_and_foreign_keys::Migration),
+            Box::new(m20261018_000003_payment_status::Migration),
+            Box::new(m20261018_000004_booking_first_night_price::Migration),
+            Box::new(m20261018_000005_unique_room_number::Migration),
+            Box::new(m20261018_000006_email_outbox_expiry::Migration),
+        ]
+    }
+}
+
+/// Named `fk-<table>-<column>` like the constraints sea-orm generated from the entity relations
+fn foreign_key<T, C, R, P>(
+    table: T,
+    column: C,
+    ref_table: R,
+    ref_column: P,
+) -> ForeignKeyCreateStatement
+where
+    T: IntoIden,
+    C: IntoIden,
+    R: IntoIden,
+    P: IntoIden,
+{
+    let table = table.into_iden();
+    let column = column.into_iden();
+
+    ForeignKey::create()
+        .name(format!("fk-{}-{}", table.to_string(), column.to_string()))
+        .from(table, column)
+        .to(ref_table.into_iden(), ref_column)
+        .to_owned()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MigrationCommand {
+    /// Applies the given number of pending migrations, all if `None`
+    Up(Option<u32>),
+    /// Rolls back the given number of applied migrations, all if `None`
+    Down(Option<u32>),
+    Status,
+}
+impl MigrationCommand {
+    pub const USAGE: &'static str = "Usage: migrate <up [steps] | down [steps] | status>";
+
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let steps = match args.get(1) {
+            Some(steps) => Some(
+                steps
+                    .parse()
+                    .map_err(|_| format!("Invalid number of steps '{steps}'"))?,
+            ),
+            None => None,
+        };
+        if args.len() > 2 {
+            return Err(Self::USAGE.to_owned());
+        }
+
+        match (args.first().map(String::as_str), steps) {
+            (Some("up"), steps) => Ok(Self::Up(steps)),
+            (Some("down"), steps) => Ok(Self::Down(steps)),
+            (Some("status"), None) => Ok(Self::Status),
+            _ => Err(Self::USAGE.to_owned()),
+        }
+    }
+}
+
+/// Runs the command in a single transaction, concurrent instances wait for each other on postgres
+pub async fn run_migrations(
+    db: &DatabaseConnection,
+    command: &MigrationCommand,
+) -> Result<(), DbErr> {
+    let transaction = db.begin().await?;
+    if transaction.get_database_backend() == DbBackend::Postgres {
+        transaction
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                "SELECT pg_advisory_xact_lock($1)",
+                [MIGRATION_LOCK_KEY.into()],
+            ))
+            .await?;
+    }
+
+    match command {
+        MigrationCommand::Up(steps) => Migrator::up(&transaction, *steps).await?,
+        MigrationCommand::Down(steps) => Migrator::down(&transaction, *steps).await?,
+        MigrationCommand::Status => {
+            for migration in Migrator::get_migration_with_status(&transaction).await? {
+                info!("Migration '{}': {}", migration.name(), migration.status());
+            }
+        }
+    }
+
+    transaction.commit().await
+}
+
+/// Names of the migrations that are not applied to the database yet
+pub async fn find_pending_migrations(db: &DatabaseConnection) -> Result<Vec<String>, DbErr> {
+    Ok(Migrator::get_pending_migrations(db)
+        .await?
+        .iter()
+        .map(|migration| migration.name().to_owned())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_orm_migration::SchemaManager;
+    use uuid::Uuid;
+
+    use crate::persistence::connect_test_db;
+
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_migration_command() {
+        assert_eq!(
+            MigrationCommand::parse(&args(&["up"])),
+            Ok(MigrationCommand::Up(None))
+        );
+        assert_eq!(
+            MigrationCommand::parse(&args(&["down", "1"])),
+            Ok(MigrationCommand::Down(Some(1)))
+        );
+        assert_eq!(
+            MigrationCommand::parse(&args(&["status"])),
+            Ok(MigrationCommand::Status)
+        );
+        assert!(MigrationCommand::parse(&args(&[])).is_err());
+        assert!(MigrationCommand::parse(&args(&["down", "x"])).is_err());
+        assert!(MigrationCommand::parse(&args(&["status", "1"])).is_err());
+        assert!(MigrationCommand::parse(&args(&["up", "1", "2"])).is_err());
+    }
+
+    #[actix_web::test]
+    async fn test_migrations_up_and_down_on_empty_database() {
+        let db = connect_test_db().await;
+
+        // Everything happens in a transaction that is rolled back at the end
+        let transaction = db.begin().await.unwrap();
+        if transaction.get_database_backend() == DbBackend::Postgres {
+            let schema = format!("migration_test_{}", Uuid::new_v4().simple());
+            transaction
+                .execute_unprepared(&format!(
+                    "CREATE SCHEMA {schema}; SET LOCAL search_path TO {schema}"
+                ))
+                .await
+                .unwrap();
+        } else {
+            // The in-memory test database belongs to this test alone
+            Migrator::down(&transaction, None).await.unwrap();
+        }
+
+        Migrator::up(&transaction, None).await.unwrap();
+        let manager = SchemaManager::new(&transaction);
+        assert!(manager.has_table("bookings").await.unwrap());
+        assert!(manager
+            .has_column("bookings", "reminder_sent_time")
+            .await
+            .unwrap());
+        assert!(Migrator::get_pending_migrations(&transaction)
+            .await
+            .unwrap()
+            .is_empty());
+
+        Migrator::down(&transaction, None).await.unwrap();
+        assert!(!manager.has_table("bookings").await.unwrap());
+        assert!(!manager.has_table("users").await.unwrap());
+        assert_eq!(
+            Migrator::get_pending_migrations(&transaction)
+                .await
+                .unwrap()
+                .len(),
+            Migrator::migrations().len()
+        );
+
+        transaction.rollback().await.unwrap();
+    }
+}
@@ -0,0 +1,675 @@
+use sea_orm::DbBackend;
+use sea_orm_migration::{prelude::*, schema::*};
+
+use super::foreign_key;
+use crate::persistence::data_migration;
+
+/// The schema as it was created from the entities before versioned migrations were introduced.
+/// Databases created back then are brought up to the same state, so every statement is idempotent.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+    Email,
+    Password,
+    Role,
+    Language,
+}
+
+#[derive(DeriveIden)]
+enum CancellationPolicies {
+    Table,
+    Id,
+    Name,
+    FreeCancellationDays,
+    PenaltyType,
+    PenaltyPercentage,
+}
+
+#[derive(DeriveIden)]
+enum Rooms {
+    Table,
+    Id,
+    Price,
+    Floor,
+    RoomNumber,
+    BathroomType,
+    IsDeleted,
+    CancellationPolicyId,
+}
+
+#[derive(DeriveIden)]
+enum Beds {
+    Table,
+    Id,
+    RoomId,
+    BedSize,
+    Count,
+    TotalCapacity,
+}
+
+#[derive(DeriveIden)]
+enum RoomPriceRules {
+    Table,
+    Id,
+    RoomId,
+    RuleType,
+    StartDate,
+    EndDate,
+    Weekdays,
+    Price,
+    CreatedTime,
+}
+
+#[derive(DeriveIden)]
+enum Otps {
+    Table,
+    Id,
+    UserId,
+    OtpCode,
+    Validity,
+}
+
+#[derive(DeriveIden)]
+enum Guests {
+    Table,
+    Id,
+    FirstName,
+    LastName,
+    DateOfBirth,
+    Ucn,
+    IdCardNumber,
+    IdCardIssueAuthority,
+    IdCardIssueDate,
+    IdCardValidity,
+    PhoneNumber,
+    OwnerUserId,
+}
+
+#[derive(DeriveIden)]
+enum Bookings {
+    Table,
+    Id,
+    MainGuestId,
+    RoomId,
+    AdminId,
+    UserId,
+    BookingTime,
+    PaymentTime,
+    StartDate,
+    EndDate,
+    TotalPrice,
+    Status,
+    CheckInTime,
+    CheckOutTime,
+    NoShowTime,
+    CancellationPolicyId,
+    CancellationPenalty,
+    CancellationRefund,
+    ReminderSentTime,
+}
+
+#[derive(DeriveIden)]
+enum BookingsGuests {
+    Table,
+    GuestId,
+    BookingId,
+}
+
+#[derive(DeriveIden)]
+enum BookingChanges {
+    Table,
+    Id,
+    BookingId,
+    AdminId,
+    ChangeTime,
+    PreviousRoomId,
+    PreviousStartDate,
+    PreviousEndDate,
+    PreviousTotalPrice,
+}
+
+#[derive(DeriveIden)]
+enum Payments {
+    Table,
+    Id,
+    BookingId,
+    AdminId,
+    PaymentType,
+    Amount,
+    Currency,
+    Method,
+    ProviderReference,
+    RefundedPaymentId,
+    PaymentTime,
+}
+
+#[derive(DeriveIden)]
+enum EmailOutbox {
+    Table,
+    Id,
+    Recipient,
+    Language,
+    Template,
+    Params,
+    Status,
+    Attempts,
+    NextAttemptTime,
+    LastError,
+    CreatedTime,
+    SentTime,
+}
+
+#[derive(DeriveIden)]
+enum InvalidatedToken {
+    Table,
+    Jwt,
+    Added,
+}
+
+#[derive(DeriveIden)]
+enum Comments {
+    Table,
+    Id,
+    RoomId,
+    UserId,
+    Content,
+    PostedTime,
+    UpdatedTime,
+}
+
+#[derive(DeriveIden)]
+enum DataMigrations {
+    Table,
+    Name,
+    AppliedTime,
+}
+
+async fn create_tables(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .create_table(
+            Table::create()
+                .table(Users::Table)
+                .if_not_exists()
+                .col(pk_uuid(Users::Id))
+                .col(string_uniq(Users::Email))
+                .col(string(Users::Password))
+                .col(string_len(Users::Role, 16))
+                .col(string_null(Users::Language))
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_table(
+            Table::create()
+                .table(CancellationPolicies::Table)
+                .if_not_exists()
+                .col(pk_uuid(CancellationPolicies::Id))
+                .col(string_len_uniq(CancellationPolicies::Name, 64))
+                .col(small_integer_null(
+                    CancellationPolicies::FreeCancellationDays,
+                ))
+                .col(string_len(CancellationPolicies::PenaltyType, 16))
+                .col(small_integer(CancellationPolicies::PenaltyPercentage))
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_table(
+            Table::create()
+                .table(Rooms::Table)
+                .if_not_exists()
+                .col(pk_uuid(Rooms::Id))
+                .col(big_integer(Rooms::Price))
+                .col(small_integer(Rooms::Floor))
+                .col(string_len(Rooms::RoomNumber, 16))
+                .col(string_len(Rooms::BathroomType, 16))
+                .col(boolean(Rooms::IsDeleted))
+                .col(uuid_null(Rooms::CancellationPolicyId))
+                .foreign_key(&mut foreign_key(
+                    Rooms::Table,
+                    Rooms::CancellationPolicyId,
+                    CancellationPolicies::Table,
+                    CancellationPolicies::Id,
+                ))
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_table(
+            Table::create()
+                .table(Beds::Table)
+                .if_not_exists()
+                .col(pk_uuid(Beds::Id))
+                .col(uuid(Beds::RoomId))
+                .col(string_len(Beds::BedSize, 16))
+                .col(small_integer(Beds::Count))
+                .col(small_integer(Beds::TotalCapacity))
+                .foreign_key(&mut foreign_key(
+                    Beds::Table,
+                    Beds::RoomId,
+                    Rooms::Table,
+                    Rooms::Id,
+                ))
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_table(
+            Table::create()
+                .table(RoomPriceRules::Table)
+                .if_not_exists()
+                .col(pk_uuid(RoomPriceRules::Id))
+                .col(uuid(RoomPriceRules::RoomId))
+                .col(string_len(RoomPriceRules::RuleType, 16))
+                .col(date_null(RoomPriceRules::StartDate))
+                .col(date_null(RoomPriceRules::EndDate))
+                .col(small_integer_null(RoomPriceRules::Weekdays))
+                .col(big_integer(RoomPriceRules::Price))
+                .col(date_time(RoomPriceRules::CreatedTime))
+                .foreign_key(&mut foreign_key(
+                    RoomPriceRules::Table,
+                    RoomPriceRules::RoomId,
+                    Rooms::Table,
+                    Rooms::Id,
+                ))
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_table(
+            Table::create()
+                .table(Otps::Table)
+                .if_not_exists()
+                .col(pk_uuid(Otps::Id))
+                .col(uuid_uniq(Otps::UserId))
+                .col(string(Otps::OtpCode))
+                .col(date_time(Otps::Validity))
+                .foreign_key(&mut foreign_key(
+                    Otps::Table,
+                    Otps::UserId,
+                    Users::Table,
+                    Users::Id,
+                ))
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_table(
+            Table::create()
+                .table(Guests::Table)
+                .if_not_exists()
+                .col(pk_uuid(Guests::Id))
+                .col(string_len(Guests::FirstName, 32))
+                .col(string_len(Guests::LastName, 32))
+                .col(date(Guests::DateOfBirth))
+                .col(string_len_null(Guests::Ucn, 16).unique_key())
+                .col(string_len_null(Guests::IdCardNumber, 16).unique_key())
+                .col(string_len_null(Guests::IdCardIssueAuthority, 32))
+                .col(date_null(Guests::IdCardIssueDate))
+                .col(date_null(Guests::IdCardValidity))
+                .col(string_len_null(Guests::PhoneNumber, 16).unique_key())
+                .col(uuid_null(Guests::OwnerUserId))
+                .foreign_key(&mut foreign_key(
+                    Guests::Table,
+                    Guests::OwnerUserId,
+                    Users::Table,
+                    Users::Id,
+                ))
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_table(
+            Table::create()
+                .table(Bookings::Table)
+                .if_not_exists()
+                .col(pk_uuid(Bookings::Id))
+                .col(uuid(Bookings::MainGuestId))
+                .col(uuid(Bookings::RoomId))
+                .col(uuid_null(Bookings::AdminId))
+                .col(uuid_null(Bookings::UserId))
+                .col(date_time(Bookings::BookingTime))
+                .col(date_time_null(Bookings::PaymentTime))
+                .col(date(Bookings::StartDate))
+                .col(date(Bookings::EndDate))
+                .col(big_integer(Bookings::TotalPrice))
+                .col(string_len(Bookings::Status, 16))
+                .col(date_time_null(Bookings::CheckInTime))
+                .col(date_time_null(Bookings::CheckOutTime))
+                .col(date_time_null(Bookings::NoShowTime))
+                .col(uuid_null(Bookings::CancellationPolicyId))
+                .col(big_integer_null(Bookings::CancellationPenalty))
+                .col(big_integer_null(Bookings::CancellationRefund))
+                .col(date_time_null(Bookings::ReminderSentTime))
+                .foreign_key(&mut foreign_key(
+                    Bookings::Table,
+                    Bookings::RoomId,
+                    Rooms::Table,
+                    Rooms::Id,
+                ))
+                .foreign_key(&mut foreign_key(
+                    Bookings::Table,
+                    Bookings::MainGuestId,
+                    Guests::Table,
+                    Guests::Id,
+                ))
+                .foreign_key(&mut foreign_key(
+                    Bookings::Table,
+                    Bookings::AdminId,
+                    Users::Table,
+                    Users::Id,
+                ))
+                .foreign_key(&mut foreign_key(
+                    Bookings::Table,
+                    Bookings::UserId,
+                    Users::Table,
+                    Users::Id,
+                ))
+                .foreign_key(&mut foreign_key(
+                    Bookings::Table,
+                    Bookings::CancellationPolicyId,
+                    CancellationPolicies::Table,
+                    CancellationPolicies::Id,
+                ))
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_table(
+            Table::create()
+                .table(BookingsGuests::Table)
+                .if_not_exists()
+                .col(uuid(BookingsGuests::GuestId))
+                .col(uuid(BookingsGuests::BookingId))
+                .primary_key(
+                    Index::create()
+                        .name("pk-bookings_guests")
+                        .col(BookingsGuests::GuestId)
+                        .col(BookingsGuests::BookingId),
+                )
+                .foreign_key(
+                    foreign_key(
+                        BookingsGuests::Table,
+                        BookingsGuests::BookingId,
+                        Bookings::Table,
+                        Bookings::Id,
+                    )
+                    .on_update(ForeignKeyAction::Cascade)
+                    .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    foreign_key(
+                        BookingsGuests::Table,
+                        BookingsGuests::GuestId,
+                        Guests::Table,
+                        Guests::Id,
+                    )
+                    .on_update(ForeignKeyAction::Cascade)
+                    .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_table(
+            Table::create()
+                .table(BookingChanges::Table)
+                .if_not_exists()
+                .col(pk_uuid(BookingChanges::Id))
+                .col(uuid(BookingChanges::BookingId))
+                .col(uuid(BookingChanges::AdminId))
+                .col(date_time(BookingChanges::ChangeTime))
+                .col(uuid(BookingChanges::PreviousRoomId))
+                .col(date(BookingChanges::PreviousStartDate))
+                .col(date(BookingChanges::PreviousEndDate))
+                .col(big_integer(BookingChanges::PreviousTotalPrice))
+                .foreign_key(&mut foreign_key(
+                    BookingChanges::Table,
+                    BookingChanges::BookingId,
+                    Bookings::Table,
+                    Bookings::Id,
+                ))
+                .foreign_key(&mut foreign_key(
+                    BookingChanges::Table,
+                    BookingChanges::AdminId,
+                    Users::Table,
+                    Users::Id,
+                ))
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_table(
+            Table::create()
+                .table(Payments::Table)
+                .if_not_exists()
+                .col(pk_uuid(Payments::Id))
+                .col(uuid(Payments::BookingId))
+                .col(uuid_null(Payments::AdminId))
+                .col(string_len(Payments::PaymentType, 16))
+                .col(big_integer(Payments::Amount))
+                .col(string_len(Payments::Currency, 3))
+                .col(string_len(Payments::Method, 16))
+                .col(string_len_null(Payments::ProviderReference, 128))
+                .col(uuid_null(Payments::RefundedPaymentId))
+                .col(date_time(Payments::PaymentTime))
+                .foreign_key(&mut foreign_key(
+                    Payments::Table,
+                    Payments::BookingId,
+                    Bookings::Table,
+                    Bookings::Id,
+                ))
+                .foreign_key(&mut foreign_key(
+                    Payments::Table,
+                    Payments::AdminId,
+                    Users::Table,
+                    Users::Id,
+                ))
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_table(
+            Table::create()
+                .table(EmailOutbox::Table)
+                .if_not_exists()
+                .col(pk_uuid(EmailOutbox::Id))
+                .col(string_len(EmailOutbox::Recipient, 255))
+                .col(string_len_null(EmailOutbox::Language, 8))
+                .col(string_len(EmailOutbox::Template, 32))
+                .col(text(EmailOutbox::Params))
+                .col(string_len(EmailOutbox::Status, 16))
+                .col(small_integer(EmailOutbox::Attempts))
+                .col(date_time(EmailOutbox::NextAttemptTime))
+                .col(text_null(EmailOutbox::LastError))
+                .col(date_time(EmailOutbox::CreatedTime))
+                .col(date_time_null(EmailOutbox::SentTime))
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_table(
+            Table::create()
+                .table(InvalidatedToken::Table)
+                .if_not_exists()
+                .col(string_len(InvalidatedToken::Jwt, 512).primary_key())
+                .col(date_time(InvalidatedToken::Added))
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_table(
+            Table::create()
+                .table(Comments::Table)
+                .if_not_exists()
+                .col(pk_uuid(Comments::Id))
+                .col(uuid(Comments::RoomId))
+                .col(uuid(Comments::UserId))
+                .col(string_len(Comments::Content, 256))
+                .col(date_time(Comments::PostedTime))
+                .col(date_time_null(Comments::UpdatedTime))
+                .foreign_key(&mut foreign_key(
+                    Comments::Table,
+                    Comments::RoomId,
+                    Rooms::Table,
+                    Rooms::Id,
+                ))
+                .foreign_key(&mut foreign_key(
+                    Comments::Table,
+                    Comments::UserId,
+                    Users::Table,
+                    Users::Id,
+                ))
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_table(
+            Table::create()
+                .table(DataMigrations::Table)
+                .if_not_exists()
+                .col(string_len(DataMigrations::Name, 128).primary_key())
+                .col(date_time(DataMigrations::AppliedTime))
+                .to_owned(),
+        )
+        .await
+}
+
+/// Columns that were added to existing tables after they were first created
+async fn add_legacy_columns(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    let columns = [
+        (
+            Bookings::Table.into_iden(),
+            date_time_null(Bookings::CheckInTime),
+        ),
+        (
+            Bookings::Table.into_iden(),
+            date_time_null(Bookings::CheckOutTime),
+        ),
+        (
+            Bookings::Table.into_iden(),
+            date_time_null(Bookings::NoShowTime),
+        ),
+        (
+            Rooms::Table.into_iden(),
+            uuid_null(Rooms::CancellationPolicyId),
+        ),
+        (
+            Bookings::Table.into_iden(),
+            uuid_null(Bookings::CancellationPolicyId),
+        ),
+        (
+            Bookings::Table.into_iden(),
+            big_integer_null(Bookings::CancellationPenalty),
+        ),
+        (
+            Bookings::Table.into_iden(),
+            big_integer_null(Bookings::CancellationRefund),
+        ),
+        (Guests::Table.into_iden(), uuid_null(Guests::OwnerUserId)),
+        (Users::Table.into_iden(), string_null(Users::Language)),
+        (
+            Bookings::Table.into_iden(),
+            date_time_null(Bookings::ReminderSentTime),
+        ),
+    ];
+
+    for (table, mut column) in columns {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(table)
+                    .add_column_if_not_exists(&mut column)
+                    .to_owned(),
+            )
+            .await?;
+    }
+
+    manager
+        .alter_table(
+            Table::alter()
+                .table(Bookings::Table)
+                .modify_column(ColumnDef::new(Bookings::AdminId).null())
+                .to_owned(),
+        )
+        .await
+}
+
+/// Booking end dates used to be the last night of the stay, they are now the departure date
+async fn migrate_to_exclusive_end_dates(manager: &SchemaManager<'_>) -> Result<bool, DbErr> {
+    let db = manager.get_connection();
+    let builder = db.get_database_backend();
+    let statements = vec![
+        builder.build(
+            Query::update()
+                .table(Bookings::Table)
+                .value(Bookings::EndDate, Expr::col(Bookings::EndDate).add(1)),
+        ),
+        builder.build(Query::update().table(BookingChanges::Table).value(
+            BookingChanges::PreviousEndDate,
+            Expr::col(BookingChanges::PreviousEndDate).add(1),
+        )),
+    ];
+
+    data_migration::apply_once(db, "exclusive_booking_end_dates", statements).await
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        create_tables(manager).await?;
+        // Only postgres was supported before the migrations, so other databases are always new
+        if manager.get_database_backend() == DbBackend::Postgres {
+            add_legacy_columns(manager).await?;
+            migrate_to_exclusive_end_dates(manager).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let tables = [
+            DataMigrations::Table.into_iden(),
+            Comments::Table.into_iden(),
+            InvalidatedToken::Table.into_iden(),
+            EmailOutbox::Table.into_iden(),
+            Payments::Table.into_iden(),
+            BookingChanges::Table.into_iden(),
+            BookingsGuests::Table.into_iden(),
+            Bookings::Table.into_iden(),
+            Guests::Table.into_iden(),
+            Otps::Table.into_iden(),
+            RoomPriceRules::Table.into_iden(),
+            Beds::Table.into_iden(),
+            Rooms::Table.into_iden(),
+            CancellationPolicies::Table.into_iden(),
+            Users::Table.into_iden(),
+        ];
+
+        for table in tables {
+            manager
+                .drop_table(Table::drop().table(table).if_exists().to_owned())
+                .await?;
+        }
+
+        Ok(())
+    }
+}
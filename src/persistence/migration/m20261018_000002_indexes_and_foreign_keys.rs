@@ -0,0 +1,471 @@
+use sea_orm::{DbBackend, Statement};
+use sea_orm_migration::prelude::*;
+
+use super::foreign_key;
+
+/// Secondary indexes for the frequent lookups, and the foreign keys of columns that were added to
+/// tables created before the referenced table existed.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum CancellationPolicies {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Rooms {
+    Table,
+    CancellationPolicyId,
+}
+
+#[derive(DeriveIden)]
+enum Beds {
+    Table,
+    RoomId,
+}
+
+#[derive(DeriveIden)]
+enum RoomPriceRules {
+    Table,
+    RoomId,
+}
+
+#[derive(DeriveIden)]
+enum Guests {
+    Table,
+    FirstName,
+    LastName,
+    OwnerUserId,
+}
+
+#[derive(DeriveIden)]
+enum Bookings {
+    Table,
+    MainGuestId,
+    RoomId,
+    UserId,
+    StartDate,
+    EndDate,
+    CancellationPolicyId,
+}
+
+#[derive(DeriveIden)]
+enum BookingsGuests {
+    Table,
+    BookingId,
+}
+
+#[derive(DeriveIden)]
+enum BookingChanges {
+    Table,
+    BookingId,
+}
+
+#[derive(DeriveIden)]
+enum Payments {
+    Table,
+    BookingId,
+}
+
+#[derive(DeriveIden)]
+enum EmailOutbox {
+    Table,
+    Status,
+    NextAttemptTime,
+}
+
+#[derive(DeriveIden)]
+enum InvalidatedToken {
+    Table,
+    Added,
+}
+
+#[derive(DeriveIden)]
+enum Comments {
+    Table,
+    RoomId,
+    PostedTime,
+}
+
+struct IndexDef {
+    table: DynIden,
+    columns: Vec<DynIden>,
+}
+impl IndexDef {
+    fn new<T, C>(table: T, columns: Vec<C>) -> Self
+    where
+        T: IntoIden,
+        C: IntoIden,
+    {
+        Self {
+            table: table.into_iden(),
+            columns: columns.into_iter().map(IntoIden::into_iden).collect(),
+        }
+    }
+
+    /// Named `idx-<table>-<columns>`
+    fn name(&self) -> String {
+        self.columns
+            .iter()
+            .fold(format!("idx-{}", self.table.to_string()), |name, column| {
+                format!("{}-{}", name, column.to_string())
+            })
+    }
+
+    fn create(&self) -> IndexCreateStatement {
+        let mut index = Index::create();
+        index
+            .name(self.name())
+            .table(self.table.clone())
+            .if_not_exists();
+        for column in &self.columns {
+            index.col(column.clone());
+        }
+
+        index.to_owned()
+    }
+
+    fn drop(&self) -> IndexDropStatement {
+        Index::drop()
+            .name(self.name())
+            .table(self.table.clone())
+            .if_exists()
+            .to_owned()
+    }
+}
+
+fn indexes() -> Vec<IndexDef> {
+    vec![
+        IndexDef::new(
+            Bookings::Table,
+            vec![Bookings::RoomId, Bookings::StartDate, Bookings::EndDate],
+        ),
+        IndexDef::new(Bookings::Table, vec![Bookings::UserId]),
+        IndexDef::new(Bookings::Table, vec![Bookings::MainGuestId]),
+        IndexDef::new(BookingsGuests::Table, vec![BookingsGuests::BookingId]),
+        IndexDef::new(BookingChanges::Table, vec![BookingChanges::BookingId]),
+        IndexDef::new(Payments::Table, vec![Payments::BookingId]),
+        IndexDef::new(
+            Comments::Table,
+            vec![Comments::RoomId, Comments::PostedTime],
+        ),
+        IndexDef::new(Beds::Table, vec![Beds::RoomId]),
+        IndexDef::new(RoomPriceRules::Table, vec![RoomPriceRules::RoomId]),
+        IndexDef::new(Guests::Table, vec![Guests::FirstName]),
+        IndexDef::new(Guests::Table, vec![Guests::LastName]),
+        IndexDef::new(InvalidatedToken::Table, vec![InvalidatedToken::Added]),
+        IndexDef::new(
+            EmailOutbox::Table,
+            vec![EmailOutbox::Status, EmailOutbox::NextAttemptTime],
+        ),
+    ]
+}
+
+struct ForeignKeyDef {
+    table: DynIden,
+    column: DynIden,
+    ref_table: DynIden,
+    ref_column: DynIden,
+}
+impl ForeignKeyDef {
+    fn new<T, C, R, P>(table: T, column: C, ref_table: R, ref_column: P) -> Self
+    where
+        T: IntoIden,
+        C: IntoIden,
+        R: IntoIden,
+        P: IntoIden,
+    {
+        Self {
+            table: table.into_iden(),
+            column: column.into_iden(),
+            ref_table: ref_table.into_iden(),
+            ref_column: ref_column.into_iden(),
+        }
+    }
+
+    fn name(&self) -> String {
+        format!("fk-{}-{}", self.table.to_string(), self.column.to_string())
+    }
+
+    fn create(&self) -> ForeignKeyCreateStatement {
+        foreign_key(
+            self.table.clone(),
+            self.column.clone(),
+            self.ref_table.clone(),
+            self.ref_column.clone(),
+        )
+    }
+
+    async fn exists(&self, manager: &SchemaManager<'_>) -> Result<bool, DbErr> {
+        let db = manager.get_connection();
+        let statement = match db.get_database_backend() {
+            DbBackend::Sqlite => Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "SELECT 1 FROM pragma_foreign_key_list(?) WHERE \"from\" = ? AND \"table\" = ?",
+                [
+                    self.table.to_string().into(),
+                    self.column.to_string().into(),
+                    self.ref_table.to_string().into(),
+                ],
+            ),
+            backend => Statement::from_sql_and_values(
+                backend,
+                "SELECT 1 FROM information_schema.table_constraints \
+                WHERE constraint_type = 'FOREIGN KEY' AND table_schema = current_schema() \
+                AND table_name = $1 AND constraint_name = $2",
+                [self.table.to_string().into(), self.name().into()],
+            ),
+        };
+
+        Ok(db.query_one(statement).await?.is_some())
+    }
+}
+
+/// Declared in the entity relations, but missing from databases where the column was added later
+fn foreign_keys() -> Vec<ForeignKeyDef> {
+    vec![
+        ForeignKeyDef::new(
+            Rooms::Table,
+            Rooms::CancellationPolicyId,
+            CancellationPolicies::Table,
+            CancellationPolicies::Id,
+        ),
+        ForeignKeyDef::new(
+            Bookings::Table,
+            Bookings::CancellationPolicyId,
+            CancellationPolicies::Table,
+            CancellationPolicies::Id,
+        ),
+        ForeignKeyDef::new(Guests::Table, Guests::OwnerUserId, Users::Table, Users::Id),
+    ]
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for index in indexes() {
+            manager.create_index(index.create()).await?;
+        }
+
+        for foreign_key in foreign_keys() {
+            if !foreign_key.exists(manager).await? {
+                manager.create_foreign_key(foreign_key.create()).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Only the indexes are dropped, the foreign keys are part of the initial schema
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for index in indexes() {
+            manager.drop_index(index.drop()).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::{
+        sqlx::types::chrono::Utc, ActiveModelTrait, ColumnTrait, ConnectionTrait,
+        DatabaseTransaction, EntityTrait, IntoActiveModel, QueryFilter, QueryOrder, QueryTrait,
+        TransactionTrait,
+    };
+    use uuid::Uuid;
+
+    use crate::{
+        persistence::{
+            bed,
+            booking::{self, BookingStatus},
+            comment, connect_test_db, guest, invalidated_token, room,
+            test_data::{insert_guest, insert_room, new_booking, unique_room_number},
+        },
+        util::add_days,
+    };
+
+    use super::*;
+
+    async fn explain<Q>(db: &DatabaseTransaction, query: Q) -> String
+    where
+        Q: QueryTrait,
+    {
+        let backend = db.get_database_backend();
+        let statement = query.build(backend);
+        let (explain, plan_column) = match backend {
+            DbBackend::Sqlite => ("EXPLAIN QUERY PLAN", "detail"),
+            _ => ("EXPLAIN", "QUERY PLAN"),
+        };
+        let values = statement.values.map(|values| values.0).unwrap_or_default();
+        let rows = db
+            .query_all(Statement::from_sql_and_values(
+                backend,
+                format!("{} {}", explain, statement.sql),
+                values,
+            ))
+            .await
+            .unwrap();
+
+        rows.iter()
+            .map(|row| row.try_get::<String>("", plan_column).unwrap())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[actix_web::test]
+    async fn test_hot_queries_use_indexes() {
+        let db = connect_test_db().await;
+        let transaction = db.begin().await.unwrap();
+        if transaction.get_database_backend() == DbBackend::Postgres {
+            // The test tables are too small for the planner to prefer an index on its own
+            transaction
+                .execute_unprepared("SET LOCAL enable_seqscan = off")
+                .await
+                .unwrap();
+        }
+        let id = Uuid::new_v4();
+        let today = Utc::now().date_naive();
+
+        let plan = explain(
+            &transaction,
+            booking::Entity::find()
+                .filter(booking::Column::RoomId.eq(id))
+                .filter(booking::Column::StartDate.lt(today))
+                .filter(booking::Column::EndDate.gt(today)),
+        )
+        .await;
+        assert!(
+            plan.contains("idx-bookings-room_id-start_date-end_date"),
+            "{plan}"
+        );
+
+        let plan = explain(
+            &transaction,
+            booking::Entity::find().filter(booking::Column::UserId.eq(id)),
+        )
+        .await;
+        assert!(plan.contains("idx-bookings-user_id"), "{plan}");
+
+        let plan = explain(
+            &transaction,
+            comment::Entity::find()
+                .filter(comment::Column::RoomId.eq(id))
+                .order_by_desc(comment::Column::PostedTime),
+        )
+        .await;
+        assert!(plan.contains("idx-comments-room_id-posted_time"), "{plan}");
+
+        let plan = explain(
+            &transaction,
+            bed::Entity::find().filter(bed::Column::RoomId.eq(id)),
+        )
+        .await;
+        assert!(plan.contains("idx-beds-room_id"), "{plan}");
+
+        let plan = explain(
+            &transaction,
+            guest::Entity::find().filter(guest::Column::LastName.eq("Smith")),
+        )
+        .await;
+        assert!(plan.contains("idx-guests-last_name"), "{plan}");
+
+        let plan = explain(
+            &transaction,
+            guest::Entity::find().filter(guest::Column::FirstName.eq("John")),
+        )
+        .await;
+        assert!(plan.contains("idx-guests-first_name"), "{plan}");
+
+        let plan = explain(
+            &transaction,
+            invalidated_token::Entity::find()
+                .filter(invalidated_token::Column::Added.lte(Utc::now().naive_utc())),
+        )
+        .await;
+        assert!(plan.contains("idx-invalidated_token-added"), "{plan}");
+
+        transaction.rollback().await.unwrap();
+    }
+
+    #[actix_web::test]
+    async fn test_foreign_keys_are_enforced() {
+        let db = connect_test_db().await;
+        let manager = SchemaManager::new(&db);
+
+        for foreign_key in foreign_keys() {
+            assert!(foreign_key.exists(&manager).await.unwrap());
+        }
+        if db.get_database_backend() == DbBackend::Sqlite {
+            let row = db
+                .query_one(Statement::from_string(
+                    DbBackend::Sqlite,
+                    "PRAGMA foreign_keys",
+                ))
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(row.try_get_by_index::<i32>(0).unwrap(), 1);
+        }
+
+        let guest = guest::Model {
+            id: Uuid::new_v4(),
+            first_name: "John".to_owned(),
+            last_name: "Smith".to_owned(),
+            date_of_birth: Utc::now().date_naive(),
+            owner_user_id: Some(Uuid::new_v4()),
+            ..Default::default()
+        };
+        assert!(guest.clone().into_active_model().insert(&db).await.is_err());
+        let guest = guest::Model {
+            owner_user_id: None,
+            ..guest
+        };
+        assert!(guest.into_active_model().insert(&db).await.is_ok());
+
+        let room = insert_room(&db, Uuid::new_v4()).await;
+        let room_with_policy = room::Model {
+            id: Uuid::new_v4(),
+            room_number: unique_room_number(),
+            cancellation_policy_id: Some(Uuid::new_v4()),
+            ..room.clone()
+        };
+        assert!(room_with_policy
+            .into_active_model()
+            .insert(&db)
+            .await
+            .is_err());
+
+        let start_date = Utc::now().date_naive();
+        let booking = booking::Model {
+            room_id: room.id,
+            admin_id: None,
+            cancellation_policy_id: Some(Uuid::new_v4()),
+            ..new_booking(
+                BookingStatus::Unpaid,
+                start_date,
+                add_days(start_date, 1).unwrap(),
+            )
+        };
+        insert_guest(&db, booking.main_guest_id).await;
+        assert!(booking
+            .clone()
+            .into_active_model()
+            .insert(&db)
+            .await
+            .is_err());
+        let booking = booking::Model {
+            cancellation_policy_id: None,
+            ..booking
+        };
+        assert!(booking.into_active_model().insert(&db).await.is_ok());
+    }
+}
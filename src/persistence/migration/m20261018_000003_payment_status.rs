@@ -0,0 +1,37 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+/// Card payments and refunds are recorded as pending before the payment provider is called, and
+/// completed or failed once it answers. Existing payments are all completed.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[derive(DeriveIden)]
+enum Payments {
+    Table,
+    Status,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Payments::Table)
+                    .add_column(string_len(Payments::Status, 16).default("Completed"))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Payments::Table)
+                    .drop_column(Payments::Status)
+                    .to_owned(),
+            )
+            .await
+    }
+}
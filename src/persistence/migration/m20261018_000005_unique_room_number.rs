@@ -0,0 +1,43 @@
+use sea_orm_migration::prelude::*;
+
+/// Room numbers of the rooms that are not deleted are unique, so two concurrent requests can't
+/// both pass the room number check. Deleted rooms keep their number until they are restored.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+const INDEX_NAME: &str = "idx-rooms-room_number-not_deleted";
+
+#[derive(DeriveIden)]
+enum Rooms {
+    Table,
+    RoomNumber,
+    IsDeleted,
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_index(
+                Index::create()
+                    .name(INDEX_NAME)
+                    .table(Rooms::Table)
+                    .col(Rooms::RoomNumber)
+                    .unique()
+                    .and_where(Expr::col(Rooms::IsDeleted).eq(false))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name(INDEX_NAME)
+                    .table(Rooms::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,194 @@
+use sea_orm::prelude::DateTime;
+use sea_orm::prelude::StringLen;
+use sea_orm::sea_query::Expr;
+use sea_orm::sea_query::Query;
+use sea_orm::sea_query::SimpleExpr;
+use sea_orm::ActiveModelBehavior;
+use sea_orm::ColumnTrait;
+use sea_orm::ConnectionTrait;
+use sea_orm::DbErr;
+use sea_orm::DeriveActiveEnum;
+use sea_orm::DeriveEntityModel;
+use sea_orm::DerivePrimaryKey;
+use sea_orm::DeriveRelation;
+use sea_orm::EntityTrait;
+use sea_orm::EnumIter;
+use sea_orm::PrimaryKeyTrait;
+use sea_orm::QueryFilter;
+use sea_orm::QueryOrder;
+use sea_orm::Related;
+use sea_orm::RelationDef;
+use sea_orm::RelationTrait;
+use serde::Deserialize;
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    Default,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(16))")]
+pub enum PaymentMethod {
+    #[default]
+    #[sea_orm(string_value = "Cash")]
+    Cash,
+    #[sea_orm(string_value = "Card")]
+    Card,
+    #[sea_orm(string_value = "BankTransfer")]
+    BankTransfer,
+}
+
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    Default,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(16))")]
+pub enum PaymentType {
+    #[default]
+    #[sea_orm(string_value = "Payment")]
+    Payment,
+    #[sea_orm(string_value = "Refund")]
+    Refund,
+}
+
+/// Card payments and refunds stay pending until the payment provider answers
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    Default,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(16))")]
+pub enum PaymentStatus {
+    #[sea_orm(string_value = "Pending")]
+    Pending,
+    #[default]
+    #[sea_orm(string_value = "Completed")]
+    Completed,
+    #[sea_orm(string_value = "Failed")]
+    Failed,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default, DeriveEntityModel)]
+#[sea_orm(table_name = "payments")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub booking_id: Uuid,
+    pub admin_id: Option<Uuid>,
+    pub payment_type: PaymentType,
+    pub amount: i64,
+    #[sea_orm(column_type = "String(StringLen::N(3))")]
+    pub currency: String,
+    pub method: PaymentMethod,
+    #[sea_orm(column_type = "String(StringLen::N(128))", nullable)]
+    pub provider_reference: Option<String>,
+    pub refunded_payment_id: Option<Uuid>,
+    pub payment_time: DateTime,
+    pub status: PaymentStatus,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::booking::Entity",
+        from = "Column::BookingId",
+        to = "super::booking::Column::Id"
+    )]
+    Booking,
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::AdminId",
+        to = "super::user::Column::Id"
+    )]
+    Admin,
+}
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Related<super::booking::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Booking.def()
+    }
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Admin.def()
+    }
+}
+
+impl Model {
+    pub fn signed_amount(&self) -> i64 {
+        match self.payment_type {
+            PaymentType::Payment => self.amount,
+            PaymentType::Refund => -self.amount,
+        }
+    }
+}
+
+pub async fn find_payments_for_booking<T>(db: &T, booking_id: Uuid) -> Result<Vec<Model>, DbErr>
+where
+    T: ConnectionTrait,
+{
+    Entity::find()
+        .filter(Column::BookingId.eq(booking_id))
+        .order_by_asc(Column::PaymentTime)
+        .all(db)
+        .await
+}
+
+pub async fn update_payment_status<T>(
+    db: &T,
+    payment_id: Uuid,
+    status: PaymentStatus,
+    provider_reference: Option<String>,
+) -> Result<(), DbErr>
+where
+    T: ConnectionTrait,
+{
+    Entity::update_many()
+        .col_expr(Column::Status, Expr::value(status))
+        .col_expr(Column::ProviderReference, Expr::value(provider_reference))
+        .filter(Column::Id.eq(payment_id))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+pub fn booking_has_payments_expr() -> SimpleExpr {
+    Expr::exists(
+        Query::select()
+            .expr(Expr::val(1))
+            .from(Entity)
+            .and_where(
+                Expr::col((Entity, Column::BookingId))
+                    .equals((super::booking::Entity, super::booking::Column::Id)),
+            )
+            .and_where(Expr::col((Entity, Column::Status)).ne(PaymentStatus::Failed))
+            .to_owned(),
+    )
+}
@@ -1,23 +1,36 @@
+use sea_orm::prelude::Date;
 use sea_orm::prelude::StringLen;
+use sea_orm::sea_query::Expr;
+use sea_orm::sea_query::Func;
+use sea_orm::sea_query::Query;
+use sea_orm::sea_query::SimpleExpr;
 use sea_orm::ColumnTrait;
 use sea_orm::ConnectionTrait;
 use sea_orm::DbErr;
 use sea_orm::DerivePrimaryKey;
 use sea_orm::EntityTrait;
 use sea_orm::FromQueryResult;
+use sea_orm::JoinType;
 use sea_orm::ModelTrait;
+use sea_orm::Order;
+use sea_orm::PaginatorTrait;
 use sea_orm::PrimaryKeyTrait;
 use sea_orm::QueryFilter;
+use sea_orm::QueryOrder;
 use sea_orm::QuerySelect;
 use sea_orm::Related;
 use sea_orm::RelationDef;
 use sea_orm::RelationTrait;
+use sea_orm::Select;
 use sea_orm::{ActiveModelBehavior, DeriveActiveEnum, DeriveEntityModel, DeriveRelation, EnumIter};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
-use crate::persistence::bed;
+use crate::persistence::{
+    bed::{self, BedSize},
+    booking,
+};
 
 #[derive(
     Clone,
@@ -51,6 +64,7 @@ pub struct Model {
     pub room_number: String,
     pub bathroom_type: BathroomType,
     pub is_deleted: bool,
+    pub cancellation_policy_id: Option<Uuid>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -61,6 +75,14 @@ pub enum Relation {
     Booking,
     #[sea_orm(has_many = "super::comment::Entity")]
     Comment,
+    #[sea_orm(has_many = "super::room_price_rule::Entity")]
+    RoomPriceRule,
+    #[sea_orm(
+        belongs_to = "super::cancellation_policy::Entity",
+        from = "Column::CancellationPolicyId",
+        to = "super::cancellation_policy::Column::Id"
+    )]
+    CancellationPolicy,
 }
 impl ActiveModelBehavior for ActiveModel {}
 
@@ -79,6 +101,11 @@ impl Related<super::comment::Entity> for Entity {
         Relation::Comment.def()
     }
 }
+impl Related<super::room_price_rule::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RoomPriceRule.def()
+    }
+}
 
 pub async fn find_first_by_room_number_not_deleted<T>(
     db: &T,
@@ -116,23 +143,156 @@ where
     Ok(Some((room, beds)))
 }
 
-pub async fn find_all_room_ids_not_deleted<T>(db: &T) -> Result<Vec<Uuid>, DbErr>
+pub async fn lock_room_by_id<T>(db: &T, room_id: Uuid) -> Result<Option<Model>, DbErr>
 where
     T: ConnectionTrait,
 {
-    #[derive(Debug, FromQueryResult)]
-    struct RoomId {
-        r_id: Uuid,
+    Entity::find_by_id(room_id).lock_exclusive().one(db).await
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum RoomSortField {
+    Price,
+    Floor,
+    Capacity,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+pub enum SortOrder {
+    #[default]
+    Ascending,
+    Descending,
+}
+impl From<SortOrder> for Order {
+    fn from(value: SortOrder) -> Self {
+        match value {
+            SortOrder::Ascending => Order::Asc,
+            SortOrder::Descending => Order::Desc,
+        }
     }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RoomFilter {
+    pub include_deleted: bool,
+    pub unoccupied_between: Option<(Date, Date)>,
+    pub room_number_prefix: Option<String>,
+    pub bathroom_type: Option<BathroomType>,
+    pub bed_sizes: Vec<BedSize>,
+    pub minimum_floor: Option<i16>,
+    pub maximum_floor: Option<i16>,
+    pub minimum_price: Option<i64>,
+    pub maximum_price: Option<i64>,
+    pub minimum_capacity: Option<i16>,
+    pub maximum_capacity: Option<i16>,
+}
 
-    Ok(Entity::find()
+#[derive(Debug, FromQueryResult)]
+pub struct RoomWithCapacity {
+    pub id: Uuid,
+    pub price: i64,
+    pub floor: i16,
+    pub room_number: String,
+    pub bathroom_type: BathroomType,
+    pub is_deleted: bool,
+    pub cancellation_policy_id: Option<Uuid>,
+    pub capacity: i64,
+}
+
+fn has_bed_size_expr(bed_size: BedSize) -> SimpleExpr {
+    Expr::exists(
+        Query::select()
+            .expr(Expr::val(1))
+            .from(bed::Entity)
+            .and_where(Expr::col((bed::Entity, bed::Column::RoomId)).equals((Entity, Column::Id)))
+            .and_where(bed::Column::BedSize.eq(bed_size))
+            .to_owned(),
+    )
+}
+
+fn apply_room_filter(
+    mut query: Select<Entity>,
+    filter: &RoomFilter,
+    capacity: &SimpleExpr,
+) -> Select<Entity> {
+    if !filter.include_deleted {
+        query = query.filter(Column::IsDeleted.eq(false));
+    }
+    if let Some((start_date, end_date)) = filter.unoccupied_between {
+        query = query.filter(booking::room_occupied_for_period_expr(start_date, end_date).not());
+    }
+    if let Some(prefix) = &filter.room_number_prefix {
+        query = query.filter(Column::RoomNumber.starts_with(prefix));
+    }
+    if let Some(bathroom_type) = &filter.bathroom_type {
+        query = query.filter(Column::BathroomType.eq(bathroom_type.clone()));
+    }
+    for bed_size in &filter.bed_sizes {
+        query = query.filter(has_bed_size_expr(bed_size.clone()));
+    }
+    if let Some(min) = filter.minimum_floor {
+        query = query.filter(Column::Floor.gte(min));
+    }
+    if let Some(max) = filter.maximum_floor {
+        query = query.filter(Column::Floor.lte(max));
+    }
+    if let Some(min) = filter.minimum_price {
+        query = query.filter(Column::Price.gte(min));
+    }
+    if let Some(max) = filter.maximum_price {
+        query = query.filter(Column::Price.lte(max));
+    }
+    if let Some(min) = filter.minimum_capacity {
+        query = query.having(Expr::expr(capacity.clone()).gte(min));
+    }
+    if let Some(max) = filter.maximum_capacity {
+        query = query.having(Expr::expr(capacity.clone()).lte(max));
+    }
+
+    query
+}
+
+pub async fn find_paged_rooms<T>(
+    db: &T,
+    filter: &RoomFilter,
+    sort_by: Option<RoomSortField>,
+    sort_order: SortOrder,
+    page: u64,
+    size: u64,
+) -> Result<(u64, Vec<RoomWithCapacity>), DbErr>
+where
+    T: ConnectionTrait,
+{
+    let capacity: SimpleExpr =
+        Func::coalesce([bed::Column::TotalCapacity.sum(), Expr::val(0).into()]).into();
+
+    let query = Entity::find()
         .select_only()
-        .column_as(Column::Id, "r_id")
-        .filter(Column::IsDeleted.eq(false))
-        .into_model::<RoomId>()
-        .all(db)
-        .await?
-        .iter()
-        .map(|r| r.r_id)
-        .collect())
+        .columns([
+            Column::Id,
+            Column::Price,
+            Column::Floor,
+            Column::RoomNumber,
+            Column::BathroomType,
+            Column::IsDeleted,
+            Column::CancellationPolicyId,
+        ])
+        .column_as(capacity.clone(), "capacity")
+        .join(JoinType::LeftJoin, Relation::Bed.def())
+        .group_by(Column::Id);
+
+    let query = apply_room_filter(query, filter, &capacity);
+    let query = match sort_by {
+        Some(RoomSortField::Price) => query.order_by(Column::Price, sort_order.into()),
+        Some(RoomSortField::Floor) => query.order_by(Column::Floor, sort_order.into()),
+        Some(RoomSortField::Capacity) => query.order_by(capacity, sort_order.into()),
+        None => query.order_by(Column::RoomNumber, sort_order.into()),
+    }
+    .order_by_asc(Column::Id);
+
+    let paginator = query.into_model::<RoomWithCapacity>().paginate(db, size);
+    let count = paginator.num_items().await?;
+    let rooms = paginator.fetch_page(page).await?;
+
+    Ok((count, rooms))
 }
@@ -0,0 +1,119 @@
+use sea_orm::prelude::Date;
+use sea_orm::prelude::DateTime;
+use sea_orm::prelude::StringLen;
+use sea_orm::ColumnTrait;
+use sea_orm::Condition;
+use sea_orm::ConnectionTrait;
+use sea_orm::DbErr;
+use sea_orm::DerivePrimaryKey;
+use sea_orm::EntityTrait;
+use sea_orm::PrimaryKeyTrait;
+use sea_orm::QueryFilter;
+use sea_orm::QueryOrder;
+use sea_orm::Related;
+use sea_orm::RelationDef;
+use sea_orm::RelationTrait;
+use sea_orm::{ActiveModelBehavior, DeriveActiveEnum, DeriveEntityModel, DeriveRelation, EnumIter};
+use serde::Deserialize;
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Kind of a price rule, more specific kinds take precedence when several rules match a night
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Default,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(16))")]
+pub enum PriceRuleType {
+    /// Applies on the given weekdays all year round
+    #[default]
+    #[sea_orm(string_value = "Weekday")]
+    Weekday,
+    /// Applies between the start and end date, optionally only on the given weekdays
+    #[sea_orm(string_value = "Season")]
+    Season,
+    /// Applies to a single date
+    #[sea_orm(string_value = "Date")]
+    Date,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default, DeriveEntityModel)]
+#[sea_orm(table_name = "room_price_rules")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub room_id: Uuid,
+    pub rule_type: PriceRuleType,
+    pub start_date: Option<Date>,
+    pub end_date: Option<Date>,
+    /// Bitmask of weekdays starting from Monday as the lowest bit
+    pub weekdays: Option<i16>,
+    pub price: i64,
+    pub created_time: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::room::Entity",
+        from = "Column::RoomId",
+        to = "super::room::Column::Id"
+    )]
+    Room,
+}
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Related<super::room::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Room.def()
+    }
+}
+
+pub async fn find_rules_for_room<T>(db: &T, room_id: Uuid) -> Result<Vec<Model>, DbErr>
+where
+    T: ConnectionTrait,
+{
+    Entity::find()
+        .filter(Column::RoomId.eq(room_id))
+        .order_by_asc(Column::CreatedTime)
+        .all(db)
+        .await
+}
+
+/// Finds the rules of a room that can apply to a night between `first_night` and `last_night`
+pub async fn find_rules_for_room_in_range<T>(
+    db: &T,
+    room_id: Uuid,
+    first_night: Date,
+    last_night: Date,
+) -> Result<Vec<Model>, DbErr>
+where
+    T: ConnectionTrait,
+{
+    Entity::find()
+        .filter(Column::RoomId.eq(room_id))
+        .filter(
+            Condition::any()
+                .add(Column::StartDate.is_null())
+                .add(Column::StartDate.lte(last_night)),
+        )
+        .filter(
+            Condition::any()
+                .add(Column::EndDate.is_null())
+                .add(Column::EndDate.gte(first_night)),
+        )
+        .order_by_asc(Column::CreatedTime)
+        .all(db)
+        .await
+}
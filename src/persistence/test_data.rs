@@ -0,0 +1,171 @@
+use sea_orm::{
+    prelude::Date, sqlx::types::chrono::Utc, ActiveModelTrait, ConnectionTrait, DatabaseConnection,
+    IntoActiveModel,
+};
+use uuid::Uuid;
+
+use crate::{
+    persistence::{
+        bed::{self, BedSize},
+        booking::{self, BookingStatus},
+        booking_guest, guest,
+        payment::{self, PaymentMethod, PaymentType},
+        room::{self, BathroomType},
+        user::{self, Role},
+    },
+    util::{add_days, sub_days},
+};
+
+pub async fn insert_admin(db: &DatabaseConnection, admin_id: Uuid) {
+    user::Model {
+        id: admin_id,
+        email: format!("{}@example.com", admin_id),
+        password: "password".to_owned(),
+        role: Role::Admin,
+        language: None,
+    }
+    .into_active_model()
+    .insert(db)
+    .await
+    .unwrap();
+}
+
+/// Room numbers of rooms that are not deleted are unique, also across tests sharing the database
+pub fn unique_room_number() -> String {
+    Uuid::new_v4().simple().to_string()[..16].to_owned()
+}
+
+/// A room for two with a single double bed
+pub async fn insert_room(db: &DatabaseConnection, room_id: Uuid) -> room::Model {
+    let room = room::Model {
+        id: room_id,
+        price: 100,
+        floor: 1,
+        room_number: unique_room_number(),
+        bathroom_type: BathroomType::Private,
+        is_deleted: false,
+        cancellation_policy_id: None,
+    }
+    .into_active_model()
+    .insert(db)
+    .await
+    .unwrap();
+
+    bed::Model {
+        id: Uuid::new_v4(),
+        room_id,
+        bed_size: BedSize::Double,
+        count: 1,
+        total_capacity: 2,
+    }
+    .into_active_model()
+    .insert(db)
+    .await
+    .unwrap();
+
+    room
+}
+
+/// An adult guest without an id card
+pub async fn insert_guest(db: &DatabaseConnection, guest_id: Uuid) -> guest::Model {
+    guest::Model {
+        id: guest_id,
+        first_name: "John".to_owned(),
+        last_name: "Smith".to_owned(),
+        date_of_birth: sub_days(Utc::now().date_naive(), 30 * 365).unwrap(),
+        ..Default::default()
+    }
+    .into_active_model()
+    .insert(db)
+    .await
+    .unwrap()
+}
+
+/// A guest with an id card and phone number, so it can be the main guest of a booking
+pub async fn insert_main_guest(db: &DatabaseConnection, date_of_birth: Date) -> guest::Model {
+    let id = Uuid::new_v4();
+    let number = id.as_u128();
+    guest::Model {
+        id,
+        first_name: "Jane".to_owned(),
+        last_name: "Smith".to_owned(),
+        date_of_birth,
+        ucn: Some(format!("{:010}", number % 10_000_000_000)),
+        id_card_number: Some(format!("{:09}", (number >> 64) % 1_000_000_000)),
+        id_card_issue_authority: Some("Sofia".to_owned()),
+        id_card_issue_date: Some(sub_days(Utc::now().date_naive(), 365).unwrap()),
+        id_card_validity: Some(add_days(Utc::now().date_naive(), 5 * 365).unwrap()),
+        phone_number: Some(format!("+359{:09}", (number >> 32) % 1_000_000_000)),
+        owner_user_id: None,
+    }
+    .into_active_model()
+    .insert(db)
+    .await
+    .unwrap()
+}
+
+/// Inserts the booking with its admin and main guest into an existing room
+pub async fn insert_booking(db: &DatabaseConnection, booking: booking::Model) -> booking::Model {
+    if let Some(admin_id) = booking.admin_id {
+        insert_admin(db, admin_id).await;
+    }
+    insert_guest(db, booking.main_guest_id).await;
+    let guest_id = booking.main_guest_id;
+    let booking = booking.into_active_model().insert(db).await.unwrap();
+
+    booking_guest::Model {
+        guest_id,
+        booking_id: booking.id,
+    }
+    .into_active_model()
+    .insert(db)
+    .await
+    .unwrap();
+
+    booking
+}
+
+pub async fn insert_room_booking(
+    db: &DatabaseConnection,
+    booking: booking::Model,
+) -> booking::Model {
+    insert_room(db, booking.room_id).await;
+    insert_booking(db, booking).await
+}
+
+pub fn new_booking(status: BookingStatus, start_date: Date, end_date: Date) -> booking::Model {
+    booking::Model {
+        id: Uuid::new_v4(),
+        main_guest_id: Uuid::new_v4(),
+        room_id: Uuid::new_v4(),
+        admin_id: Some(Uuid::new_v4()),
+        booking_time: Utc::now().naive_utc(),
+        start_date,
+        end_date,
+        total_price: 100,
+        status,
+        ..Default::default()
+    }
+}
+
+/// Card payments get a provider reference, so they can be refunded through the provider
+pub async fn insert_payment<T>(db: &T, booking_id: Uuid, method: PaymentMethod, amount: i64)
+where
+    T: ConnectionTrait,
+{
+    payment::Model {
+        id: Uuid::new_v4(),
+        booking_id,
+        payment_type: PaymentType::Payment,
+        amount,
+        currency: "EUR".to_owned(),
+        provider_reference: (method == PaymentMethod::Card).then(|| "charge".to_owned()),
+        method,
+        payment_time: Utc::now().naive_utc(),
+        ..Default::default()
+    }
+    .into_active_model()
+    .insert(db)
+    .await
+    .unwrap();
+}
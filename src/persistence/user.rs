@@ -27,6 +27,9 @@ pub struct Model {
     #[sea_orm(db_type = "String(StringLen::N(255))")]
     pub password: String,
     pub role: Role,
+    /// Language of the emails sent to the user
+    #[sea_orm(db_type = "String(StringLen::N(8))", nullable)]
+    pub language: Option<String>,
 }
 
 #[derive(
@@ -79,7 +82,7 @@ pub async fn find_user_by_id<T>(db: &T, id: &Uuid) -> Result<Option<Model>, DbEr
 where
     T: ConnectionTrait,
 {
-    Ok(crate::persistence::user::Entity::find_by_id(*id)
+    crate::persistence::user::Entity::find_by_id(*id)
         .one(db)
-        .await?)
+        .await
 }
@@ -29,7 +29,7 @@ impl Claims {
     pub fn from_token(token: &str, app_state: &AppState) -> Result<Self, Box<dyn Error>> {
         let secret = &app_state.security_info.jwt_secret;
         let result = decode::<Claims>(
-            &token,
+            token,
             &DecodingKey::from_secret(secret.as_bytes()),
             &Validation::new(Algorithm::HS256),
         );
@@ -55,6 +55,11 @@ impl Claims {
             Err(Box::new(result.err().unwrap()))
         }
     }
+
+    /// Users can only access the records they own, admins can access all of them
+    pub fn owner_scope(&self) -> Option<Uuid> {
+        (self.role == Role::User).then_some(self.user_id)
+    }
 }
 
 pub trait WithClaims {
@@ -133,7 +138,7 @@ pub async fn decode_claims(
         ));
     }
 
-    let has_role = roles.iter().any(|role| *role == claims.role);
+    let has_role = roles.contains(&claims.role);
 
     if has_role {
         Ok(claims)
@@ -1,6 +1,14 @@
 pub mod auth;
 pub mod booking;
+pub mod booking_email;
+pub mod cancellation_policy;
 pub mod comment;
+pub mod email_outbox;
 pub mod email_service;
+pub mod email_template;
+pub mod email_transport;
 pub mod guest;
+pub mod payment;
+pub mod payment_provider;
+pub mod pricing;
 pub mod room;
@@ -32,6 +32,7 @@ pub async fn register_user_service(
         email: ActiveValue::Set(input.email.clone()),
         password: ActiveValue::Set(password),
         role: ActiveValue::Set(user::Role::User),
+        language: ActiveValue::Set(input.language.clone()),
     };
 
     let user = user_to_save.insert(app_state.db.as_ref()).await?;
@@ -1,4 +1,4 @@
-use sea_orm::{sqlx::types::chrono::Utc, ActiveModelTrait};
+use sea_orm::{sqlx::types::chrono::Utc, ActiveModelTrait, ConnectionTrait, TransactionTrait};
 use std::time::Duration;
 use uuid::Uuid;
 
@@ -13,6 +13,7 @@ use crate::{
         user::{self},
     },
     security::generate_otp,
+    services::{email_outbox::queue_email, email_template::EmailTemplateName},
     util::find_user,
 };
 
@@ -21,42 +22,52 @@ pub async fn send_otp_service(
     input: SendOtpInput,
 ) -> Result<SendOtpOutput, ErrorResponse> {
     let user = find_user(app_state, &input.email).await?;
-    let otp_code = create_otp(app_state, &user).await?;
-    send_email(app_state, &user, &otp_code).await?;
+    let transaction = app_state.db.begin().await?;
+    let otp = create_otp(app_state, &transaction, &user).await?;
+    queue_otp_email(&transaction, user, otp).await?;
+    transaction.commit().await?;
 
     Ok(SendOtpOutput)
 }
 
-async fn create_otp(app_state: &AppState, user: &user::Model) -> Result<String, ErrorResponse> {
-    one_time_password::delete_all_for_user(app_state.db.as_ref(), &user.id).await?;
+async fn create_otp<T>(
+    app_state: &AppState,
+    db: &T,
+    user: &user::Model,
+) -> Result<one_time_password::Model, ErrorResponse>
+where
+    T: ConnectionTrait,
+{
+    one_time_password::delete_all_for_user(db, &user.id).await?;
 
     let otp_code = generate_otp();
     let validity = Utc::now() + Duration::new(app_state.security_info.otp_validity, 0);
     let otp = one_time_password::ActiveModel {
         id: sea_orm::ActiveValue::Set(Uuid::new_v4()),
         user_id: sea_orm::ActiveValue::Set(user.id),
-        otp_code: sea_orm::ActiveValue::Set(otp_code.clone()),
+        otp_code: sea_orm::ActiveValue::Set(otp_code),
         validity: sea_orm::ActiveValue::Set(validity.naive_utc()),
     };
-    otp.insert(app_state.db.as_ref()).await?;
 
-    Ok(otp_code)
+    Ok(otp.insert(db).await?)
 }
 
-async fn send_email(
-    app_state: &AppState,
-    user: &user::Model,
-    otp_code: &str,
-) -> Result<(), ErrorResponse> {
-    let body = format!("Password reset code: '{otp_code}'");
-    app_state
-        .email_service
-        .send_text_mail(
-            user.email.to_string(),
-            "Reset password code".to_string(),
-            body,
-        )
-        .await?;
-
-    Ok(())
+/// The email is not sent after the otp expires
+async fn queue_otp_email<T>(
+    db: &T,
+    user: user::Model,
+    otp: one_time_password::Model,
+) -> Result<(), ErrorResponse>
+where
+    T: ConnectionTrait,
+{
+    queue_email(
+        db,
+        user.email,
+        user.language,
+        EmailTemplateName::Otp,
+        &[("otpCode", otp.otp_code)],
+        Some(otp.validity),
+    )
+    .await
 }
@@ -1,6 +1,94 @@
+use actix_web::http::StatusCode;
+use sea_orm::{sqlx::types::chrono::Utc, ConnectionTrait, DatabaseTransaction, EntityTrait};
+use uuid::Uuid;
+
+use crate::{
+    api::error_response::ErrorResponse,
+    persistence::{booking, guest},
+    util::require_some,
+    validation::Validator,
+};
+
 pub mod book_room;
 pub mod cancel_booking;
+pub mod check_in;
+pub mod check_out;
+pub mod confirm_booking;
 pub mod find_unoccupied_rooms;
 pub mod get_booking;
 pub mod get_own_bookings;
+pub mod mark_no_show;
+pub mod modify_booking;
 pub mod pay_booking;
+pub mod quote_booking;
+pub mod update_booking_guests;
+
+const MIN_BOOKING_AGE: u32 = 18;
+
+pub(super) async fn lock_booking(
+    transaction: &DatabaseTransaction,
+    booking_id: Uuid,
+) -> Result<booking::Model, ErrorResponse> {
+    let booking_option = booking::lock_booking_by_id(transaction, booking_id).await?;
+
+    require_some(
+        booking_option,
+        || format!("Booking with id '{}' not found", booking_id),
+        StatusCode::NOT_FOUND,
+    )
+}
+
+pub(super) fn check_is_active(booking: &booking::Model) -> Result<(), ErrorResponse> {
+    if booking.status.is_finished() {
+        Err(ErrorResponse::new(
+            "Booking is no longer active".to_owned(),
+            StatusCode::BAD_REQUEST,
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+async fn validate_main_guest<T>(db: &T, main_guest_id: Uuid) -> Result<(), ErrorResponse>
+where
+    T: ConnectionTrait,
+{
+    let main_guest_option = guest::Entity::find_by_id(main_guest_id).one(db).await?;
+    let main_guest = require_some(
+        main_guest_option,
+        || format!("Main guest with id '{}' not found", main_guest_id),
+        StatusCode::NOT_FOUND,
+    )?;
+
+    Validator::validate_option(
+        &main_guest.id_card_issue_authority,
+        "Main guest's id card issue authority",
+    )?;
+    Validator::validate_option(
+        &main_guest.id_card_issue_date,
+        "Main guest's id card issue date",
+    )?;
+    Validator::validate_option(&main_guest.id_card_number, "Main guest's id card number")?;
+    Validator::validate_option(
+        &main_guest.id_card_validity,
+        "Main guest's id card validity",
+    )?;
+    Validator::validate_option(&main_guest.phone_number, "Main guest's phone number")?;
+    Validator::validate_option(&main_guest.ucn, "Main guest's ucn")?;
+
+    let main_guest_age = require_some(
+        Utc::now()
+            .date_naive()
+            .years_since(main_guest.date_of_birth),
+        || "Invalid main guest age".to_owned(),
+        StatusCode::BAD_REQUEST,
+    )?;
+    if main_guest_age < MIN_BOOKING_AGE {
+        Err(ErrorResponse::new(
+            format!("Main guest needs to be at least {MIN_BOOKING_AGE} years old"),
+            StatusCode::BAD_REQUEST,
+        ))
+    } else {
+        Ok(())
+    }
+}
@@ -2,8 +2,8 @@ use std::iter::once;
 
 use actix_web::http::StatusCode;
 use sea_orm::{
-    sqlx::types::chrono::Utc, ActiveModelTrait, DatabaseTransaction, EntityTrait, IntoActiveModel,
-    TransactionTrait,
+    sqlx::types::chrono::Utc, ActiveModelTrait, ConnectionTrait, DatabaseConnection,
+    DatabaseTransaction, EntityTrait, IntoActiveModel, TransactionTrait,
 };
 use uuid::Uuid;
 
@@ -11,51 +11,115 @@ use crate::{
     api::{
         booking::book_room::{BookRoomInput, BookRoomOutput},
         error_response::ErrorResponse,
+        room::NightPrice,
     },
     app_state::AppState,
     persistence::{
         booking, booking_guest, guest, room,
         user::{self, Role},
     },
+    services::{
+        booking_email::{queue_booking_email, BookingEmail},
+        cancellation_policy::find_cancellation_policy,
+        guest::check_guest_owner,
+        pricing::{calculate_night_prices, sum_night_prices},
+    },
     util::require_some,
-    validation::Validator,
 };
 
-const MIN_BOOKING_AGE: u32 = 18;
+use super::validate_main_guest;
 
 pub async fn book_room_service(
     app_state: &AppState,
     input: BookRoomInput,
 ) -> Result<BookRoomOutput, ErrorResponse> {
-    validate_guests(app_state, &input).await?;
-    let (room_capacity, room_price) = find_room_capacity_and_price(app_state, &input).await?;
+    let booked_by = get_booked_by(app_state, &input).await?;
+    validate_guests(app_state, &input, booked_by.owner_user_id()).await?;
+    let (room_capacity, room) = find_room_with_capacity(app_state, &input).await?;
     check_is_capacity_enough(&input, room_capacity)?;
     validate_guest_user(app_state, &input).await?;
-    check_room_occipied(app_state, &input).await?;
-    create_booking(app_state, input, room_price).await
+    let cancellation_policy_id = select_cancellation_policy(app_state, &input, &room).await?;
+    let input = BookRoomInput {
+        cancellation_policy_id,
+        ..input
+    };
+    let nights = calculate_night_prices(
+        app_state.db.as_ref(),
+        &room,
+        input.start_date,
+        input.end_date,
+    )
+    .await?;
+    create_booking(app_state.db.as_ref(), input, &nights, booked_by).await
 }
 
-async fn get_booked_by(app_state: &AppState, input: &BookRoomInput) -> Result<Uuid, ErrorResponse> {
-    if let Some(admin_id) = &input.booked_by {
-        let admin_option = user::find_user_by_id(app_state.db.as_ref(), admin_id).await?;
-        let admin = require_some(
-            admin_option,
-            || format!("Admin with id '{}' not found", admin_id),
-            StatusCode::NOT_FOUND,
-        )?;
-        if admin.role != Role::Admin {
-            return Err(ErrorResponse::new(
-                "Not booked by admin".to_owned(),
-                StatusCode::FORBIDDEN,
-            ));
+#[derive(Debug, Clone)]
+pub(super) struct BookedBy {
+    admin_id: Option<Uuid>,
+    user_id: Option<Uuid>,
+    status: booking::BookingStatus,
+}
+impl BookedBy {
+    fn owner_user_id(&self) -> Option<Uuid> {
+        match self.admin_id {
+            Some(_) => None,
+            None => self.user_id,
         }
+    }
+}
 
-        Ok(admin.id)
-    } else {
-        Err(ErrorResponse::new(
-            "Admin not found".to_owned(),
-            StatusCode::NOT_FOUND,
-        ))
+/// Admins book for any guest user, users book for themselves and wait for an admin to confirm
+/// unless auto-confirmation is enabled
+async fn get_booked_by(
+    app_state: &AppState,
+    input: &BookRoomInput,
+) -> Result<BookedBy, ErrorResponse> {
+    let booked_by = require_some(
+        input.booked_by,
+        || "User not found".to_owned(),
+        StatusCode::NOT_FOUND,
+    )?;
+    let user_option = user::find_user_by_id(app_state.db.as_ref(), &booked_by).await?;
+    let user = require_some(
+        user_option,
+        || format!("User with id '{}' not found", booked_by),
+        StatusCode::NOT_FOUND,
+    )?;
+
+    match user.role {
+        Role::Admin => Ok(BookedBy {
+            admin_id: Some(user.id),
+            user_id: input.guest_user_id,
+            status: booking::BookingStatus::Unpaid,
+        }),
+        Role::User => {
+            if input
+                .guest_user_id
+                .is_some_and(|user_id| user_id != user.id)
+            {
+                return Err(ErrorResponse::new(
+                    "Users can only book for themselves".to_owned(),
+                    StatusCode::FORBIDDEN,
+                ));
+            }
+            if input.cancellation_policy_id.is_some() {
+                return Err(ErrorResponse::new(
+                    "Only admins can choose the cancellation policy".to_owned(),
+                    StatusCode::FORBIDDEN,
+                ));
+            }
+
+            let status = if app_state.auto_confirm_user_bookings {
+                booking::BookingStatus::Unpaid
+            } else {
+                booking::BookingStatus::Pending
+            };
+            Ok(BookedBy {
+                admin_id: None,
+                user_id: Some(user.id),
+                status,
+            })
+        }
     }
 }
 
@@ -75,82 +139,64 @@ fn check_guest_duplicated(input: &BookRoomInput) -> Result<(), ErrorResponse> {
     }
 }
 
-async fn validate_main_guest(
+async fn validate_other_guests(
     app_state: &AppState,
     input: &BookRoomInput,
 ) -> Result<(), ErrorResponse> {
-    let main_guest_option = guest::Entity::find_by_id(input.main_guest)
-        .one(app_state.db.as_ref())
-        .await?;
-    let main_guest = require_some(
-        main_guest_option,
-        || format!("Main guest with id '{}' not found", input.main_guest),
-        StatusCode::NOT_FOUND,
-    )?;
-
-    Validator::validate_option(
-        &main_guest.id_card_issue_authority,
-        "Main guest's id card issue authority",
-    )?;
-    Validator::validate_option(
-        &main_guest.id_card_issue_date,
-        "Main guest's id card issue date",
-    )?;
-    Validator::validate_option(&main_guest.id_card_number, "Main guest's id card number")?;
-    Validator::validate_option(
-        &main_guest.id_card_validity,
-        "Main guest's id card validity",
-    )?;
-    Validator::validate_option(&main_guest.phone_number, "Main guest's phone number")?;
-    Validator::validate_option(&main_guest.ucn, "Main guest's ucn")?;
-
-    let main_guest_age = require_some(
-        Utc::now()
-            .date_naive()
-            .years_since(main_guest.date_of_birth),
-        || "Invalid main guest age".to_owned(),
-        StatusCode::BAD_REQUEST,
-    )?;
-    if main_guest_age < MIN_BOOKING_AGE {
-        Err(ErrorResponse::new(
-            format!("Main guest needs to be at least {MIN_BOOKING_AGE} years old"),
-            StatusCode::BAD_REQUEST,
-        ))
-    } else {
-        Ok(())
+    for guest_id in &input.other_guests {
+        let guest = guest::Entity::find_by_id(*guest_id)
+            .one(app_state.db.as_ref())
+            .await?;
+        require_some(
+            guest,
+            || format!("Guest with id '{}' not found", input.main_guest),
+            StatusCode::NOT_FOUND,
+        )?;
     }
+
+    Ok(())
 }
 
-async fn validate_other_guests(
+async fn check_guests_owned(
     app_state: &AppState,
     input: &BookRoomInput,
+    owner_user_id: Uuid,
 ) -> Result<(), ErrorResponse> {
-    for guest_id in &input.other_guests {
+    for guest_id in input.other_guests.iter().chain(once(&input.main_guest)) {
         let guest = guest::Entity::find_by_id(*guest_id)
             .one(app_state.db.as_ref())
             .await?;
-        require_some(
+        let guest = require_some(
             guest,
-            || format!("Guest with id '{}' not found", input.main_guest),
+            || format!("Guest with id '{}' not found", guest_id),
             StatusCode::NOT_FOUND,
         )?;
+        check_guest_owner(&guest, Some(owner_user_id))?;
     }
 
     Ok(())
 }
 
-async fn validate_guests(app_state: &AppState, input: &BookRoomInput) -> Result<(), ErrorResponse> {
+/// `owner_user_id` is set when a user books and restricts the guests to the ones they own
+pub(super) async fn validate_guests(
+    app_state: &AppState,
+    input: &BookRoomInput,
+    owner_user_id: Option<Uuid>,
+) -> Result<(), ErrorResponse> {
     check_guest_duplicated(input)?;
-    validate_main_guest(app_state, input).await?;
+    validate_main_guest(app_state.db.as_ref(), input.main_guest).await?;
     validate_other_guests(app_state, input).await?;
+    if let Some(owner_user_id) = owner_user_id {
+        check_guests_owned(app_state, input, owner_user_id).await?;
+    }
 
     Ok(())
 }
 
-async fn find_room_capacity_and_price(
+pub(super) async fn find_room_with_capacity(
     app_state: &AppState,
     input: &BookRoomInput,
-) -> Result<(i16, i64), ErrorResponse> {
+) -> Result<(i16, room::Model), ErrorResponse> {
     let room_option = room::find_room_by_id(app_state.db.as_ref(), input.room_id).await?;
     let (room, beds) = require_some(
         room_option,
@@ -165,12 +211,25 @@ async fn find_room_capacity_and_price(
     }
 
     let capacity = beds.iter().map(|bed| bed.total_capacity).sum();
-    let price = room.price;
 
-    Ok((capacity, price))
+    Ok((capacity, room))
 }
 
-fn check_is_capacity_enough(
+pub(super) async fn select_cancellation_policy(
+    app_state: &AppState,
+    input: &BookRoomInput,
+    room: &room::Model,
+) -> Result<Option<Uuid>, ErrorResponse> {
+    match input.cancellation_policy_id {
+        Some(policy_id) => {
+            find_cancellation_policy(app_state.db.as_ref(), policy_id).await?;
+            Ok(Some(policy_id))
+        }
+        None => Ok(room.cancellation_policy_id),
+    }
+}
+
+pub(super) fn check_is_capacity_enough(
     input: &BookRoomInput,
     room_capacity: i16,
 ) -> Result<(), ErrorResponse> {
@@ -188,34 +247,52 @@ fn check_is_capacity_enough(
     }
 }
 
-async fn check_room_occipied(
-    app_state: &AppState,
+async fn lock_room(
+    transaction: &DatabaseTransaction,
     input: &BookRoomInput,
 ) -> Result<(), ErrorResponse> {
+    let room_option = room::lock_room_by_id(transaction, input.room_id).await?;
+    require_some(
+        room_option,
+        || format!("Room with id '{}'", input.room_id),
+        StatusCode::NOT_FOUND,
+    )?;
+
+    Ok(())
+}
+
+pub(super) async fn check_room_not_occupied<T>(
+    db: &T,
+    input: &BookRoomInput,
+) -> Result<(), ErrorResponse>
+where
+    T: ConnectionTrait,
+{
     let is_occupied = booking::is_room_occupied_for_period(
-        app_state.db.as_ref(),
+        db,
         input.room_id,
         input.start_date,
         input.end_date,
+        None,
     )
     .await?;
 
     if is_occupied {
         Err(ErrorResponse::new(
-            "Room is occupied of period".to_owned(),
-            StatusCode::BAD_REQUEST,
+            "Room is occupied for period".to_owned(),
+            StatusCode::CONFLICT,
         ))
     } else {
         Ok(())
     }
 }
 
-async fn validate_guest_user(
+pub(super) async fn validate_guest_user(
     app_state: &AppState,
     input: &BookRoomInput,
 ) -> Result<(), ErrorResponse> {
     if let Some(user_id) = &input.guest_user_id {
-        let user = user::find_user_by_id(app_state.db.as_ref(), &user_id).await?;
+        let user = user::find_user_by_id(app_state.db.as_ref(), user_id).await?;
         require_some(
             user,
             || format!("Guest user with id '{}' not found ", user_id),
@@ -229,22 +306,29 @@ async fn validate_guest_user(
 async fn insert_booking(
     transaction: &DatabaseTransaction,
     input: &BookRoomInput,
-    room_price: i64,
-    admin_id: Uuid,
+    nights: &[NightPrice],
+    booked_by: BookedBy,
 ) -> Result<Uuid, ErrorResponse> {
-    let total_price = room_price * ((input.end_date - input.start_date).num_days() + 1);
     let booking = booking::Model {
         id: Uuid::new_v4(),
         main_guest_id: input.main_guest,
         room_id: input.room_id,
-        admin_id,
-        user_id: input.guest_user_id,
+        admin_id: booked_by.admin_id,
+        user_id: booked_by.user_id,
         booking_time: Utc::now().naive_utc(),
         payment_time: None,
         start_date: input.start_date,
         end_date: input.end_date,
-        total_price,
-        status: booking::BookingStatus::Unpaid,
+        total_price: sum_night_prices(nights),
+        status: booked_by.status,
+        check_in_time: None,
+        check_out_time: None,
+        no_show_time: None,
+        cancellation_policy_id: input.cancellation_policy_id,
+        cancellation_penalty: None,
+        cancellation_refund: None,
+        reminder_sent_time: None,
+        first_night_price: nights.first().map(|night| night.price),
     }
     .into_active_model()
     .insert(transaction)
@@ -272,15 +356,98 @@ async fn insert_guests(
 }
 
 async fn create_booking(
-    app_state: &AppState,
+    db: &DatabaseConnection,
     input: BookRoomInput,
-    room_price: i64,
+    nights: &[NightPrice],
+    booked_by: BookedBy,
 ) -> Result<BookRoomOutput, ErrorResponse> {
-    let admin_id = get_booked_by(app_state, &input).await?;
-    let transaction = app_state.db.begin().await?;
-    let booking_id = insert_booking(&transaction, &input, room_price, admin_id).await?;
+    let transaction = db.begin().await?;
+    lock_room(&transaction, &input).await?;
+    check_room_not_occupied(&transaction, &input).await?;
+    let booking_id = insert_booking(&transaction, &input, nights, booked_by).await?;
     insert_guests(&transaction, &input, booking_id).await?;
+    queue_booking_email(&transaction, booking_id, BookingEmail::Created).await?;
     transaction.commit().await?;
 
     Ok(BookRoomOutput { booking_id })
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use sea_orm::{ColumnTrait, QueryFilter};
+
+    use crate::{
+        persistence::{
+            connect_concurrent_test_db,
+            test_data::{insert_admin, insert_guest, insert_room},
+        },
+        util::add_days,
+    };
+
+    use super::*;
+
+    const PARALLEL_BOOKINGS: usize = 5;
+
+    /// Postgres serializes the bookings with the room lock, SQLite with its database write lock
+    #[actix_web::test]
+    async fn test_parallel_bookings_for_same_room_only_one_succeeds() {
+        let db = connect_concurrent_test_db().await;
+        let (admin_id, room_id, guest_id) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        insert_admin(&db, admin_id).await;
+        insert_room(&db, room_id).await;
+        insert_guest(&db, guest_id).await;
+
+        let start_date = add_days(Utc::now().date_naive(), 365).unwrap();
+        let input = BookRoomInput {
+            booked_by: Some(admin_id),
+            room_id,
+            start_date,
+            end_date: add_days(start_date, 3).unwrap(),
+            main_guest: guest_id,
+            other_guests: HashSet::new(),
+            guest_user_id: None,
+            cancellation_policy_id: None,
+        };
+        let nights: Vec<_> = start_date
+            .iter_days()
+            .zip([150, 100, 100])
+            .map(|(date, price)| NightPrice { date, price })
+            .collect();
+
+        let handles: Vec<_> = (0..PARALLEL_BOOKINGS)
+            .map(|_| {
+                let db = db.clone();
+                let input = input.clone();
+                let nights = nights.clone();
+                let booked_by = BookedBy {
+                    admin_id: Some(admin_id),
+                    user_id: None,
+                    status: booking::BookingStatus::Unpaid,
+                };
+                actix_web::rt::spawn(
+                    async move { create_booking(&db, input, &nights, booked_by).await },
+                )
+            })
+            .collect();
+
+        let mut successful = 0;
+        for handle in handles {
+            match handle.await.unwrap() {
+                Ok(_) => successful += 1,
+                Err(err) => assert_eq!(err.status, StatusCode::CONFLICT),
+            }
+        }
+        assert_eq!(successful, 1);
+
+        let saved_bookings = booking::Entity::find()
+            .filter(booking::Column::RoomId.eq(room_id))
+            .all(&db)
+            .await
+            .unwrap();
+        assert_eq!(saved_bookings.len(), 1);
+        assert_eq!(saved_bookings[0].total_price, 350);
+        assert_eq!(saved_bookings[0].first_night_price, Some(150));
+    }
+}
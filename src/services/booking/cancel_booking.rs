@@ -1,5 +1,9 @@
 use actix_web::http::StatusCode;
-use sea_orm::{sqlx::types::chrono::Utc, ActiveModelTrait, EntityTrait, IntoActiveModel};
+use sea_orm::{
+    prelude::Date, sqlx::types::chrono::Utc, ActiveModelTrait, ActiveValue, DatabaseTransaction,
+    IntoActiveModel, TransactionTrait,
+};
+use uuid::Uuid;
 
 use crate::{
     api::{
@@ -7,46 +11,103 @@ use crate::{
         error_response::ErrorResponse,
     },
     app_state::AppState,
-    persistence::booking,
-    util::require_some,
+    persistence::{
+        booking,
+        cancellation_policy::{self, PenaltyType},
+    },
+    services::{
+        booking_email::{queue_booking_email, BookingEmail},
+        cancellation_policy::find_cancellation_policy,
+        payment::{find_paid_amount, refund_payments, send_refunds},
+        pricing::count_nights,
+    },
 };
 
+use super::lock_booking;
+
 pub async fn cancel_booking_service(
     app_state: &AppState,
     input: CancelBookingInput,
 ) -> Result<CancelBookingOutput, ErrorResponse> {
-    let booking = find_booking(app_state, input).await?;
-    check_not_payed(&booking)?;
+    let transaction = app_state.db.begin().await?;
+    let booking = lock_booking(&transaction, input.booking_id).await?;
+    check_owner_can_cancel(&booking, input.owner_user_id)?;
+    check_can_cancel(&booking)?;
     check_before_start_date(&booking)?;
-    set_status_to_canceled(app_state, booking).await
+    let policy = match booking.cancellation_policy_id {
+        Some(policy_id) => Some(find_cancellation_policy(&transaction, policy_id).await?),
+        None => None,
+    };
+    let penalty = calculate_penalty(policy.as_ref(), &booking, Utc::now().date_naive());
+    let paid_amount = find_paid_amount(&transaction, booking.id).await?;
+    let refunds = refund_payments(
+        &transaction,
+        booking.id,
+        input.canceled_by,
+        (paid_amount - penalty).max(0),
+    )
+    .await?;
+    let booking_id = booking.id;
+    set_status_to_canceled(&transaction, booking, penalty, refunds.amount).await?;
+    queue_booking_email(&transaction, booking_id, BookingEmail::Canceled).await?;
+    transaction.commit().await?;
+    let refunded_amount = send_refunds(
+        app_state.db.as_ref(),
+        app_state.payment_provider.as_ref(),
+        refunds,
+    )
+    .await;
+
+    Ok(CancelBookingOutput {
+        penalty,
+        refunded_amount,
+    })
 }
 
-async fn find_booking(
-    app_state: &AppState,
-    input: CancelBookingInput,
-) -> Result<booking::Model, ErrorResponse> {
-    let booking_option = booking::Entity::find_by_id(input.booking_id)
-        .one(app_state.db.as_ref())
-        .await?;
-
-    require_some(
-        booking_option,
-        || format!("Booking with id '{}' not found", input.booking_id),
-        StatusCode::NOT_FOUND,
-    )
+/// Users can only cancel their own bookings while they are still pending,
+/// `owner_user_id` is not set for admins
+fn check_owner_can_cancel(
+    booking: &booking::Model,
+    owner_user_id: Option<Uuid>,
+) -> Result<(), ErrorResponse> {
+    let Some(user_id) = owner_user_id else {
+        return Ok(());
+    };
+
+    if booking.user_id != Some(user_id) {
+        Err(ErrorResponse::new(
+            "Access not allowed".to_owned(),
+            StatusCode::FORBIDDEN,
+        ))
+    } else if booking.status != booking::BookingStatus::Pending {
+        Err(ErrorResponse::new(
+            "Only pending bookings can be canceled".to_owned(),
+            StatusCode::BAD_REQUEST,
+        ))
+    } else {
+        Ok(())
+    }
 }
 
-fn check_not_payed(booking: &booking::Model) -> Result<(), ErrorResponse> {
+fn check_can_cancel(booking: &booking::Model) -> Result<(), ErrorResponse> {
     match booking.status {
-        booking::BookingStatus::Unpaid => Ok(()),
-        booking::BookingStatus::Paid => Err(ErrorResponse::new(
-            "Booking is paid".to_owned(),
-            StatusCode::BAD_REQUEST,
-        )),
+        booking::BookingStatus::Pending
+        | booking::BookingStatus::Unpaid
+        | booking::BookingStatus::Paid => Ok(()),
         booking::BookingStatus::Canceled => Err(ErrorResponse::new(
             "Booking already canceld".to_owned(),
             StatusCode::BAD_REQUEST,
         )),
+        booking::BookingStatus::CheckedIn | booking::BookingStatus::CheckedOut => {
+            Err(ErrorResponse::new(
+                "Guest already checked in".to_owned(),
+                StatusCode::BAD_REQUEST,
+            ))
+        }
+        booking::BookingStatus::NoShow => Err(ErrorResponse::new(
+            "Booking is marked as no-show".to_owned(),
+            StatusCode::BAD_REQUEST,
+        )),
     }
 }
 
@@ -63,16 +124,167 @@ fn check_before_start_date(booking: &booking::Model) -> Result<(), ErrorResponse
     }
 }
 
+/// Penalty kept by the hotel when the booking is canceled on `today`, no policy means free cancellation
+/// The first night penalty is the first night price saved on the booking, at most the total price.
+/// Older bookings without it are charged the average price of a night
+fn calculate_penalty(
+    policy: Option<&cancellation_policy::Model>,
+    booking: &booking::Model,
+    today: Date,
+) -> i64 {
+    let Some(policy) = policy else {
+        return 0;
+    };
+
+    let days_before_start = (booking.start_date - today).num_days();
+    let is_free = policy
+        .free_cancellation_days
+        .is_some_and(|days| days_before_start >= days as i64);
+    if is_free {
+        return 0;
+    }
+
+    match policy.penalty_type {
+        PenaltyType::Percentage => booking.total_price * policy.penalty_percentage as i64 / 100,
+        PenaltyType::FirstNight => booking
+            .first_night_price
+            .unwrap_or_else(|| {
+                booking.total_price / count_nights(booking.start_date, booking.end_date).max(1)
+            })
+            .min(booking.total_price),
+        PenaltyType::FullPrice => booking.total_price,
+    }
+}
+
 async fn set_status_to_canceled(
-    app_state: &AppState,
+    transaction: &DatabaseTransaction,
     booking: booking::Model,
-) -> Result<CancelBookingOutput, ErrorResponse> {
+    penalty: i64,
+    refunded_amount: i64,
+) -> Result<(), ErrorResponse> {
     booking::ActiveModel {
-        status: sea_orm::ActiveValue::Set(booking::BookingStatus::Canceled),
+        status: ActiveValue::Set(booking::BookingStatus::Canceled),
+        cancellation_penalty: ActiveValue::Set(Some(penalty)),
+        cancellation_refund: ActiveValue::Set(Some(refunded_amount)),
         ..booking.into_active_model()
     }
-    .update(app_state.db.as_ref())
+    .update(transaction)
     .await?;
 
-    Ok(CancelBookingOutput)
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        persistence::{booking::BookingStatus, test_data::new_booking},
+        util::add_days,
+    };
+
+    use super::*;
+
+    fn policy(
+        free_days: Option<i16>,
+        penalty_type: PenaltyType,
+        percentage: i16,
+    ) -> cancellation_policy::Model {
+        cancellation_policy::Model {
+            id: Uuid::new_v4(),
+            name: "Test".to_owned(),
+            free_cancellation_days: free_days,
+            penalty_type,
+            penalty_percentage: percentage,
+        }
+    }
+
+    #[test]
+    fn test_calculate_penalty() {
+        let today = Utc::now().date_naive();
+        let start_date = add_days(today, 5).unwrap();
+        let booking = booking::Model {
+            total_price: 300,
+            ..new_booking(
+                BookingStatus::Paid,
+                start_date,
+                add_days(start_date, 3).unwrap(),
+            )
+        };
+        let booking = booking::Model {
+            first_night_price: Some(100),
+            ..booking
+        };
+
+        let flexible = policy(Some(5), PenaltyType::Percentage, 50);
+        assert_eq!(calculate_penalty(Some(&flexible), &booking, today), 0);
+        assert_eq!(
+            calculate_penalty(Some(&flexible), &booking, add_days(today, 1).unwrap()),
+            150
+        );
+
+        let first_night = policy(Some(7), PenaltyType::FirstNight, 0);
+        assert_eq!(calculate_penalty(Some(&first_night), &booking, today), 100);
+
+        let non_refundable = policy(None, PenaltyType::FullPrice, 0);
+        assert_eq!(
+            calculate_penalty(Some(&non_refundable), &booking, today),
+            300
+        );
+
+        assert_eq!(calculate_penalty(None, &booking, today), 0);
+    }
+
+    #[test]
+    fn test_check_owner_can_cancel() {
+        let start_date = add_days(Utc::now().date_naive(), 5).unwrap();
+        let owner_id = Uuid::new_v4();
+        let booking = |status| booking::Model {
+            user_id: Some(owner_id),
+            ..new_booking(status, start_date, add_days(start_date, 2).unwrap())
+        };
+
+        assert!(check_owner_can_cancel(&booking(BookingStatus::Pending), Some(owner_id)).is_ok());
+        assert!(check_owner_can_cancel(&booking(BookingStatus::Paid), None).is_ok());
+
+        let error = check_owner_can_cancel(&booking(BookingStatus::Pending), Some(Uuid::new_v4()))
+            .unwrap_err();
+        assert_eq!(error.status, StatusCode::FORBIDDEN);
+
+        let error =
+            check_owner_can_cancel(&booking(BookingStatus::Paid), Some(owner_id)).unwrap_err();
+        assert_eq!(error.status, StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn test_first_night_penalty_uses_the_saved_first_night_price() {
+        let today = Utc::now().date_naive();
+        let start_date = add_days(today, 5).unwrap();
+        let booking = |first_night_price| booking::Model {
+            total_price: 450,
+            first_night_price,
+            ..new_booking(
+                BookingStatus::Paid,
+                start_date,
+                add_days(start_date, 3).unwrap(),
+            )
+        };
+        let first_night = policy(None, PenaltyType::FirstNight, 0);
+
+        let weekend_first = booking(Some(250));
+        assert_eq!(
+            calculate_penalty(Some(&first_night), &weekend_first, today),
+            250
+        );
+
+        let weekend_last = booking(Some(100));
+        assert_eq!(
+            calculate_penalty(Some(&first_night), &weekend_last, today),
+            100
+        );
+
+        let booked_before_saving_prices = booking(None);
+        assert_eq!(
+            calculate_penalty(Some(&first_night), &booked_before_saving_prices, today),
+            150
+        );
+    }
 }
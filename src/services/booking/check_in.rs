@@ -0,0 +1,69 @@
+use actix_web::http::StatusCode;
+use sea_orm::{
+    sqlx::types::chrono::Utc, ActiveModelTrait, ActiveValue, DatabaseTransaction, IntoActiveModel,
+    TransactionTrait,
+};
+
+use crate::{
+    api::{
+        booking::check_in::{CheckInInput, CheckInOutput},
+        error_response::ErrorResponse,
+    },
+    app_state::AppState,
+    persistence::booking::{self, BookingStatus},
+};
+
+use super::lock_booking;
+
+pub async fn check_in_service(
+    app_state: &AppState,
+    input: CheckInInput,
+) -> Result<CheckInOutput, ErrorResponse> {
+    let transaction = app_state.db.begin().await?;
+    let booking = lock_booking(&transaction, input.booking_id).await?;
+    check_can_check_in(&booking)?;
+    let output = set_status_as_checked_in(&transaction, booking).await?;
+    transaction.commit().await?;
+
+    Ok(output)
+}
+
+fn check_can_check_in(booking: &booking::Model) -> Result<(), ErrorResponse> {
+    if !matches!(booking.status, BookingStatus::Unpaid | BookingStatus::Paid) {
+        return Err(ErrorResponse::new(
+            format!("Can't check in booking with status '{:?}'", booking.status),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    let today = Utc::now().date_naive();
+    if today < booking.start_date {
+        return Err(ErrorResponse::new(
+            "Can't check in before the start date".to_owned(),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+    if today >= booking.end_date {
+        return Err(ErrorResponse::new(
+            "Can't check in on or after the end date".to_owned(),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    Ok(())
+}
+
+async fn set_status_as_checked_in(
+    transaction: &DatabaseTransaction,
+    booking: booking::Model,
+) -> Result<CheckInOutput, ErrorResponse> {
+    booking::ActiveModel {
+        status: ActiveValue::Set(BookingStatus::CheckedIn),
+        check_in_time: ActiveValue::Set(Some(Utc::now().naive_utc())),
+        ..booking.into_active_model()
+    }
+    .update(transaction)
+    .await?;
+
+    Ok(CheckInOutput)
+}
@@ -0,0 +1,62 @@
+use actix_web::http::StatusCode;
+use sea_orm::{
+    sqlx::types::chrono::Utc, ActiveModelTrait, ActiveValue, DatabaseTransaction, IntoActiveModel,
+    TransactionTrait,
+};
+
+use crate::{
+    api::{
+        booking::check_out::{CheckOutInput, CheckOutOutput},
+        error_response::ErrorResponse,
+    },
+    app_state::AppState,
+    persistence::booking::{self, BookingStatus},
+};
+
+use super::lock_booking;
+
+pub async fn check_out_service(
+    app_state: &AppState,
+    input: CheckOutInput,
+) -> Result<CheckOutOutput, ErrorResponse> {
+    let transaction = app_state.db.begin().await?;
+    let booking = lock_booking(&transaction, input.booking_id).await?;
+    check_can_check_out(&booking)?;
+    let output = set_status_as_checked_out(&transaction, booking).await?;
+    transaction.commit().await?;
+
+    Ok(output)
+}
+
+fn check_can_check_out(booking: &booking::Model) -> Result<(), ErrorResponse> {
+    if booking.status != BookingStatus::CheckedIn {
+        return Err(ErrorResponse::new(
+            "Guest is not checked in".to_owned(),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    if booking.payment_time.is_none() {
+        return Err(ErrorResponse::new(
+            "Can't check out: booking is not paid".to_owned(),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    Ok(())
+}
+
+async fn set_status_as_checked_out(
+    transaction: &DatabaseTransaction,
+    booking: booking::Model,
+) -> Result<CheckOutOutput, ErrorResponse> {
+    booking::ActiveModel {
+        status: ActiveValue::Set(BookingStatus::CheckedOut),
+        check_out_time: ActiveValue::Set(Some(Utc::now().naive_utc())),
+        ..booking.into_active_model()
+    }
+    .update(transaction)
+    .await?;
+
+    Ok(CheckOutOutput)
+}
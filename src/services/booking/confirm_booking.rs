@@ -0,0 +1,56 @@
+use actix_web::http::StatusCode;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, DatabaseTransaction, IntoActiveModel, TransactionTrait,
+};
+use uuid::Uuid;
+
+use crate::{
+    api::{
+        booking::confirm_booking::{ConfirmBookingInput, ConfirmBookingOutput},
+        error_response::ErrorResponse,
+    },
+    app_state::AppState,
+    persistence::booking::{self, BookingStatus},
+};
+
+use super::lock_booking;
+
+pub async fn confirm_booking_service(
+    app_state: &AppState,
+    input: ConfirmBookingInput,
+) -> Result<ConfirmBookingOutput, ErrorResponse> {
+    let transaction = app_state.db.begin().await?;
+    let booking = lock_booking(&transaction, input.booking_id).await?;
+    check_can_confirm(&booking)?;
+    set_status_as_confirmed(&transaction, booking, input.confirmed_by.unwrap()).await?;
+    transaction.commit().await?;
+
+    Ok(ConfirmBookingOutput)
+}
+
+fn check_can_confirm(booking: &booking::Model) -> Result<(), ErrorResponse> {
+    if booking.status != BookingStatus::Pending {
+        return Err(ErrorResponse::new(
+            format!("Can't confirm booking with status '{:?}'", booking.status),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    Ok(())
+}
+
+async fn set_status_as_confirmed(
+    transaction: &DatabaseTransaction,
+    booking: booking::Model,
+    admin_id: Uuid,
+) -> Result<(), ErrorResponse> {
+    booking::ActiveModel {
+        status: ActiveValue::Set(BookingStatus::Unpaid),
+        admin_id: ActiveValue::Set(Some(admin_id)),
+        ..booking.into_active_model()
+    }
+    .update(transaction)
+    .await?;
+
+    Ok(())
+}
@@ -1,71 +1,58 @@
-use uuid::Uuid;
-
 use crate::{
     api::{
-        booking::find_unoccupied_rooms::{FindUnoccupiedRoomsInput, FindUnoccupiedRoomsOutput},
+        booking::find_unoccupied_rooms::{
+            FindUnoccupiedRoomsInput, FindUnoccupiedRoomsOutput, UnoccupiedRoom,
+        },
         error_response::ErrorResponse,
     },
     app_state::AppState,
-    persistence::{bed, booking::is_room_occupied_for_period, room},
+    persistence::room::{self, RoomFilter},
+    services::room::find_beds_by_room,
 };
 
 pub async fn find_unoccupied_rooms_service(
     app_state: &AppState,
     input: FindUnoccupiedRoomsInput,
 ) -> Result<FindUnoccupiedRoomsOutput, ErrorResponse> {
-    let room_ids = room::find_all_room_ids_not_deleted(app_state.db.as_ref()).await?;
-    let free_room_ids = apply_filters(app_state, &input, &room_ids).await?;
-
-    let output = FindUnoccupiedRoomsOutput {
-        room_ids: free_room_ids,
-    };
-
-    Ok(output)
+    let filter = create_filter(&input);
+    let (total_size, rooms) = room::find_paged_rooms(
+        app_state.db.as_ref(),
+        &filter,
+        input.sort_by,
+        input.sort_order,
+        input.page,
+        input.size,
+    )
+    .await?;
+
+    let mut beds_by_room = find_beds_by_room(app_state, &rooms).await?;
+    let rooms = rooms
+        .into_iter()
+        .map(|room| UnoccupiedRoom {
+            beds: beds_by_room.remove(&room.id).unwrap_or_default(),
+            id: room.id,
+            price: room.price,
+            floor: room.floor,
+            room_number: room.room_number,
+            bathroom_type: room.bathroom_type,
+            capacity: room.capacity as i16,
+        })
+        .collect();
+
+    Ok(FindUnoccupiedRoomsOutput { total_size, rooms })
 }
 
-async fn check_bed_capacity(
-    app_state: &AppState,
-    room_id: Uuid,
-    input: &FindUnoccupiedRoomsInput,
-) -> Result<bool, ErrorResponse> {
-    if input.minimum_capacity.is_none() && input.maximum_capacity.is_none() {
-        return Ok(true);
-    }
-
-    let capacity = bed::find_total_bed_capacity_for_room(app_state.db.as_ref(), room_id).await?;
-
-    if let Some(max) = input.maximum_capacity {
-        if capacity > max {
-            return Ok(false);
-        }
-    }
-    if let Some(min) = input.minimum_capacity {
-        if capacity < min {
-            return Ok(false);
-        }
+fn create_filter(input: &FindUnoccupiedRoomsInput) -> RoomFilter {
+    RoomFilter {
+        unoccupied_between: Some((input.start_date, input.end_date)),
+        bathroom_type: input.bathroom_type.clone(),
+        bed_sizes: input.bed_sizes.clone(),
+        minimum_floor: input.minimum_floor,
+        maximum_floor: input.maximum_floor,
+        minimum_price: input.minimum_price,
+        maximum_price: input.maximum_price,
+        minimum_capacity: input.minimum_capacity,
+        maximum_capacity: input.maximum_capacity,
+        ..Default::default()
     }
-
-    Ok(true)
-}
-
-async fn apply_filters(
-    app_state: &AppState,
-    input: &FindUnoccupiedRoomsInput,
-    room_ids: &[Uuid],
-) -> Result<Vec<Uuid>, ErrorResponse> {
-    let mut free_room_ids = Vec::with_capacity(100);
-    for id in room_ids {
-        if !check_bed_capacity(app_state, *id, input).await? {
-            continue;
-        }
-        if is_room_occupied_for_period(app_state.db.as_ref(), *id, input.start_date, input.end_date)
-            .await?
-        {
-            continue;
-        }
-
-        free_room_ids.push(*id);
-    }
-
-    Ok(free_room_ids)
 }
@@ -3,12 +3,12 @@ use sea_orm::{EntityTrait, ModelTrait};
 
 use crate::{
     api::{
-        booking::get_booking::{BookingGuest, GetBookingInput, GetBookingOutput},
+        booking::get_booking::{BookingGuest, BookingPayment, GetBookingInput, GetBookingOutput},
         error_response::ErrorResponse,
         guest::GuestIdCard,
     },
     app_state::AppState,
-    persistence::{self, booking, guest},
+    persistence::{self, booking, guest, payment},
     util::require_some,
 };
 
@@ -85,8 +85,8 @@ fn convert_id_card(guest: &guest::Model) -> Option<GuestIdCard> {
             ucn: ucn.clone(),
             id_card_number: id_card_number.clone(),
             issue_authority: issue_authority.clone(),
-            issue_date: issue_date.clone(),
-            validity: validity.clone(),
+            issue_date: *issue_date,
+            validity: *validity,
         }),
         _ => None,
     }
@@ -108,6 +108,18 @@ fn convert_guest(guest: Option<guest::Model>) -> Result<BookingGuest, ErrorRespo
     })
 }
 
+fn convert_payment(payment: payment::Model) -> BookingPayment {
+    BookingPayment {
+        id: payment.id,
+        payment_type: payment.payment_type,
+        amount: payment.amount,
+        currency: payment.currency,
+        method: payment.method,
+        provider_reference: payment.provider_reference,
+        payment_time: payment.payment_time,
+    }
+}
+
 async fn convert_to_output(
     app_state: &AppState,
     booking: booking::Model,
@@ -135,6 +147,10 @@ async fn convert_to_output(
         other_guests.push(convert_guest(guest.await?)?);
     }
 
+    let payments = payment::find_payments_for_booking(app_state.db.as_ref(), booking.id).await?;
+    let paid_amount = payments.iter().map(|payment| payment.signed_amount()).sum();
+    let payments = payments.into_iter().map(convert_payment).collect();
+
     Ok(GetBookingOutput {
         main_guest,
         other_guests,
@@ -147,5 +163,13 @@ async fn convert_to_output(
         end_date: booking.end_date,
         total_price: booking.total_price,
         status: booking.status,
+        check_in_time: booking.check_in_time,
+        check_out_time: booking.check_out_time,
+        no_show_time: booking.no_show_time,
+        cancellation_policy_id: booking.cancellation_policy_id,
+        cancellation_penalty: booking.cancellation_penalty,
+        cancellation_refund: booking.cancellation_refund,
+        paid_amount,
+        payments,
     })
 }
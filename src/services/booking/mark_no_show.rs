@@ -0,0 +1,65 @@
+use actix_web::http::StatusCode;
+use sea_orm::{
+    sqlx::types::chrono::Utc, ActiveModelTrait, ActiveValue, DatabaseTransaction, IntoActiveModel,
+    TransactionTrait,
+};
+
+use crate::{
+    api::{
+        booking::mark_no_show::{MarkNoShowInput, MarkNoShowOutput},
+        error_response::ErrorResponse,
+    },
+    app_state::AppState,
+    persistence::booking::{self, BookingStatus},
+};
+
+use super::lock_booking;
+
+pub async fn mark_no_show_service(
+    app_state: &AppState,
+    input: MarkNoShowInput,
+) -> Result<MarkNoShowOutput, ErrorResponse> {
+    let transaction = app_state.db.begin().await?;
+    let booking = lock_booking(&transaction, input.booking_id).await?;
+    check_can_mark_no_show(&booking)?;
+    let output = set_status_as_no_show(&transaction, booking).await?;
+    transaction.commit().await?;
+
+    Ok(output)
+}
+
+fn check_can_mark_no_show(booking: &booking::Model) -> Result<(), ErrorResponse> {
+    if !matches!(booking.status, BookingStatus::Unpaid | BookingStatus::Paid) {
+        return Err(ErrorResponse::new(
+            format!(
+                "Can't mark booking with status '{:?}' as no-show",
+                booking.status
+            ),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    if Utc::now().date_naive() < booking.start_date {
+        return Err(ErrorResponse::new(
+            "Can't mark as no-show before the start date".to_owned(),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    Ok(())
+}
+
+async fn set_status_as_no_show(
+    transaction: &DatabaseTransaction,
+    booking: booking::Model,
+) -> Result<MarkNoShowOutput, ErrorResponse> {
+    booking::ActiveModel {
+        status: ActiveValue::Set(BookingStatus::NoShow),
+        no_show_time: ActiveValue::Set(Some(Utc::now().naive_utc())),
+        ..booking.into_active_model()
+    }
+    .update(transaction)
+    .await?;
+
+    Ok(MarkNoShowOutput)
+}
@@ -0,0 +1,415 @@
+use actix_web::http::StatusCode;
+use sea_orm::{
+    sqlx::types::chrono::Utc, ActiveModelTrait, ActiveValue, ColumnTrait, DatabaseConnection,
+    DatabaseTransaction, EntityTrait, IntoActiveModel, ModelTrait, PaginatorTrait, QueryFilter,
+    TransactionTrait,
+};
+use uuid::Uuid;
+
+use crate::{
+    api::{
+        booking::modify_booking::{ModifyBookingInput, ModifyBookingOutput},
+        error_response::ErrorResponse,
+        room::NightPrice,
+    },
+    app_state::AppState,
+    persistence::{
+        bed,
+        booking::{self, BookingStatus},
+        booking_change, booking_guest, room,
+    },
+    services::{
+        booking_email::{queue_booking_email, BookingEmail},
+        payment::{find_paid_amount, refund_payments, send_refunds, Refunds},
+        payment_provider::PaymentProvider,
+        pricing::{calculate_night_prices, sum_night_prices},
+    },
+    util::require_some,
+};
+
+use super::{check_is_active, lock_booking};
+
+pub async fn modify_booking_service(
+    app_state: &AppState,
+    input: ModifyBookingInput,
+) -> Result<ModifyBookingOutput, ErrorResponse> {
+    modify_booking(
+        app_state.db.as_ref(),
+        app_state.payment_provider.as_ref(),
+        &input,
+    )
+    .await
+}
+
+async fn modify_booking(
+    db: &DatabaseConnection,
+    payment_provider: &dyn PaymentProvider,
+    input: &ModifyBookingInput,
+) -> Result<ModifyBookingOutput, ErrorResponse> {
+    let transaction = db.begin().await?;
+    let booking_id = input.booking_id.unwrap();
+    let room_id = match input.room_id {
+        Some(room_id) => room_id,
+        None => find_booking(&transaction, booking_id).await?.room_id,
+    };
+    let room = lock_room(&transaction, room_id).await?;
+    let booking = lock_booking(&transaction, booking_id).await?;
+    check_is_active(&booking)?;
+    check_room_not_changed(&booking, room_id, input)?;
+    check_start_date(&booking, input)?;
+    check_capacity_is_enough(&transaction, &room, booking.id).await?;
+    check_room_not_occupied(&transaction, room_id, booking.id, input).await?;
+    insert_booking_change(&transaction, &booking, input.modified_by.unwrap()).await?;
+    let nights =
+        calculate_night_prices(&transaction, &room, input.start_date, input.end_date).await?;
+    let total_price = sum_night_prices(&nights);
+    let refunds = refund_overpayment(&transaction, booking_id, input, total_price).await?;
+    let paid_amount = find_paid_amount(&transaction, booking_id).await?;
+    let status =
+        update_booking(&transaction, booking, room_id, input, &nights, paid_amount).await?;
+    queue_booking_email(&transaction, booking_id, BookingEmail::Modified).await?;
+    transaction.commit().await?;
+    let refunded_amount = send_refunds(db, payment_provider, refunds).await;
+
+    Ok(ModifyBookingOutput {
+        total_price,
+        status,
+        refunded_amount,
+    })
+}
+
+async fn find_booking(
+    transaction: &DatabaseTransaction,
+    booking_id: Uuid,
+) -> Result<booking::Model, ErrorResponse> {
+    let booking_option = booking::Entity::find_by_id(booking_id)
+        .one(transaction)
+        .await?;
+
+    require_some(
+        booking_option,
+        || format!("Booking with id '{}' not found", booking_id),
+        StatusCode::NOT_FOUND,
+    )
+}
+
+/// The room is locked before the booking, so the booking may have been moved in between
+fn check_room_not_changed(
+    booking: &booking::Model,
+    room_id: Uuid,
+    input: &ModifyBookingInput,
+) -> Result<(), ErrorResponse> {
+    if input.room_id.is_none() && booking.room_id != room_id {
+        Err(ErrorResponse::new(
+            "Booking was moved to another room, try again".to_owned(),
+            StatusCode::CONFLICT,
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+fn check_start_date(
+    booking: &booking::Model,
+    input: &ModifyBookingInput,
+) -> Result<(), ErrorResponse> {
+    let is_start_date_changed = booking.start_date != input.start_date;
+    if is_start_date_changed && input.start_date < Utc::now().date_naive() {
+        Err(ErrorResponse::new(
+            "Start date cannot be a past date".to_owned(),
+            StatusCode::BAD_REQUEST,
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+async fn lock_room(
+    transaction: &DatabaseTransaction,
+    room_id: Uuid,
+) -> Result<room::Model, ErrorResponse> {
+    let room_option = room::lock_room_by_id(transaction, room_id).await?;
+    let message = || format!("Room with id '{}' not found", room_id);
+    let room = require_some(room_option, message, StatusCode::NOT_FOUND)?;
+
+    if room.is_deleted {
+        return Err(ErrorResponse::new(message(), StatusCode::NOT_FOUND));
+    }
+
+    Ok(room)
+}
+
+async fn check_capacity_is_enough(
+    transaction: &DatabaseTransaction,
+    room: &room::Model,
+    booking_id: Uuid,
+) -> Result<(), ErrorResponse> {
+    let room_capacity: i64 = room
+        .find_related(bed::Entity)
+        .all(transaction)
+        .await?
+        .iter()
+        .map(|bed| bed.total_capacity as i64)
+        .sum();
+
+    let number_of_guests = booking_guest::Entity::find()
+        .filter(booking_guest::Column::BookingId.eq(booking_id))
+        .count(transaction)
+        .await? as i64;
+
+    if room_capacity >= number_of_guests {
+        Ok(())
+    } else {
+        Err(ErrorResponse::new(
+            format!(
+                "Room capacity '{}' is less than the number of guests '{}'",
+                room_capacity, number_of_guests
+            ),
+            StatusCode::BAD_REQUEST,
+        ))
+    }
+}
+
+async fn check_room_not_occupied(
+    transaction: &DatabaseTransaction,
+    room_id: Uuid,
+    booking_id: Uuid,
+    input: &ModifyBookingInput,
+) -> Result<(), ErrorResponse> {
+    let is_occupied = booking::is_room_occupied_for_period(
+        transaction,
+        room_id,
+        input.start_date,
+        input.end_date,
+        Some(booking_id),
+    )
+    .await?;
+
+    if is_occupied {
+        Err(ErrorResponse::new(
+            "Room is occupied for period".to_owned(),
+            StatusCode::CONFLICT,
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+async fn insert_booking_change(
+    transaction: &DatabaseTransaction,
+    booking: &booking::Model,
+    admin_id: Uuid,
+) -> Result<(), ErrorResponse> {
+    booking_change::Model {
+        id: Uuid::new_v4(),
+        booking_id: booking.id,
+        admin_id,
+        change_time: Utc::now().naive_utc(),
+        previous_room_id: booking.room_id,
+        previous_start_date: booking.start_date,
+        previous_end_date: booking.end_date,
+        previous_total_price: booking.total_price,
+    }
+    .into_active_model()
+    .insert(transaction)
+    .await?;
+
+    Ok(())
+}
+
+/// Refunds whatever was paid above the new total price
+async fn refund_overpayment(
+    transaction: &DatabaseTransaction,
+    booking_id: Uuid,
+    input: &ModifyBookingInput,
+    total_price: i64,
+) -> Result<Refunds, ErrorResponse> {
+    let overpaid_amount = find_paid_amount(transaction, booking_id).await? - total_price;
+    if overpaid_amount <= 0 {
+        return Ok(Refunds::default());
+    }
+
+    refund_payments(transaction, booking_id, input.modified_by, overpaid_amount).await
+}
+
+/// A paid booking becomes unpaid when the new price is higher than what was paid and the other
+/// way around
+async fn update_booking(
+    transaction: &DatabaseTransaction,
+    booking: booking::Model,
+    room_id: Uuid,
+    input: &ModifyBookingInput,
+    nights: &[NightPrice],
+    paid_amount: i64,
+) -> Result<BookingStatus, ErrorResponse> {
+    let total_price = sum_night_prices(nights);
+    let is_paid = paid_amount >= total_price;
+    let (status, payment_time) = match (&booking.status, is_paid) {
+        (BookingStatus::Paid, false) => (BookingStatus::Unpaid, None),
+        (BookingStatus::Unpaid, true) => (BookingStatus::Paid, Some(Utc::now().naive_utc())),
+        (BookingStatus::CheckedIn, false) => (BookingStatus::CheckedIn, None),
+        (BookingStatus::CheckedIn, true) => (
+            BookingStatus::CheckedIn,
+            booking.payment_time.or(Some(Utc::now().naive_utc())),
+        ),
+        (status, _) => (status.clone(), booking.payment_time),
+    };
+
+    booking::ActiveModel {
+        room_id: ActiveValue::Set(room_id),
+        start_date: ActiveValue::Set(input.start_date),
+        end_date: ActiveValue::Set(input.end_date),
+        total_price: ActiveValue::Set(total_price),
+        first_night_price: ActiveValue::Set(nights.first().map(|night| night.price)),
+        status: ActiveValue::Set(status.clone()),
+        payment_time: ActiveValue::Set(payment_time),
+        ..booking.into_active_model()
+    }
+    .update(transaction)
+    .await?;
+
+    Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::prelude::Date;
+
+    use crate::{
+        persistence::{
+            booking::BookingStatus,
+            connect_test_db,
+            payment::{self, PaymentMethod, PaymentType},
+            test_data::{insert_booking, insert_payment, insert_room, new_booking},
+        },
+        services::{payment_provider::MockPaymentProvider, pricing::count_nights},
+        util::add_days,
+    };
+
+    use super::*;
+
+    fn paid_booking(room_id: Uuid, start_date: Date, end_date: Date) -> booking::Model {
+        booking::Model {
+            room_id,
+            total_price: 100 * count_nights(start_date, end_date),
+            ..new_booking(BookingStatus::Paid, start_date, end_date)
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_extend_booking_checks_other_bookings_only() {
+        let db = connect_test_db().await;
+        let room_id = insert_room(&db, Uuid::new_v4()).await.id;
+        let start_date = add_days(Utc::now().date_naive(), 365).unwrap();
+        let booking = insert_booking(
+            &db,
+            paid_booking(room_id, start_date, add_days(start_date, 2).unwrap()),
+        )
+        .await;
+        let (booking_id, admin_id) = (booking.id, booking.admin_id.unwrap());
+        insert_booking(
+            &db,
+            paid_booking(
+                room_id,
+                add_days(start_date, 10).unwrap(),
+                add_days(start_date, 12).unwrap(),
+            ),
+        )
+        .await;
+
+        let mut input = ModifyBookingInput {
+            booking_id: Some(booking_id),
+            modified_by: Some(admin_id),
+            start_date,
+            end_date: add_days(start_date, 5).unwrap(),
+            room_id: None,
+        };
+        let output = modify_booking(&db, &MockPaymentProvider, &input)
+            .await
+            .unwrap();
+        assert_eq!(output.total_price, 500);
+
+        input.end_date = add_days(start_date, 10).unwrap();
+        let output = modify_booking(&db, &MockPaymentProvider, &input)
+            .await
+            .unwrap();
+        assert_eq!(output.total_price, 1000);
+
+        input.end_date = add_days(start_date, 11).unwrap();
+        let err = modify_booking(&db, &MockPaymentProvider, &input)
+            .await
+            .unwrap_err();
+        assert_eq!(err.status, StatusCode::CONFLICT);
+
+        let booking = booking::Entity::find_by_id(booking_id)
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(booking.end_date, add_days(start_date, 10).unwrap());
+        assert_eq!(booking.total_price, 1000);
+        assert_eq!(booking.first_night_price, Some(100));
+
+        let changes = booking_change::Entity::find()
+            .filter(booking_change::Column::BookingId.eq(booking_id))
+            .all(&db)
+            .await
+            .unwrap();
+        assert_eq!(changes.len(), 2);
+        assert!(changes.iter().any(|change| change.previous_end_date
+            == add_days(start_date, 2).unwrap()
+            && change.previous_total_price == 200));
+    }
+
+    #[actix_web::test]
+    async fn test_modify_booking_reconciles_payments_with_new_price() {
+        let db = connect_test_db().await;
+        let room_id = insert_room(&db, Uuid::new_v4()).await.id;
+        let start_date = add_days(Utc::now().date_naive(), 365).unwrap();
+        let booking = insert_booking(
+            &db,
+            paid_booking(room_id, start_date, add_days(start_date, 4).unwrap()),
+        )
+        .await;
+        insert_payment(&db, booking.id, PaymentMethod::Cash, 100).await;
+        insert_payment(&db, booking.id, PaymentMethod::Card, 300).await;
+
+        let mut input = ModifyBookingInput {
+            booking_id: Some(booking.id),
+            modified_by: booking.admin_id,
+            start_date,
+            end_date: add_days(start_date, 5).unwrap(),
+            room_id: None,
+        };
+        let output = modify_booking(&db, &MockPaymentProvider, &input)
+            .await
+            .unwrap();
+        assert_eq!(output.total_price, 500);
+        assert_eq!(output.status, BookingStatus::Unpaid);
+        assert_eq!(output.refunded_amount, 0);
+        let saved = booking::Entity::find_by_id(booking.id)
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(saved.payment_time.is_none());
+
+        input.end_date = add_days(start_date, 2).unwrap();
+        let output = modify_booking(&db, &MockPaymentProvider, &input)
+            .await
+            .unwrap();
+        assert_eq!(output.total_price, 200);
+        assert_eq!(output.status, BookingStatus::Paid);
+        assert_eq!(output.refunded_amount, 200);
+
+        let refunds: Vec<_> = payment::find_payments_for_booking(&db, booking.id)
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|payment| payment.payment_type == PaymentType::Refund)
+            .collect();
+        assert_eq!(refunds.len(), 1);
+        assert_eq!(refunds[0].method, PaymentMethod::Card);
+        assert_eq!(refunds[0].amount, 200);
+    }
+}
@@ -1,7 +1,9 @@
 use actix_web::http::StatusCode;
 use sea_orm::{
-    sqlx::types::chrono::Utc, ActiveModelTrait, ActiveValue, EntityTrait, IntoActiveModel,
+    sqlx::types::chrono::Utc, ActiveModelTrait, ActiveValue, DatabaseConnection,
+    DatabaseTransaction, IntoActiveModel, TransactionTrait,
 };
+use uuid::Uuid;
 
 use crate::{
     api::{
@@ -9,56 +11,315 @@ use crate::{
         error_response::ErrorResponse,
     },
     app_state::AppState,
-    persistence::booking::{self, BookingStatus},
-    util::require_some,
+    constants::CURRENCY,
+    persistence::{
+        booking::{self, BookingStatus},
+        payment::{self, PaymentMethod, PaymentStatus, PaymentType},
+    },
+    services::{
+        booking_email::{queue_booking_email, BookingEmail},
+        payment::{find_paid_amount, refund_payments, send_refunds},
+        payment_provider::{ChargeRequest, PaymentProvider},
+    },
 };
 
+use super::lock_booking;
+
 pub async fn pay_booking_service(
     app_state: &AppState,
     input: PayBookingInput,
 ) -> Result<PayBookingOutput, ErrorResponse> {
-    let booking = find_booking(app_state, input).await?;
-    set_status_as_paid(app_state, booking).await
+    pay_booking(
+        app_state.db.as_ref(),
+        app_state.payment_provider.as_ref(),
+        &input,
+    )
+    .await
 }
 
-async fn find_booking(
-    app_state: &AppState,
-    input: PayBookingInput,
-) -> Result<booking::Model, ErrorResponse> {
-    let booking = booking::Entity::find_by_id(input.booking_id)
-        .one(app_state.db.as_ref())
-        .await?;
-
-    require_some(
-        booking,
-        || format!("Booking with id '{}' not found", input.booking_id),
-        StatusCode::NOT_FOUND,
+/// Card payments are recorded as pending and committed before the payment provider is called,
+/// then completed or failed in a second transaction
+async fn pay_booking(
+    db: &DatabaseConnection,
+    payment_provider: &dyn PaymentProvider,
+    input: &PayBookingInput,
+) -> Result<PayBookingOutput, ErrorResponse> {
+    let transaction = db.begin().await?;
+    let booking = lock_booking(&transaction, input.booking_id).await?;
+    check_can_pay(&booking)?;
+    let remaining_amount = booking.total_price - find_paid_amount(&transaction, booking.id).await?;
+    let amount = get_payment_amount(input, remaining_amount)?;
+    let payment = insert_payment(&transaction, input, amount).await?;
+    let Some(card_token) = get_card_token(input) else {
+        let output = finish_payment(&transaction, booking, payment.id, amount).await?;
+        transaction.commit().await?;
+        return Ok(output);
+    };
+    transaction.commit().await?;
+
+    let request = ChargeRequest {
+        idempotency_key: payment.id,
+        booking_id: input.booking_id,
+        amount,
+        currency: CURRENCY,
+        card_token,
+    };
+    let charge_result = payment_provider.charge(request).await;
+
+    let transaction = db.begin().await?;
+    let booking = lock_booking(&transaction, input.booking_id).await?;
+    let provider_reference = match charge_result {
+        Ok(provider_reference) => provider_reference,
+        Err(err) => {
+            payment::update_payment_status(&transaction, payment.id, PaymentStatus::Failed, None)
+                .await?;
+            transaction.commit().await?;
+            return Err(err);
+        }
+    };
+    payment::update_payment_status(
+        &transaction,
+        payment.id,
+        PaymentStatus::Completed,
+        Some(provider_reference),
     )
+    .await?;
+    if booking.status == BookingStatus::Canceled {
+        let refunds = refund_payments(&transaction, booking.id, input.paid_by, amount).await?;
+        transaction.commit().await?;
+        send_refunds(db, payment_provider, refunds).await;
+        return Err(ErrorResponse::new(
+            "Booking was canceled during the payment, the payment is refunded".to_owned(),
+            StatusCode::CONFLICT,
+        ));
+    }
+    let output = finish_payment(&transaction, booking, payment.id, amount).await?;
+    transaction.commit().await?;
+
+    Ok(output)
 }
 
-async fn set_status_as_paid(
-    app_state: &AppState,
+/// Marks the booking as paid once nothing remains to be paid
+async fn finish_payment(
+    transaction: &DatabaseTransaction,
     booking: booking::Model,
+    payment_id: Uuid,
+    amount: i64,
 ) -> Result<PayBookingOutput, ErrorResponse> {
+    let remaining_amount = booking.total_price - find_paid_amount(transaction, booking.id).await?;
+    let status = if remaining_amount <= 0 && check_can_pay(&booking).is_ok() {
+        let booking_id = booking.id;
+        let status = set_as_paid(transaction, booking).await?;
+        queue_booking_email(transaction, booking_id, BookingEmail::Paid).await?;
+        status
+    } else {
+        booking.status
+    };
+
+    Ok(PayBookingOutput {
+        payment_id,
+        paid_amount: amount,
+        remaining_amount,
+        status,
+    })
+}
+
+fn check_can_pay(booking: &booking::Model) -> Result<(), ErrorResponse> {
     match booking.status {
-        booking::BookingStatus::Unpaid => {
-            let payment_time = Some(Utc::now().naive_utc());
-            let updated_booking = booking::ActiveModel {
-                status: ActiveValue::Set(BookingStatus::Paid),
-                payment_time: ActiveValue::Set(payment_time),
-                ..booking.into_active_model()
-            };
-            updated_booking.update(app_state.db.as_ref()).await?;
-
-            Ok(PayBookingOutput)
-        }
-        booking::BookingStatus::Paid => Err(ErrorResponse::new(
-            "Booking already paid".to_owned(),
+        BookingStatus::Unpaid => Ok(()),
+        BookingStatus::CheckedIn if booking.payment_time.is_none() => Ok(()),
+        BookingStatus::Paid | BookingStatus::CheckedIn | BookingStatus::CheckedOut => Err(
+            ErrorResponse::new("Booking already paid".to_owned(), StatusCode::BAD_REQUEST),
+        ),
+        BookingStatus::Canceled => Err(ErrorResponse::new(
+            "Can't pay, booking is canceled".to_owned(),
             StatusCode::BAD_REQUEST,
         )),
-        booking::BookingStatus::Canceled => Err(ErrorResponse::new(
-            "Can't pay, booking is canceled".to_owned(),
+        BookingStatus::NoShow => Err(ErrorResponse::new(
+            "Can't pay, guest did not show up".to_owned(),
+            StatusCode::BAD_REQUEST,
+        )),
+        BookingStatus::Pending => Err(ErrorResponse::new(
+            "Can't pay, booking is not confirmed yet".to_owned(),
             StatusCode::BAD_REQUEST,
         )),
     }
 }
+
+fn get_payment_amount(
+    input: &PayBookingInput,
+    remaining_amount: i64,
+) -> Result<i64, ErrorResponse> {
+    if remaining_amount <= 0 {
+        return Err(ErrorResponse::new(
+            "Booking already paid".to_owned(),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    let amount = input.amount.unwrap_or(remaining_amount);
+    if amount > remaining_amount {
+        return Err(ErrorResponse::new(
+            format!(
+                "Amount '{}' exceeds the remaining amount '{}'",
+                amount, remaining_amount
+            ),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    Ok(amount)
+}
+
+fn get_card_token(input: &PayBookingInput) -> Option<&str> {
+    match (&input.method, &input.card_token) {
+        (PaymentMethod::Card, Some(card_token)) => Some(card_token),
+        _ => None,
+    }
+}
+
+async fn insert_payment(
+    transaction: &DatabaseTransaction,
+    input: &PayBookingInput,
+    amount: i64,
+) -> Result<payment::Model, ErrorResponse> {
+    let status = if get_card_token(input).is_some() {
+        PaymentStatus::Pending
+    } else {
+        PaymentStatus::Completed
+    };
+    let payment = payment::Model {
+        id: Uuid::new_v4(),
+        booking_id: input.booking_id,
+        admin_id: input.paid_by,
+        payment_type: PaymentType::Payment,
+        amount,
+        currency: CURRENCY.to_owned(),
+        method: input.method.clone(),
+        provider_reference: input.provider_reference.clone(),
+        refunded_payment_id: None,
+        payment_time: Utc::now().naive_utc(),
+        status,
+    }
+    .into_active_model()
+    .insert(transaction)
+    .await?;
+
+    Ok(payment)
+}
+
+async fn set_as_paid(
+    transaction: &DatabaseTransaction,
+    booking: booking::Model,
+) -> Result<BookingStatus, ErrorResponse> {
+    let status = match booking.status {
+        BookingStatus::Unpaid => BookingStatus::Paid,
+        _ => booking.status.clone(),
+    };
+
+    booking::ActiveModel {
+        status: ActiveValue::Set(status.clone()),
+        payment_time: ActiveValue::Set(Some(Utc::now().naive_utc())),
+        ..booking.into_active_model()
+    }
+    .update(transaction)
+    .await?;
+
+    Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::EntityTrait;
+
+    use crate::{
+        persistence::{
+            connect_test_db,
+            test_data::{insert_room_booking, new_booking},
+        },
+        services::payment_provider::{FailingPaymentProvider, MockPaymentProvider},
+        util::add_days,
+    };
+
+    use super::*;
+
+    fn card_payment(booking_id: Uuid) -> PayBookingInput {
+        PayBookingInput {
+            booking_id,
+            method: PaymentMethod::Card,
+            card_token: Some("tok_visa".to_owned()),
+            ..Default::default()
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_pay_booking_with_card() {
+        let db = connect_test_db().await;
+        let start_date = add_days(Utc::now().date_naive(), 30).unwrap();
+        let booking = insert_room_booking(
+            &db,
+            new_booking(
+                BookingStatus::Unpaid,
+                start_date,
+                add_days(start_date, 1).unwrap(),
+            ),
+        )
+        .await;
+
+        let output = pay_booking(&db, &MockPaymentProvider, &card_payment(booking.id))
+            .await
+            .unwrap();
+        assert_eq!(output.status, BookingStatus::Paid);
+        assert_eq!(output.remaining_amount, 0);
+
+        let payment = payment::Entity::find_by_id(output.payment_id)
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(payment.status, PaymentStatus::Completed);
+        assert_eq!(
+            payment.provider_reference,
+            Some(format!("mock-charge-{}", payment.id))
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_pay_booking_when_charge_fails() {
+        let db = connect_test_db().await;
+        let start_date = add_days(Utc::now().date_naive(), 30).unwrap();
+        let booking = insert_room_booking(
+            &db,
+            new_booking(
+                BookingStatus::Unpaid,
+                start_date,
+                add_days(start_date, 1).unwrap(),
+            ),
+        )
+        .await;
+
+        let err = pay_booking(&db, &FailingPaymentProvider, &card_payment(booking.id))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status, StatusCode::BAD_GATEWAY);
+
+        let booking = booking::Entity::find_by_id(booking.id)
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(booking.status, BookingStatus::Unpaid);
+        assert!(booking.payment_time.is_none());
+        let payments = payment::find_payments_for_booking(&db, booking.id)
+            .await
+            .unwrap();
+        assert_eq!(payments.len(), 1);
+        assert_eq!(payments[0].status, PaymentStatus::Failed);
+        assert_eq!(find_paid_amount(&db, booking.id).await.unwrap(), 0);
+
+        let output = pay_booking(&db, &MockPaymentProvider, &card_payment(booking.id))
+            .await
+            .unwrap();
+        assert_eq!(output.status, BookingStatus::Paid);
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::HashSet;
+
+use actix_web::http::StatusCode;
+
+use crate::{
+    api::{
+        booking::{
+            book_room::BookRoomInput,
+            quote_booking::{QuoteBookingInput, QuoteBookingOutput},
+        },
+        error_response::ErrorResponse,
+    },
+    app_state::AppState,
+    services::pricing::{calculate_night_prices, sum_night_prices},
+    validation::Validate,
+};
+
+use super::book_room::{
+    check_is_capacity_enough, check_room_not_occupied, find_room_with_capacity,
+    select_cancellation_policy, validate_guest_user, validate_guests,
+};
+
+/// Runs the checks of booking a room without saving anything, errors caused by the input
+/// are returned as part of the quote
+pub async fn quote_booking_service(
+    app_state: &AppState,
+    input: QuoteBookingInput,
+) -> Result<QuoteBookingOutput, ErrorResponse> {
+    let book_input = convert_to_book_room_input(&input);
+    let (room_capacity, room) = find_room_with_capacity(app_state, &book_input).await?;
+
+    let mut errors = vec![];
+    collect_error(&mut errors, book_input.validate(&app_state.validator))?;
+    collect_error(&mut errors, check_other_guests_not_duplicated(&input))?;
+    collect_error(
+        &mut errors,
+        validate_guests(app_state, &book_input, None).await,
+    )?;
+    let is_capacity_enough = collect_error(
+        &mut errors,
+        check_is_capacity_enough(&book_input, room_capacity),
+    )?;
+    collect_error(
+        &mut errors,
+        validate_guest_user(app_state, &book_input).await,
+    )?;
+    let cancellation_policy_id =
+        match select_cancellation_policy(app_state, &book_input, &room).await {
+            Ok(policy_id) => policy_id,
+            Err(err) => {
+                collect_error(&mut errors, Err(err))?;
+                None
+            }
+        };
+    let is_room_available = collect_error(
+        &mut errors,
+        check_room_not_occupied(app_state.db.as_ref(), &book_input).await,
+    )?;
+
+    let nights = calculate_night_prices(
+        app_state.db.as_ref(),
+        &room,
+        input.start_date,
+        input.end_date,
+    )
+    .await?;
+
+    Ok(QuoteBookingOutput {
+        room_id: room.id,
+        start_date: input.start_date,
+        end_date: input.end_date,
+        total_price: sum_night_prices(&nights),
+        nights,
+        guest_count: (book_input.other_guests.len() + 1) as i16,
+        room_capacity,
+        is_capacity_enough,
+        is_room_available,
+        cancellation_policy_id,
+        errors,
+    })
+}
+
+/// Booking takes the other guests as a set, so duplicates would be dropped silently when converting
+fn check_other_guests_not_duplicated(input: &QuoteBookingInput) -> Result<(), ErrorResponse> {
+    let unique_guests = input.other_guests.iter().collect::<HashSet<_>>();
+
+    if unique_guests.len() != input.other_guests.len() {
+        Err(ErrorResponse::new(
+            "Other guest is duplicated".to_owned(),
+            StatusCode::BAD_REQUEST,
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+fn convert_to_book_room_input(input: &QuoteBookingInput) -> BookRoomInput {
+    BookRoomInput {
+        booked_by: input.quoted_by,
+        room_id: input.room_id,
+        start_date: input.start_date,
+        end_date: input.end_date,
+        main_guest: input.main_guest,
+        other_guests: input.other_guests.iter().copied().collect::<HashSet<_>>(),
+        guest_user_id: input.guest_user_id,
+        cancellation_policy_id: input.cancellation_policy_id,
+    }
+}
+
+/// Records client errors and returns whether the check passed, server errors are propagated
+fn collect_error(
+    errors: &mut Vec<String>,
+    result: Result<(), ErrorResponse>,
+) -> Result<bool, ErrorResponse> {
+    match result {
+        Ok(()) => Ok(true),
+        Err(err) if err.status == StatusCode::INTERNAL_SERVER_ERROR => Err(err),
+        Err(err) => {
+            errors.push(err.error);
+            Ok(false)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::sqlx::types::chrono::Utc;
+    use uuid::Uuid;
+
+    use crate::util::add_days;
+
+    use super::*;
+
+    #[test]
+    fn test_check_other_guests_not_duplicated() {
+        let start_date = add_days(Utc::now().date_naive(), 30).unwrap();
+        let guest_id = Uuid::new_v4();
+        let input = |other_guests| QuoteBookingInput {
+            quoted_by: None,
+            room_id: Uuid::new_v4(),
+            start_date,
+            end_date: add_days(start_date, 2).unwrap(),
+            main_guest: Uuid::new_v4(),
+            other_guests,
+            guest_user_id: None,
+            cancellation_policy_id: None,
+        };
+
+        assert!(check_other_guests_not_duplicated(&input(vec![guest_id, Uuid::new_v4()])).is_ok());
+
+        let error =
+            check_other_guests_not_duplicated(&input(vec![guest_id, guest_id])).unwrap_err();
+        assert_eq!(error.status, StatusCode::BAD_REQUEST);
+    }
+}
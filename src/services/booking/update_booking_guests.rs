@@ -0,0 +1,402 @@
+use std::collections::HashSet;
+
+use actix_web::http::StatusCode;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, DatabaseConnection, DatabaseTransaction,
+    EntityTrait, IntoActiveModel, ModelTrait, QueryFilter, TransactionTrait,
+};
+use uuid::Uuid;
+
+use crate::{
+    api::{
+        booking::{
+            update_booking_guests::{UpdateBookingGuestsInput, UpdateBookingGuestsOutput},
+            MAX_OTHER_GUESTS,
+        },
+        error_response::ErrorResponse,
+    },
+    app_state::AppState,
+    persistence::{bed, booking, booking_guest, guest, room},
+    util::require_some,
+};
+
+use super::{check_is_active, lock_booking, validate_main_guest};
+
+pub async fn update_booking_guests_service(
+    app_state: &AppState,
+    input: UpdateBookingGuestsInput,
+) -> Result<UpdateBookingGuestsOutput, ErrorResponse> {
+    update_booking_guests(app_state.db.as_ref(), &input).await
+}
+
+async fn update_booking_guests(
+    db: &DatabaseConnection,
+    input: &UpdateBookingGuestsInput,
+) -> Result<UpdateBookingGuestsOutput, ErrorResponse> {
+    let transaction = db.begin().await?;
+    let booking = lock_booking(&transaction, input.booking_id.unwrap()).await?;
+    check_is_active(&booking)?;
+    let current_guests = find_booking_guests(&transaction, booking.id).await?;
+    let main_guest = input.main_guest.unwrap_or(booking.main_guest_id);
+    let new_guests = calculate_new_guests(&current_guests, main_guest, input)?;
+    check_is_capacity_enough(&transaction, booking.room_id, new_guests.len()).await?;
+    if main_guest != booking.main_guest_id {
+        validate_main_guest(&transaction, main_guest).await?;
+    }
+    validate_added_guests(&transaction, &current_guests, &new_guests).await?;
+    replace_guests(&transaction, booking.id, &current_guests, &new_guests).await?;
+    update_main_guest(&transaction, booking, main_guest).await?;
+    transaction.commit().await?;
+
+    Ok(UpdateBookingGuestsOutput)
+}
+
+async fn find_booking_guests(
+    transaction: &DatabaseTransaction,
+    booking_id: Uuid,
+) -> Result<HashSet<Uuid>, ErrorResponse> {
+    Ok(booking_guest::Entity::find()
+        .filter(booking_guest::Column::BookingId.eq(booking_id))
+        .all(transaction)
+        .await?
+        .into_iter()
+        .map(|booking_guest| booking_guest.guest_id)
+        .collect())
+}
+
+fn calculate_new_guests(
+    current_guests: &HashSet<Uuid>,
+    main_guest: Uuid,
+    input: &UpdateBookingGuestsInput,
+) -> Result<HashSet<Uuid>, ErrorResponse> {
+    if let Some(guest_id) = input
+        .remove_guests
+        .iter()
+        .find(|guest_id| !current_guests.contains(guest_id))
+    {
+        return Err(ErrorResponse::new(
+            format!("Guest with id '{}' is not in the booking", guest_id),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    if let Some(guest_id) = input
+        .add_guests
+        .iter()
+        .find(|guest_id| current_guests.contains(guest_id))
+    {
+        return Err(ErrorResponse::new(
+            format!("Guest with id '{}' is already in the booking", guest_id),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    if input.remove_guests.contains(&main_guest) {
+        return Err(ErrorResponse::new(
+            "Main guest can't be removed".to_owned(),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    let new_guests: HashSet<Uuid> = current_guests
+        .iter()
+        .chain(input.add_guests.iter())
+        .chain([main_guest].iter())
+        .filter(|guest_id| !input.remove_guests.contains(guest_id))
+        .copied()
+        .collect();
+
+    if new_guests.len() - 1 > MAX_OTHER_GUESTS {
+        return Err(ErrorResponse::new(
+            "Too many guests".to_owned(),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    Ok(new_guests)
+}
+
+async fn check_is_capacity_enough(
+    transaction: &DatabaseTransaction,
+    room_id: Uuid,
+    number_of_guests: usize,
+) -> Result<(), ErrorResponse> {
+    let room_option = room::Entity::find_by_id(room_id).one(transaction).await?;
+    let room = require_some(
+        room_option,
+        || format!("Room with id '{}' not found", room_id),
+        StatusCode::NOT_FOUND,
+    )?;
+    let room_capacity: usize = room
+        .find_related(bed::Entity)
+        .all(transaction)
+        .await?
+        .iter()
+        .map(|bed| bed.total_capacity as usize)
+        .sum();
+
+    if room_capacity >= number_of_guests {
+        Ok(())
+    } else {
+        Err(ErrorResponse::new(
+            format!(
+                "Room capacity '{}' is less than the number of guests '{}'",
+                room_capacity, number_of_guests
+            ),
+            StatusCode::BAD_REQUEST,
+        ))
+    }
+}
+
+async fn validate_added_guests(
+    transaction: &DatabaseTransaction,
+    current_guests: &HashSet<Uuid>,
+    new_guests: &HashSet<Uuid>,
+) -> Result<(), ErrorResponse> {
+    for guest_id in new_guests.difference(current_guests) {
+        let guest = guest::Entity::find_by_id(*guest_id)
+            .one(transaction)
+            .await?;
+        require_some(
+            guest,
+            || format!("Guest with id '{}' not found", guest_id),
+            StatusCode::NOT_FOUND,
+        )?;
+    }
+
+    Ok(())
+}
+
+async fn replace_guests(
+    transaction: &DatabaseTransaction,
+    booking_id: Uuid,
+    current_guests: &HashSet<Uuid>,
+    new_guests: &HashSet<Uuid>,
+) -> Result<(), ErrorResponse> {
+    let removed_guests: Vec<Uuid> = current_guests.difference(new_guests).copied().collect();
+    if !removed_guests.is_empty() {
+        booking_guest::Entity::delete_many()
+            .filter(booking_guest::Column::BookingId.eq(booking_id))
+            .filter(booking_guest::Column::GuestId.is_in(removed_guests))
+            .exec(transaction)
+            .await?;
+    }
+
+    for guest_id in new_guests.difference(current_guests) {
+        booking_guest::Model {
+            guest_id: *guest_id,
+            booking_id,
+        }
+        .into_active_model()
+        .insert(transaction)
+        .await?;
+    }
+
+    Ok(())
+}
+
+async fn update_main_guest(
+    transaction: &DatabaseTransaction,
+    booking: booking::Model,
+    main_guest: Uuid,
+) -> Result<(), ErrorResponse> {
+    if booking.main_guest_id == main_guest {
+        return Ok(());
+    }
+
+    booking::ActiveModel {
+        main_guest_id: ActiveValue::Set(main_guest),
+        ..booking.into_active_model()
+    }
+    .update(transaction)
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::{sqlx::types::chrono::Utc, PaginatorTrait};
+
+    use crate::{
+        persistence::{
+            booking::BookingStatus,
+            connect_test_db,
+            test_data::{insert_guest, insert_main_guest, insert_room_booking, new_booking},
+        },
+        util::{add_days, sub_days},
+    };
+
+    use super::*;
+
+    async fn insert_test_booking(db: &DatabaseConnection) -> booking::Model {
+        let start_date = add_days(Utc::now().date_naive(), 30).unwrap();
+        insert_room_booking(
+            db,
+            new_booking(
+                BookingStatus::Paid,
+                start_date,
+                add_days(start_date, 2).unwrap(),
+            ),
+        )
+        .await
+    }
+
+    async fn insert_guests(db: &DatabaseConnection, count: usize) -> HashSet<Uuid> {
+        let mut guests = HashSet::new();
+        for _ in 0..count {
+            guests.insert(insert_guest(db, Uuid::new_v4()).await.id);
+        }
+
+        guests
+    }
+
+    fn input(booking_id: Uuid) -> UpdateBookingGuestsInput {
+        UpdateBookingGuestsInput {
+            booking_id: Some(booking_id),
+            add_guests: HashSet::new(),
+            remove_guests: HashSet::new(),
+            main_guest: None,
+        }
+    }
+
+    async fn count_guests(db: &DatabaseConnection, booking_id: Uuid) -> u64 {
+        booking_guest::Entity::find()
+            .filter(booking_guest::Column::BookingId.eq(booking_id))
+            .count(db)
+            .await
+            .unwrap()
+    }
+
+    #[actix_web::test]
+    async fn test_capacity_comes_from_the_beds() {
+        let db = connect_test_db().await;
+        let booking = insert_test_booking(&db).await;
+
+        let mut input = input(booking.id);
+        input.add_guests = insert_guests(&db, 2).await;
+        let err = update_booking_guests(&db, &input).await.unwrap_err();
+        assert_eq!(err.status, StatusCode::BAD_REQUEST);
+        assert!(err.error.contains("capacity"));
+        assert_eq!(count_guests(&db, booking.id).await, 1);
+
+        bed::Model {
+            id: Uuid::new_v4(),
+            room_id: booking.room_id,
+            bed_size: bed::BedSize::Single,
+            count: 1,
+            total_capacity: 1,
+        }
+        .into_active_model()
+        .insert(&db)
+        .await
+        .unwrap();
+        update_booking_guests(&db, &input).await.unwrap();
+        assert_eq!(count_guests(&db, booking.id).await, 3);
+    }
+
+    #[actix_web::test]
+    async fn test_at_most_ten_other_guests() {
+        let db = connect_test_db().await;
+        let booking = insert_test_booking(&db).await;
+        bed::Model {
+            id: Uuid::new_v4(),
+            room_id: booking.room_id,
+            bed_size: bed::BedSize::Single,
+            count: 20,
+            total_capacity: 20,
+        }
+        .into_active_model()
+        .insert(&db)
+        .await
+        .unwrap();
+
+        let mut input = input(booking.id);
+        input.add_guests = insert_guests(&db, MAX_OTHER_GUESTS).await;
+        update_booking_guests(&db, &input).await.unwrap();
+
+        input.add_guests = insert_guests(&db, 1).await;
+        let err = update_booking_guests(&db, &input).await.unwrap_err();
+        assert_eq!(err.status, StatusCode::BAD_REQUEST);
+        assert_eq!(err.error, "Too many guests");
+        assert_eq!(
+            count_guests(&db, booking.id).await,
+            MAX_OTHER_GUESTS as u64 + 1
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_main_guest_is_not_duplicated() {
+        let db = connect_test_db().await;
+        let booking = insert_test_booking(&db).await;
+
+        let mut input = input(booking.id);
+        input.add_guests = HashSet::from([booking.main_guest_id]);
+        let err = update_booking_guests(&db, &input).await.unwrap_err();
+        assert_eq!(err.status, StatusCode::BAD_REQUEST);
+
+        input.add_guests = HashSet::new();
+        input.main_guest = Some(booking.main_guest_id);
+        update_booking_guests(&db, &input).await.unwrap();
+        assert_eq!(count_guests(&db, booking.id).await, 1);
+    }
+
+    #[actix_web::test]
+    async fn test_main_guest_must_be_an_adult() {
+        let db = connect_test_db().await;
+        let booking = insert_test_booking(&db).await;
+        let child =
+            insert_main_guest(&db, sub_days(Utc::now().date_naive(), 10 * 365).unwrap()).await;
+
+        let mut input = input(booking.id);
+        input.main_guest = Some(child.id);
+        let err = update_booking_guests(&db, &input).await.unwrap_err();
+        assert_eq!(err.status, StatusCode::BAD_REQUEST);
+
+        let saved = booking::Entity::find_by_id(booking.id)
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(saved.main_guest_id, booking.main_guest_id);
+        assert_eq!(count_guests(&db, booking.id).await, 1);
+    }
+
+    #[actix_web::test]
+    async fn test_add_remove_and_swap_main_guest() {
+        let db = connect_test_db().await;
+        let booking = insert_test_booking(&db).await;
+        let new_main_guest =
+            insert_main_guest(&db, sub_days(Utc::now().date_naive(), 40 * 365).unwrap())
+                .await
+                .id;
+
+        let mut input = input(booking.id);
+        input.main_guest = Some(new_main_guest);
+        update_booking_guests(&db, &input).await.unwrap();
+        let saved = booking::Entity::find_by_id(booking.id)
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(saved.main_guest_id, new_main_guest);
+        assert_eq!(count_guests(&db, booking.id).await, 2);
+
+        input.main_guest = None;
+        input.remove_guests = HashSet::from([new_main_guest]);
+        let err = update_booking_guests(&db, &input).await.unwrap_err();
+        assert_eq!(err.error, "Main guest can't be removed");
+
+        input.remove_guests = HashSet::from([booking.main_guest_id]);
+        update_booking_guests(&db, &input).await.unwrap();
+        let guests: Vec<_> = booking_guest::Entity::find()
+            .filter(booking_guest::Column::BookingId.eq(booking.id))
+            .all(&db)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|booking_guest| booking_guest.guest_id)
+            .collect();
+        assert_eq!(guests, vec![new_main_guest]);
+    }
+}
@@ -0,0 +1,155 @@
+use actix_web::http::StatusCode;
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter};
+use uuid::Uuid;
+
+use crate::{
+    api::error_response::ErrorResponse,
+    constants::CURRENCY,
+    persistence::{booking, booking_guest, guest, room, user},
+    services::{email_outbox::queue_email, email_template::EmailTemplateName},
+    util::require_some,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookingEmail {
+    Created,
+    Paid,
+    Modified,
+    Canceled,
+    Reminder,
+    Expired,
+    RoomUnavailable,
+}
+impl BookingEmail {
+    fn template(&self) -> EmailTemplateName {
+        match self {
+            BookingEmail::Created => EmailTemplateName::BookingCreated,
+            BookingEmail::Paid => EmailTemplateName::BookingPaid,
+            BookingEmail::Modified => EmailTemplateName::BookingModified,
+            BookingEmail::Canceled => EmailTemplateName::BookingCanceled,
+            BookingEmail::Reminder => EmailTemplateName::BookingReminder,
+            BookingEmail::Expired => EmailTemplateName::BookingExpired,
+            BookingEmail::RoomUnavailable => EmailTemplateName::RoomUnavailable,
+        }
+    }
+}
+
+struct BookingDetails {
+    booking: booking::Model,
+    room_number: String,
+    guests: Vec<String>,
+}
+
+fn template_params(details: &BookingDetails) -> Vec<(&'static str, String)> {
+    vec![
+        ("bookingId", details.booking.id.to_string()),
+        ("roomNumber", details.room_number.clone()),
+        ("startDate", details.booking.start_date.to_string()),
+        ("endDate", details.booking.end_date.to_string()),
+        ("guests", details.guests.join(", ")),
+        ("totalPrice", details.booking.total_price.to_string()),
+        ("currency", CURRENCY.to_owned()),
+    ]
+}
+
+async fn find_details<T>(db: &T, booking_id: Uuid) -> Result<BookingDetails, ErrorResponse>
+where
+    T: ConnectionTrait,
+{
+    let booking = require_some(
+        booking::Entity::find_by_id(booking_id).one(db).await?,
+        || format!("Booking with id '{}' not found", booking_id),
+        StatusCode::NOT_FOUND,
+    )?;
+    let room = require_some(
+        room::Entity::find_by_id(booking.room_id).one(db).await?,
+        || format!("Room with id '{}' not found", booking.room_id),
+        StatusCode::NOT_FOUND,
+    )?;
+    let guests = guest::Entity::find()
+        .inner_join(booking_guest::Entity)
+        .filter(booking_guest::Column::BookingId.eq(booking_id))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|guest| format!("{} {}", guest.first_name, guest.last_name))
+        .collect();
+
+    Ok(BookingDetails {
+        booking,
+        room_number: room.room_number,
+        guests,
+    })
+}
+
+/// Queues the email for the user linked to the booking, bookings without a user are skipped
+pub async fn queue_booking_email<T>(
+    db: &T,
+    booking_id: Uuid,
+    email: BookingEmail,
+) -> Result<(), ErrorResponse>
+where
+    T: ConnectionTrait,
+{
+    let details = find_details(db, booking_id).await?;
+    let Some(user_id) = details.booking.user_id else {
+        return Ok(());
+    };
+    let Some(user) = user::find_user_by_id(db, &user_id).await? else {
+        return Ok(());
+    };
+
+    queue_email(
+        db,
+        user.email,
+        user.language,
+        email.template(),
+        &template_params(&details),
+        None,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::sqlx::types::chrono::Utc;
+
+    use crate::{
+        services::email_template::{EmailTemplates, DEFAULT_LANGUAGE},
+        util::add_days,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_rendered_email_contains_booking_details() {
+        let start_date = Utc::now().date_naive();
+        let details = BookingDetails {
+            booking: booking::Model {
+                id: Uuid::new_v4(),
+                start_date,
+                end_date: add_days(start_date, 2).unwrap(),
+                total_price: 250,
+                ..Default::default()
+            },
+            room_number: "201A".to_owned(),
+            guests: vec!["John Smith".to_owned(), "Jane Smith".to_owned()],
+        };
+
+        let templates = EmailTemplates::load(None, DEFAULT_LANGUAGE);
+        let email = templates.render(
+            BookingEmail::Paid.template(),
+            None,
+            &template_params(&details),
+        );
+        let body = email.text;
+
+        assert_eq!(email.subject, "Booking paid");
+        assert!(body.starts_with("Your booking has been paid."));
+        assert!(email.html.contains("<td>201A</td>"));
+        assert!(body.contains("Room: 201A"));
+        assert!(body.contains(&format!("Departure: {}", add_days(start_date, 2).unwrap())));
+        assert!(body.contains("Guests: John Smith, Jane Smith"));
+        assert!(body.contains(&format!("Total price: 250 {}", CURRENCY)));
+    }
+}
@@ -0,0 +1,28 @@
+use actix_web::http::StatusCode;
+use sea_orm::{ConnectionTrait, EntityTrait};
+use uuid::Uuid;
+
+use crate::{
+    api::error_response::ErrorResponse, persistence::cancellation_policy, util::require_some,
+};
+
+pub mod add_cancellation_policy;
+pub mod get_cancellation_policies;
+
+pub async fn find_cancellation_policy<T>(
+    db: &T,
+    policy_id: Uuid,
+) -> Result<cancellation_policy::Model, ErrorResponse>
+where
+    T: ConnectionTrait,
+{
+    let policy = cancellation_policy::Entity::find_by_id(policy_id)
+        .one(db)
+        .await?;
+
+    require_some(
+        policy,
+        || format!("Cancellation policy with id '{}' not found", policy_id),
+        StatusCode::NOT_FOUND,
+    )
+}
@@ -0,0 +1,49 @@
+use actix_web::http::StatusCode;
+use sea_orm::{ActiveModelTrait, IntoActiveModel};
+use uuid::Uuid;
+
+use crate::{
+    api::{
+        cancellation_policy::add_cancellation_policy::{
+            AddCancellationPolicyInput, AddCancellationPolicyOutput,
+        },
+        error_response::ErrorResponse,
+    },
+    app_state::AppState,
+    persistence::cancellation_policy,
+};
+
+pub async fn add_cancellation_policy_service(
+    app_state: &AppState,
+    input: AddCancellationPolicyInput,
+) -> Result<AddCancellationPolicyOutput, ErrorResponse> {
+    let name = input.name.trim().to_owned();
+    check_name_not_used(app_state, &name).await?;
+
+    let policy = cancellation_policy::Model {
+        id: Uuid::new_v4(),
+        name,
+        free_cancellation_days: input.free_cancellation_days,
+        penalty_type: input.penalty_type,
+        penalty_percentage: input.penalty_percentage.unwrap_or(0),
+    }
+    .into_active_model()
+    .insert(app_state.db.as_ref())
+    .await?;
+
+    Ok(AddCancellationPolicyOutput {
+        cancellation_policy_id: policy.id,
+    })
+}
+
+async fn check_name_not_used(app_state: &AppState, name: &str) -> Result<(), ErrorResponse> {
+    let existing = cancellation_policy::find_first_by_name(app_state.db.as_ref(), name).await?;
+    if existing.is_some() {
+        return Err(ErrorResponse::new(
+            format!("Cancellation policy '{}' already exists", name),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use crate::{
+    api::{
+        cancellation_policy::{
+            get_cancellation_policies::{
+                GetCancellationPoliciesInput, GetCancellationPoliciesOutput,
+            },
+            CancellationPolicy,
+        },
+        error_response::ErrorResponse,
+    },
+    app_state::AppState,
+    persistence::cancellation_policy,
+};
+
+pub async fn get_cancellation_policies_service(
+    app_state: &AppState,
+    _input: GetCancellationPoliciesInput,
+) -> Result<GetCancellationPoliciesOutput, ErrorResponse> {
+    let policies = cancellation_policy::find_all_policies(app_state.db.as_ref())
+        .await?
+        .into_iter()
+        .map(|policy| CancellationPolicy {
+            id: policy.id,
+            name: policy.name,
+            free_cancellation_days: policy.free_cancellation_days,
+            penalty_type: policy.penalty_type,
+            penalty_percentage: policy.penalty_percentage,
+        })
+        .collect();
+
+    Ok(GetCancellationPoliciesOutput { policies })
+}
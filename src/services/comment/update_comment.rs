@@ -30,11 +30,11 @@ async fn find_comment(
         .one(app_state.db.as_ref())
         .await?;
 
-    Ok(require_some(
+    require_some(
         comment,
         || format!("Comment with id '{}' not found", input.comment_id),
         StatusCode::NOT_FOUND,
-    )?)
+    )
 }
 
 fn check_can_update_comment(
@@ -45,7 +45,7 @@ fn check_can_update_comment(
         return Ok(());
     }
 
-    if comment.user_id == input.user_id.clone().unwrap() {
+    if comment.user_id == input.user_id.unwrap() {
         Ok(())
     } else {
         let message = "Comment not posted by user".to_owned();
@@ -0,0 +1,96 @@
+use sea_orm::{prelude::DateTime, sqlx::types::chrono::Utc, ConnectionTrait};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::{
+    api::error_response::ErrorResponse,
+    persistence::email_outbox::{self, EmailStatus},
+    services::email_template::EmailTemplateName,
+};
+
+pub mod get_outbox_emails;
+pub mod retry_email;
+
+/// Stores the email with the given connection so it is sent only if the transaction commits,
+/// it is given up on after `expiry_time`
+pub async fn queue_email<T>(
+    db: &T,
+    recipient: String,
+    language: Option<String>,
+    template: EmailTemplateName,
+    params: &[(&str, String)],
+    expiry_time: Option<DateTime>,
+) -> Result<(), ErrorResponse>
+where
+    T: ConnectionTrait,
+{
+    let params: Map<String, Value> = params
+        .iter()
+        .map(|(name, value)| (name.to_string(), Value::String(value.clone())))
+        .collect();
+    let now = Utc::now().naive_utc();
+
+    email_outbox::insert_email(
+        db,
+        email_outbox::Model {
+            id: Uuid::new_v4(),
+            recipient,
+            language,
+            template: template.dir_name().to_owned(),
+            params: Value::Object(params).to_string(),
+            status: EmailStatus::Pending,
+            attempts: 0,
+            next_attempt_time: now,
+            last_error: None,
+            created_time: now,
+            sent_time: None,
+            expiry_time,
+        },
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Params can hold secrets like one time passwords, so they are not kept once they're not needed
+pub const CLEARED_PARAMS: &str = "{}";
+
+pub fn parse_params(params: &str) -> Vec<(String, String)> {
+    let Ok(Value::Object(params)) = serde_json::from_str::<Value>(params) else {
+        return vec![];
+    };
+
+    params
+        .into_iter()
+        .filter_map(|(name, value)| match value {
+            Value::String(value) => Some((name, value)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Delay before the next attempt after `attempts` failed ones, doubling every time
+pub fn retry_delay_secs(base_delay_secs: u64, attempts: i16) -> u64 {
+    let exponent = (attempts.max(1) - 1).min(16) as u32;
+    base_delay_secs.saturating_mul(2u64.pow(exponent))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay_doubles() {
+        assert_eq!(retry_delay_secs(30, 1), 30);
+        assert_eq!(retry_delay_secs(30, 2), 60);
+        assert_eq!(retry_delay_secs(30, 4), 240);
+        assert_eq!(retry_delay_secs(30, 100), 30 * 2u64.pow(16));
+    }
+
+    #[test]
+    fn test_parse_params() {
+        let params = parse_params(r#"{"otpCode":"ABC","count":1}"#);
+        assert_eq!(params, vec![("otpCode".to_owned(), "ABC".to_owned())]);
+        assert!(parse_params("not json").is_empty());
+    }
+}
@@ -0,0 +1,44 @@
+use crate::{
+    api::{
+        email_outbox::{
+            get_outbox_emails::{GetOutboxEmailsInput, GetOutboxEmailsOutput},
+            OutboxEmail,
+        },
+        error_response::ErrorResponse,
+    },
+    app_state::AppState,
+    persistence::email_outbox,
+};
+
+pub async fn get_outbox_emails_service(
+    app_state: &AppState,
+    input: GetOutboxEmailsInput,
+) -> Result<GetOutboxEmailsOutput, ErrorResponse> {
+    let (total_size, emails) = email_outbox::find_paged_emails(
+        app_state.db.as_ref(),
+        input.status,
+        input.page,
+        input.size,
+    )
+    .await?;
+
+    Ok(GetOutboxEmailsOutput {
+        total_size,
+        emails: emails.into_iter().map(convert_email).collect(),
+    })
+}
+
+fn convert_email(email: email_outbox::Model) -> OutboxEmail {
+    OutboxEmail {
+        id: email.id,
+        recipient: email.recipient,
+        template: email.template,
+        status: email.status,
+        attempts: email.attempts,
+        next_attempt_time: email.next_attempt_time,
+        last_error: email.last_error,
+        created_time: email.created_time,
+        sent_time: email.sent_time,
+        expiry_time: email.expiry_time,
+    }
+}
@@ -0,0 +1,77 @@
+use actix_web::http::StatusCode;
+use sea_orm::{
+    sqlx::types::chrono::Utc, ActiveModelTrait, ActiveValue, EntityTrait, IntoActiveModel,
+};
+
+use crate::{
+    api::{
+        email_outbox::retry_email::{RetryEmailInput, RetryEmailOutput},
+        error_response::ErrorResponse,
+    },
+    app_state::AppState,
+    persistence::email_outbox::{self, EmailStatus},
+    util::require_some,
+};
+
+pub async fn retry_email_service(
+    app_state: &AppState,
+    input: RetryEmailInput,
+) -> Result<RetryEmailOutput, ErrorResponse> {
+    let email = find_email(app_state, &input).await?;
+    check_can_retry(&email)?;
+    schedule_retry(app_state, email).await?;
+
+    Ok(RetryEmailOutput)
+}
+
+async fn find_email(
+    app_state: &AppState,
+    input: &RetryEmailInput,
+) -> Result<email_outbox::Model, ErrorResponse> {
+    let email = email_outbox::Entity::find_by_id(input.email_id)
+        .one(app_state.db.as_ref())
+        .await?;
+
+    require_some(
+        email,
+        || format!("Email with id '{}' not found", input.email_id),
+        StatusCode::NOT_FOUND,
+    )
+}
+
+fn check_can_retry(email: &email_outbox::Model) -> Result<(), ErrorResponse> {
+    if email.status != EmailStatus::Failed {
+        return Err(ErrorResponse::new(
+            format!("Can't retry email with status '{:?}'", email.status),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    if email
+        .expiry_time
+        .is_some_and(|expiry_time| expiry_time <= Utc::now().naive_utc())
+    {
+        return Err(ErrorResponse::new(
+            format!("Email with id '{}' has expired", email.id),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    Ok(())
+}
+
+async fn schedule_retry(
+    app_state: &AppState,
+    email: email_outbox::Model,
+) -> Result<(), ErrorResponse> {
+    email_outbox::ActiveModel {
+        status: ActiveValue::Set(EmailStatus::Pending),
+        attempts: ActiveValue::Set(0),
+        next_attempt_time: ActiveValue::Set(Utc::now().naive_utc()),
+        ..email.into_active_model()
+    }
+    .update(app_state.db.as_ref())
+    .await?;
+
+    Ok(())
+}
@@ -1,72 +1,132 @@
 use std::error::Error;
+use std::path::PathBuf;
+use std::sync::Arc;
 
-use lettre::message::Mailbox;
-use lettre::AsyncTransport;
-use lettre::{
-    message::header::ContentType, transport::smtp::authentication::Credentials, AsyncSmtpTransport,
-    Message, Tokio1Executor,
-};
-use log::error;
+use lettre::message::{Mailbox, MultiPart};
+use lettre::Message;
 
 use crate::api::error_response::ErrorResponse;
 use crate::app_state::EnvironmentVariables;
-use crate::constants::{ENV_EMAIL_PASSWORD, ENV_EMAIL_RELAY, ENV_EMAIL_USERNAME};
+use crate::constants::{
+    DEFAULT_EMAIL_SENDER, ENV_EMAIL_DEFAULT_LANGUAGE, ENV_EMAIL_SENDER, ENV_EMAIL_TEMPLATE_DIR,
+    ENV_EMAIL_USERNAME,
+};
+use crate::services::email_template::{
+    EmailTemplateName, EmailTemplates, RenderedEmail, DEFAULT_LANGUAGE,
+};
+use crate::services::email_transport::{load_email_transport, EmailTransport, OutgoingEmail};
 use crate::util::error_to_response;
 
 pub struct EmailService {
     email: Mailbox,
-    mailer: AsyncSmtpTransport<Tokio1Executor>,
+    transport: Arc<dyn EmailTransport>,
+    templates: EmailTemplates,
 }
 impl EmailService {
     pub fn new(env: &EnvironmentVariables) -> Self {
-        let credentials = Credentials::new(
-            env.get(ENV_EMAIL_USERNAME).to_string(),
-            env.get(ENV_EMAIL_PASSWORD).to_string(),
-        );
+        let sender = env
+            .get_optional::<String>(ENV_EMAIL_SENDER)
+            .or_else(|| env.get_optional(ENV_EMAIL_USERNAME))
+            .unwrap_or_else(|| DEFAULT_EMAIL_SENDER.to_owned());
+
+        Self::with_transport(
+            sender.parse().expect("Invalid sender email"),
+            load_email_transport(env),
+            load_templates(env),
+        )
+    }
+
+    pub fn with_transport(
+        email: Mailbox,
+        transport: Arc<dyn EmailTransport>,
+        templates: EmailTemplates,
+    ) -> Self {
         Self {
-            email: env
-                .get(ENV_EMAIL_USERNAME)
-                .parse()
-                .expect("Invalid sender email"),
-            mailer: AsyncSmtpTransport::<Tokio1Executor>::relay(env.get(ENV_EMAIL_RELAY))
-                .expect("Invalid relay")
-                .credentials(credentials)
-                .build(),
+            email,
+            transport,
+            templates,
         }
     }
 
-    async fn try_send_text_mail(
+    fn build_email(
         &self,
         to: String,
-        subject: String,
-        body: String,
-    ) -> Result<(), Box<dyn Error>> {
-        let email = Message::builder()
+        email: RenderedEmail,
+    ) -> Result<OutgoingEmail, Box<dyn Error>> {
+        let message = Message::builder()
             .from(self.email.clone())
             .to(to.parse()?)
-            .subject(subject)
-            .header(ContentType::TEXT_PLAIN)
-            .body(body)?;
-
-        match self.mailer.send(email).await {
-            Ok(_) => Ok(()),
-            Err(err) => {
-                error!("Could not send email: {err}");
-                Err(Box::new(err))
-            }
-        }
+            .subject(email.subject.clone())
+            .multipart(MultiPart::alternative_plain_html(
+                email.text.clone(),
+                email.html.clone(),
+            ))?;
+
+        Ok(OutgoingEmail {
+            to,
+            content: email,
+            message,
+        })
     }
 
-    pub async fn send_text_mail(
+    /// Renders the template in the recipient's language and sends it with text and html parts
+    pub async fn send_mail(
         &self,
         to: String,
-        subject: String,
-        body: String,
+        language: Option<&str>,
+        template: EmailTemplateName,
+        params: &[(&str, String)],
     ) -> Result<(), ErrorResponse> {
-        if let Err(err) = self.try_send_text_mail(to, subject, body).await {
-            Err(error_to_response(err))
-        } else {
-            Ok(())
-        }
+        let email = self.templates.render(template, language, params);
+        let email = self.build_email(to, email).map_err(error_to_response)?;
+
+        self.transport.send(&email).await
+    }
+}
+
+fn load_templates(env: &EnvironmentVariables) -> EmailTemplates {
+    let dir = env.get_optional::<PathBuf>(ENV_EMAIL_TEMPLATE_DIR);
+    let default_language = env
+        .get_optional::<String>(ENV_EMAIL_DEFAULT_LANGUAGE)
+        .unwrap_or_else(|| DEFAULT_LANGUAGE.to_owned());
+
+    EmailTemplates::load(dir.as_deref(), &default_language)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::services::email_transport::InMemoryEmailTransport;
+
+    use super::*;
+
+    #[actix_web::test]
+    async fn test_in_memory_transport_captures_otp() {
+        let transport = Arc::new(InMemoryEmailTransport::default());
+        let email_service = EmailService::with_transport(
+            DEFAULT_EMAIL_SENDER.parse().unwrap(),
+            transport.clone(),
+            EmailTemplates::load(None, DEFAULT_LANGUAGE),
+        );
+
+        email_service
+            .send_mail(
+                "user@example.com".to_owned(),
+                None,
+                EmailTemplateName::Otp,
+                &[("otpCode", "ABCD1234".to_owned())],
+            )
+            .await
+            .unwrap();
+
+        let email = transport.last_email_to("user@example.com").unwrap();
+        assert_eq!(email.content.subject, "Reset password code");
+        assert!(email.content.text.contains("ABCD1234"));
+        assert!(transport.last_email_to("other@example.com").is_none());
+        assert_eq!(transport.sent_emails().len(), 1);
+
+        let invalid = email_service
+            .send_mail("invalid".to_owned(), None, EmailTemplateName::Otp, &[])
+            .await;
+        assert!(invalid.is_err());
     }
 }
@@ -0,0 +1,288 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use log::{info, warn};
+
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+const SUBJECT_FILE: &str = "subject.txt";
+const TEXT_FILE: &str = "text.txt";
+const HTML_FILE: &str = "html.html";
+
+macro_rules! builtin_template {
+    ($name:literal) => {
+        EmailTemplate {
+            subject: include_str!(concat!("../../templates/email/en/", $name, "/subject.txt"))
+                .to_owned(),
+            text: include_str!(concat!("../../templates/email/en/", $name, "/text.txt")).to_owned(),
+            html: include_str!(concat!("../../templates/email/en/", $name, "/html.html"))
+                .to_owned(),
+        }
+    };
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EmailTemplateName {
+    Otp,
+    BookingCreated,
+    BookingPaid,
+    BookingModified,
+    BookingCanceled,
+    BookingReminder,
+    BookingExpired,
+    RoomUnavailable,
+}
+impl EmailTemplateName {
+    pub const ALL: [EmailTemplateName; 8] = [
+        EmailTemplateName::Otp,
+        EmailTemplateName::BookingCreated,
+        EmailTemplateName::BookingPaid,
+        EmailTemplateName::BookingModified,
+        EmailTemplateName::BookingCanceled,
+        EmailTemplateName::BookingReminder,
+        EmailTemplateName::BookingExpired,
+        EmailTemplateName::RoomUnavailable,
+    ];
+
+    /// Name of the template directory inside a language directory
+    pub fn dir_name(&self) -> &'static str {
+        match self {
+            EmailTemplateName::Otp => "otp",
+            EmailTemplateName::BookingCreated => "booking_created",
+            EmailTemplateName::BookingPaid => "booking_paid",
+            EmailTemplateName::BookingModified => "booking_modified",
+            EmailTemplateName::BookingCanceled => "booking_canceled",
+            EmailTemplateName::BookingReminder => "booking_reminder",
+            EmailTemplateName::BookingExpired => "booking_expired",
+            EmailTemplateName::RoomUnavailable => "room_unavailable",
+        }
+    }
+
+    pub fn from_dir_name(dir_name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|name| name.dir_name() == dir_name)
+    }
+
+    fn builtin(&self) -> EmailTemplate {
+        match self {
+            EmailTemplateName::Otp => builtin_template!("otp"),
+            EmailTemplateName::BookingCreated => builtin_template!("booking_created"),
+            EmailTemplateName::BookingPaid => builtin_template!("booking_paid"),
+            EmailTemplateName::BookingModified => builtin_template!("booking_modified"),
+            EmailTemplateName::BookingCanceled => builtin_template!("booking_canceled"),
+            EmailTemplateName::BookingReminder => builtin_template!("booking_reminder"),
+            EmailTemplateName::BookingExpired => builtin_template!("booking_expired"),
+            EmailTemplateName::RoomUnavailable => builtin_template!("room_unavailable"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmailTemplate {
+    pub subject: String,
+    pub text: String,
+    pub html: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderedEmail {
+    pub subject: String,
+    pub text: String,
+    pub html: String,
+}
+
+/// Templates by language, the default language always has every template
+pub struct EmailTemplates {
+    default_language: String,
+    templates: HashMap<(String, EmailTemplateName), EmailTemplate>,
+}
+impl EmailTemplates {
+    /// Starts from the built-in english templates and overrides them with the files found in
+    /// `<dir>/<language>/<template>/{subject.txt,text.txt,html.html}`
+    pub fn load(dir: Option<&Path>, default_language: &str) -> Self {
+        let mut templates = HashMap::new();
+        for name in EmailTemplateName::ALL {
+            templates.insert((DEFAULT_LANGUAGE.to_owned(), name), name.builtin());
+        }
+
+        if let Some(dir) = dir {
+            load_dir(dir, &mut templates);
+        }
+
+        for name in EmailTemplateName::ALL {
+            if !templates.contains_key(&(default_language.to_owned(), name)) {
+                panic!(
+                    "Email template '{}' missing for default language '{}'",
+                    name.dir_name(),
+                    default_language
+                );
+            }
+        }
+
+        Self {
+            default_language: default_language.to_owned(),
+            templates,
+        }
+    }
+
+    fn find(&self, name: EmailTemplateName, language: Option<&str>) -> &EmailTemplate {
+        language
+            .and_then(|language| self.templates.get(&(language.to_owned(), name)))
+            .unwrap_or_else(|| &self.templates[&(self.default_language.clone(), name)])
+    }
+
+    /// Renders the template in the given language, falling back to the default language
+    pub fn render(
+        &self,
+        name: EmailTemplateName,
+        language: Option<&str>,
+        params: &[(&str, String)],
+    ) -> RenderedEmail {
+        let template = self.find(name, language);
+
+        RenderedEmail {
+            subject: render(template.subject.trim(), params, false),
+            text: render(&template.text, params, false),
+            html: render(&template.html, params, true),
+        }
+    }
+}
+
+fn read_part(dir: &Path, file: &str, fallback: Option<&String>) -> Option<String> {
+    match fs::read_to_string(dir.join(file)) {
+        Ok(content) => Some(content),
+        Err(_) => fallback.cloned(),
+    }
+}
+
+fn load_dir(dir: &Path, templates: &mut HashMap<(String, EmailTemplateName), EmailTemplate>) {
+    let language_dirs = fs::read_dir(dir).unwrap_or_else(|err| {
+        panic!(
+            "Can't read email template directory '{}': {}",
+            dir.display(),
+            err
+        )
+    });
+
+    for language_dir in language_dirs.flatten() {
+        if !language_dir.path().is_dir() {
+            continue;
+        }
+        let language = language_dir.file_name().to_string_lossy().to_string();
+
+        for name in EmailTemplateName::ALL {
+            let template_dir = language_dir.path().join(name.dir_name());
+            if !template_dir.is_dir() {
+                continue;
+            }
+
+            let existing = templates.get(&(language.clone(), name));
+            let parts = (
+                read_part(&template_dir, SUBJECT_FILE, existing.map(|t| &t.subject)),
+                read_part(&template_dir, TEXT_FILE, existing.map(|t| &t.text)),
+                read_part(&template_dir, HTML_FILE, existing.map(|t| &t.html)),
+            );
+            let (Some(subject), Some(text), Some(html)) = parts else {
+                warn!(
+                    "Skipping incomplete email template '{}'",
+                    template_dir.display()
+                );
+                continue;
+            };
+
+            info!("Loaded email template '{}'", template_dir.display());
+            templates.insert(
+                (language.clone(), name),
+                EmailTemplate {
+                    subject,
+                    text,
+                    html,
+                },
+            );
+        }
+    }
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Replaces `{{name}}` placeholders, unknown placeholders are left as they are
+fn render(template: &str, params: &[(&str, String)], is_html: bool) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}") else {
+            break;
+        };
+        let end = start + end;
+        result.push_str(&rest[..start]);
+
+        let key = rest[start + 2..end].trim();
+        match params.iter().find(|(name, _)| *name == key) {
+            Some((_, value)) if is_html => result.push_str(&escape_html(value)),
+            Some((_, value)) => result.push_str(value),
+            None => result.push_str(&rest[start..end + 2]),
+        }
+        rest = &rest[end + 2..];
+    }
+    result.push_str(rest);
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env::temp_dir;
+
+    use uuid::Uuid;
+
+    use super::*;
+
+    #[test]
+    fn test_render_replaces_placeholders() {
+        let params = [("name", "<John>".to_owned()), ("code", "123".to_owned())];
+
+        assert_eq!(
+            render("Hi {{name}}, {{ code }} {{unknown}}", &params, false),
+            "Hi <John>, 123 {{unknown}}"
+        );
+        assert_eq!(
+            render("<p>{{name}}</p> {{code", &params, true),
+            "<p>&lt;John&gt;</p> {{code"
+        );
+    }
+
+    #[test]
+    fn test_load_overrides_and_language_fallback() {
+        let dir = temp_dir().join(format!("email_templates_{}", Uuid::new_v4()));
+        let otp_dir = dir.join("bg").join(EmailTemplateName::Otp.dir_name());
+        fs::create_dir_all(&otp_dir).unwrap();
+        fs::write(otp_dir.join(SUBJECT_FILE), "Код за смяна на паролата\n").unwrap();
+        fs::write(otp_dir.join(TEXT_FILE), "Код: {{otpCode}}").unwrap();
+        fs::write(otp_dir.join(HTML_FILE), "<p>Код: {{otpCode}}</p>").unwrap();
+
+        let templates = EmailTemplates::load(Some(&dir), DEFAULT_LANGUAGE);
+        fs::remove_dir_all(&dir).unwrap();
+        let params = [("otpCode", "ABCD1234".to_owned())];
+
+        let bulgarian = templates.render(EmailTemplateName::Otp, Some("bg"), &params);
+        assert_eq!(bulgarian.subject, "Код за смяна на паролата");
+        assert_eq!(bulgarian.text, "Код: ABCD1234");
+        assert_eq!(bulgarian.html, "<p>Код: ABCD1234</p>");
+
+        let fallback = templates.render(EmailTemplateName::Otp, Some("de"), &params);
+        assert_eq!(fallback.subject, "Reset password code");
+        assert!(fallback.text.contains("ABCD1234"));
+        assert!(fallback.html.contains("<strong>ABCD1234</strong>"));
+
+        let missing = templates.render(EmailTemplateName::BookingPaid, Some("bg"), &[]);
+        assert_eq!(missing.subject, "Booking paid");
+    }
+}
@@ -0,0 +1,167 @@
+use std::{
+    fs,
+    future::Future,
+    path::PathBuf,
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
+
+use actix_web::http::StatusCode;
+use lettre::{
+    transport::smtp::authentication::Credentials, AsyncSmtpTransport, AsyncTransport, Message,
+    Tokio1Executor,
+};
+use log::{error, info};
+use uuid::Uuid;
+
+use crate::{
+    api::error_response::ErrorResponse,
+    app_state::EnvironmentVariables,
+    constants::{
+        DEFAULT_EMAIL_FILE_DIR, ENV_EMAIL_FILE_DIR, ENV_EMAIL_PASSWORD, ENV_EMAIL_RELAY,
+        ENV_EMAIL_TRANSPORT, ENV_EMAIL_USERNAME,
+    },
+    services::email_template::RenderedEmail,
+};
+
+pub type EmailFuture<'a> = Pin<Box<dyn Future<Output = Result<(), ErrorResponse>> + Send + 'a>>;
+
+#[derive(Debug, Clone)]
+pub struct OutgoingEmail {
+    pub to: String,
+    pub content: RenderedEmail,
+    pub message: Message,
+}
+
+/// Delivers an already rendered email
+pub trait EmailTransport: Send + Sync {
+    fn send<'a>(&'a self, email: &'a OutgoingEmail) -> EmailFuture<'a>;
+}
+
+fn send_error<E>(err: E) -> ErrorResponse
+where
+    E: std::fmt::Display,
+{
+    error!("Could not send email: {err}");
+    ErrorResponse::new(
+        format!("Could not send email: {err}"),
+        StatusCode::INTERNAL_SERVER_ERROR,
+    )
+}
+
+pub struct SmtpEmailTransport {
+    mailer: AsyncSmtpTransport<Tokio1Executor>,
+}
+impl SmtpEmailTransport {
+    pub fn new(env: &EnvironmentVariables) -> Self {
+        let credentials = Credentials::new(
+            env.get(ENV_EMAIL_USERNAME).to_string(),
+            env.get(ENV_EMAIL_PASSWORD).to_string(),
+        );
+
+        Self {
+            mailer: AsyncSmtpTransport::<Tokio1Executor>::relay(env.get(ENV_EMAIL_RELAY))
+                .expect("Invalid relay")
+                .credentials(credentials)
+                .build(),
+        }
+    }
+}
+impl EmailTransport for SmtpEmailTransport {
+    fn send<'a>(&'a self, email: &'a OutgoingEmail) -> EmailFuture<'a> {
+        Box::pin(async move {
+            self.mailer
+                .send(email.message.clone())
+                .await
+                .map_err(send_error)?;
+            Ok(())
+        })
+    }
+}
+
+/// Writes every email as an `.eml` file into a directory instead of sending it
+pub struct FileEmailTransport {
+    dir: PathBuf,
+}
+impl FileEmailTransport {
+    pub fn new(dir: PathBuf) -> Self {
+        fs::create_dir_all(&dir).unwrap_or_else(|err| {
+            panic!("Can't create email directory '{}': {}", dir.display(), err)
+        });
+
+        Self { dir }
+    }
+}
+impl EmailTransport for FileEmailTransport {
+    fn send<'a>(&'a self, email: &'a OutgoingEmail) -> EmailFuture<'a> {
+        Box::pin(async move {
+            let path = self.dir.join(format!("{}.eml", Uuid::new_v4()));
+            fs::write(&path, email.message.formatted()).map_err(send_error)?;
+            info!("Email to '{}' written to '{}'", email.to, path.display());
+            Ok(())
+        })
+    }
+}
+
+/// Prints the text part of every email to stdout
+pub struct StdoutEmailTransport;
+impl EmailTransport for StdoutEmailTransport {
+    fn send<'a>(&'a self, email: &'a OutgoingEmail) -> EmailFuture<'a> {
+        Box::pin(async move {
+            println!(
+                "To: {}\nSubject: {}\n\n{}\n",
+                email.to, email.content.subject, email.content.text
+            );
+            Ok(())
+        })
+    }
+}
+
+/// Keeps the sent emails in memory so tests can inspect them
+#[derive(Default)]
+pub struct InMemoryEmailTransport {
+    sent: Mutex<Vec<OutgoingEmail>>,
+}
+#[cfg(test)]
+impl InMemoryEmailTransport {
+    pub fn sent_emails(&self) -> Vec<OutgoingEmail> {
+        self.sent.lock().unwrap().clone()
+    }
+
+    /// The most recent email sent to the recipient
+    pub fn last_email_to(&self, to: &str) -> Option<OutgoingEmail> {
+        self.sent
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find(|email| email.to == to)
+            .cloned()
+    }
+}
+impl EmailTransport for InMemoryEmailTransport {
+    fn send<'a>(&'a self, email: &'a OutgoingEmail) -> EmailFuture<'a> {
+        Box::pin(async move {
+            self.sent.lock().unwrap().push(email.clone());
+            Ok(())
+        })
+    }
+}
+
+/// Selected by `EMAIL_TRANSPORT`: `smtp` (default), `file`, `stdout` or `memory`
+pub fn load_email_transport(env: &EnvironmentVariables) -> Arc<dyn EmailTransport> {
+    let transport = env
+        .get_optional::<String>(ENV_EMAIL_TRANSPORT)
+        .unwrap_or_else(|| "smtp".to_owned());
+
+    match transport.as_str() {
+        "smtp" => Arc::new(SmtpEmailTransport::new(env)),
+        "file" => Arc::new(FileEmailTransport::new(
+            env.get_optional(ENV_EMAIL_FILE_DIR)
+                .unwrap_or_else(|| PathBuf::from(DEFAULT_EMAIL_FILE_DIR)),
+        )),
+        "stdout" => Arc::new(StdoutEmailTransport),
+        "memory" => Arc::new(InMemoryEmailTransport::default()),
+        other => panic!("Unknown email transport '{other}'"),
+    }
+}
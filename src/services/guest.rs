@@ -1,5 +1,6 @@
 use actix_web::http::StatusCode;
 use log::error;
+use uuid::Uuid;
 
 use crate::{
     api::error_response::ErrorResponse,
@@ -14,6 +15,20 @@ pub mod update_guest;
 
 const INVALID_STATE: &str = "Invalid state when searching for existing ucn or id card number";
 
+/// Checks that a user owns the guest, `owner_user_id` is not set for admins
+pub fn check_guest_owner(
+    guest: &guest::Model,
+    owner_user_id: Option<Uuid>,
+) -> Result<(), ErrorResponse> {
+    match owner_user_id {
+        Some(user_id) if guest.owner_user_id != Some(user_id) => Err(ErrorResponse::new(
+            format!("Access to guest with id '{}' not allowed", guest.id),
+            StatusCode::FORBIDDEN,
+        )),
+        _ => Ok(()),
+    }
+}
+
 async fn find_existing_guest(
     app_state: &AppState,
     ucn: &Option<String>,
@@ -31,12 +46,22 @@ async fn find_existing_guest(
     Ok(guest)
 }
 
+/// Users are told which field is in use only for their own guests, so they can't look up the
+/// details of other guests. `owner_user_id` is not set for admins
 fn find_conflicting_fields(
     guest: guest::Model,
     ucn: Option<String>,
     id_card_number: Option<String>,
     phone_number: Option<String>,
+    owner_user_id: Option<Uuid>,
 ) -> ErrorResponse {
+    if check_guest_owner(&guest, owner_user_id).is_err() {
+        return ErrorResponse::new(
+            "Guest details are already in use".to_string(),
+            StatusCode::BAD_REQUEST,
+        );
+    }
+
     let found_ucn = guest.ucn;
     let found_card_number = guest.id_card_number;
     let found_phone_number = guest.phone_number;
@@ -42,6 +42,7 @@ async fn check_ucn_and_card_number_not_in_use(
             ucn,
             id_card_number,
             input.phone_number.clone(),
+            input.owner_user_id,
         ))
     } else {
         Ok(())
@@ -74,6 +75,7 @@ async fn save_guest(app_state: &AppState, input: &AddGuestInput) -> Result<Uuid,
         id_card_issue_date: ActiveValue::Set(id_card_issue_date),
         id_card_validity: ActiveValue::Set(id_card_validity),
         phone_number: ActiveValue::Set(input.phone_number.clone()),
+        owner_user_id: ActiveValue::Set(input.owner_user_id),
     };
     guest.insert(app_state.db.as_ref()).await?;
 
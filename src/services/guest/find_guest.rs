@@ -18,6 +18,7 @@ pub async fn find_guest_service(
         input.phone_number,
         input.date_of_birth,
         input.ucn,
+        input.owner_user_id,
     )
     .await?;
 
@@ -13,6 +13,7 @@ use crate::{
     },
     app_state::AppState,
     persistence::guest,
+    services::guest::check_guest_owner,
     util::require_some,
 };
 
@@ -20,8 +21,10 @@ pub async fn get_guest_service(
     app_state: &AppState,
     input: GetGuestInput,
 ) -> Result<GetGuestOutput, ErrorResponse> {
+    let owner_user_id = input.owner_user_id;
     let guest = find_guest_in_db(app_state, input).await?;
-    Ok(convert_model_to_output(guest)?)
+    check_guest_owner(&guest, owner_user_id)?;
+    convert_model_to_output(guest)
 }
 
 async fn find_guest_in_db(
@@ -58,8 +61,7 @@ fn require_id_card_data<T>(option: Option<T>, guest_id: Uuid) -> Result<T, Error
 }
 
 fn convert_model_to_output(guest: guest::Model) -> Result<GetGuestOutput, ErrorResponse> {
-    let id_card = if guest.id_card_number.is_some() {
-        let id_card_number = guest.id_card_number.unwrap();
+    let id_card = if let Some(id_card_number) = guest.id_card_number {
         let ucn = require_id_card_data(guest.ucn, guest.id)?;
         let issue_authority = require_id_card_data(guest.id_card_issue_authority, guest.id)?;
         let issue_date = require_id_card_data(guest.id_card_issue_date, guest.id)?;
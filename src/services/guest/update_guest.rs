@@ -8,7 +8,7 @@ use crate::{
     },
     app_state::AppState,
     persistence,
-    services::guest::find_existing_guest,
+    services::guest::{check_guest_owner, find_existing_guest},
     util::require_some,
 };
 
@@ -24,7 +24,7 @@ pub async fn update_guest_service(
 }
 
 async fn check_exists(app_state: &AppState, input: &UpdateGuestInput) -> Result<(), ErrorResponse> {
-    require_some(
+    let guest = require_some(
         persistence::guest::Entity::find_by_id(input.id.unwrap())
             .one(app_state.db.as_ref())
             .await?,
@@ -32,7 +32,7 @@ async fn check_exists(app_state: &AppState, input: &UpdateGuestInput) -> Result<
         StatusCode::NOT_FOUND,
     )?;
 
-    Ok(())
+    check_guest_owner(&guest, input.owner_user_id)
 }
 
 async fn check_ucn_and_card_number_not_in_use(
@@ -56,6 +56,7 @@ async fn check_ucn_and_card_number_not_in_use(
                     ucn,
                     id_card_number,
                     input.phone_number.clone(),
+                    input.owner_user_id,
                 ))
             } else {
                 Ok(())
@@ -93,6 +94,7 @@ async fn save_guest(
         id_card_issue_date: ActiveValue::Set(id_card_issue_date),
         id_card_validity: ActiveValue::Set(id_card_validity),
         phone_number: ActiveValue::Set(input.phone_number.clone()),
+        owner_user_id: ActiveValue::NotSet,
     };
     guest.save(app_state.db.as_ref()).await?;
 
@@ -0,0 +1,280 @@
+use std::collections::HashMap;
+
+use log::error;
+use sea_orm::{
+    sqlx::types::chrono::Utc, ActiveModelTrait, ConnectionTrait, DatabaseConnection,
+    DatabaseTransaction, IntoActiveModel,
+};
+use uuid::Uuid;
+
+use crate::{
+    api::error_response::ErrorResponse,
+    persistence::payment::{self, PaymentMethod, PaymentStatus, PaymentType},
+    services::payment_provider::{PaymentProvider, RefundRequest},
+};
+
+/// Pending payments and refunds are counted, so the same amount is not charged or refunded twice
+pub async fn find_paid_amount<T>(db: &T, booking_id: Uuid) -> Result<i64, ErrorResponse>
+where
+    T: ConnectionTrait,
+{
+    Ok(payment::find_payments_for_booking(db, booking_id)
+        .await?
+        .iter()
+        .filter(|payment| payment.status != PaymentStatus::Failed)
+        .map(|payment| payment.signed_amount())
+        .sum())
+}
+
+struct PendingRefund {
+    refund_id: Uuid,
+    charge_reference: String,
+    amount: i64,
+}
+
+/// Refunds recorded in a transaction. The card refunds are pending until they are sent to the
+/// payment provider with `send_refunds` after the transaction is committed.
+#[derive(Default)]
+pub struct Refunds {
+    pub amount: i64,
+    pending: Vec<PendingRefund>,
+}
+impl Refunds {
+    pub fn append(&mut self, other: Refunds) {
+        self.amount += other.amount;
+        self.pending.extend(other.pending);
+    }
+}
+
+/// Records refunds of up to `amount` starting from the most recent completed payments.
+pub async fn refund_payments(
+    transaction: &DatabaseTransaction,
+    booking_id: Uuid,
+    admin_id: Option<Uuid>,
+    amount: i64,
+) -> Result<Refunds, ErrorResponse> {
+    let payments = payment::find_payments_for_booking(transaction, booking_id).await?;
+    let mut refunded_by_payment: HashMap<Uuid, i64> = HashMap::new();
+    for refund in payments.iter().filter(|payment| {
+        payment.payment_type == PaymentType::Refund && payment.status != PaymentStatus::Failed
+    }) {
+        if let Some(payment_id) = refund.refunded_payment_id {
+            *refunded_by_payment.entry(payment_id).or_default() += refund.amount;
+        }
+    }
+
+    let mut refunds = Refunds::default();
+    for payment in payments.iter().rev().filter(|payment| {
+        payment.payment_type == PaymentType::Payment && payment.status == PaymentStatus::Completed
+    }) {
+        let remaining = amount - refunds.amount;
+        if remaining <= 0 {
+            break;
+        }
+
+        let refundable =
+            payment.amount - refunded_by_payment.get(&payment.id).copied().unwrap_or(0);
+        let refund_amount = refundable.min(remaining);
+        if refund_amount <= 0 {
+            continue;
+        }
+
+        let charge_reference = match (&payment.method, &payment.provider_reference) {
+            (PaymentMethod::Card, Some(reference)) => Some(reference.clone()),
+            _ => None,
+        };
+        let refund = payment::Model {
+            id: Uuid::new_v4(),
+            booking_id,
+            admin_id,
+            payment_type: PaymentType::Refund,
+            amount: refund_amount,
+            currency: payment.currency.clone(),
+            method: payment.method.clone(),
+            provider_reference: None,
+            refunded_payment_id: Some(payment.id),
+            payment_time: Utc::now().naive_utc(),
+            status: if charge_reference.is_some() {
+                PaymentStatus::Pending
+            } else {
+                PaymentStatus::Completed
+            },
+        }
+        .into_active_model()
+        .insert(transaction)
+        .await?;
+
+        if let Some(charge_reference) = charge_reference {
+            refunds.pending.push(PendingRefund {
+                refund_id: refund.id,
+                charge_reference,
+                amount: refund_amount,
+            });
+        }
+        refunds.amount += refund_amount;
+    }
+
+    Ok(refunds)
+}
+
+pub async fn refund_all_payments(
+    transaction: &DatabaseTransaction,
+    booking_id: Uuid,
+    admin_id: Option<Uuid>,
+) -> Result<Refunds, ErrorResponse> {
+    let paid_amount = find_paid_amount(transaction, booking_id).await?;
+    refund_payments(transaction, booking_id, admin_id, paid_amount).await
+}
+
+/// Sends the pending card refunds to the payment provider and records the outcome of each one
+/// separately. Returns the amount that was actually refunded, failed refunds are kept as failed
+/// payments.
+pub async fn send_refunds(
+    db: &DatabaseConnection,
+    payment_provider: &dyn PaymentProvider,
+    refunds: Refunds,
+) -> i64 {
+    let mut refunded_amount = refunds.amount;
+    for refund in refunds.pending {
+        let request = RefundRequest {
+            idempotency_key: refund.refund_id,
+            provider_reference: &refund.charge_reference,
+            amount: refund.amount,
+        };
+        let (status, provider_reference) = match payment_provider.refund(request).await {
+            Ok(reference) => (PaymentStatus::Completed, Some(reference)),
+            Err(err) => {
+                error!("Refund '{}' failed: {}", refund.refund_id, err.error);
+                refunded_amount -= refund.amount;
+                (PaymentStatus::Failed, None)
+            }
+        };
+
+        let result =
+            payment::update_payment_status(db, refund.refund_id, status, provider_reference).await;
+        if let Err(err) = result {
+            error!(
+                "Error saving the outcome of refund '{}': {}",
+                refund.refund_id, err
+            );
+        }
+    }
+
+    refunded_amount
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::TransactionTrait;
+
+    use crate::{
+        persistence::{
+            booking::BookingStatus,
+            connect_test_db,
+            test_data::{insert_payment, insert_room_booking, new_booking},
+        },
+        services::payment_provider::{FailingPaymentProvider, MockPaymentProvider},
+        util::add_days,
+    };
+
+    use super::*;
+
+    #[actix_web::test]
+    async fn test_refunds_latest_payments_first() {
+        let db = connect_test_db().await;
+        let start_date = add_days(Utc::now().date_naive(), 30).unwrap();
+        let booking = insert_room_booking(
+            &db,
+            new_booking(
+                BookingStatus::Paid,
+                start_date,
+                add_days(start_date, 1).unwrap(),
+            ),
+        )
+        .await;
+        insert_payment(&db, booking.id, PaymentMethod::Cash, 100).await;
+        insert_payment(&db, booking.id, PaymentMethod::Card, 200).await;
+
+        let transaction = db.begin().await.unwrap();
+        let refunds = refund_payments(&transaction, booking.id, None, 250)
+            .await
+            .unwrap();
+        assert_eq!(refunds.amount, 250);
+        assert_eq!(
+            find_paid_amount(&transaction, booking.id).await.unwrap(),
+            50
+        );
+        transaction.commit().await.unwrap();
+        assert_eq!(send_refunds(&db, &MockPaymentProvider, refunds).await, 250);
+
+        let transaction = db.begin().await.unwrap();
+        let refunds = refund_all_payments(&transaction, booking.id, None)
+            .await
+            .unwrap();
+        assert_eq!(refunds.amount, 50);
+        transaction.commit().await.unwrap();
+        assert_eq!(send_refunds(&db, &MockPaymentProvider, refunds).await, 50);
+        assert_eq!(find_paid_amount(&db, booking.id).await.unwrap(), 0);
+
+        let refunds = find_refunds(&db, booking.id).await;
+        assert_eq!(refunds.len(), 3);
+        assert!(refunds
+            .iter()
+            .all(|refund| refund.status == PaymentStatus::Completed));
+        assert!(refunds
+            .iter()
+            .any(|refund| refund.method == PaymentMethod::Card
+                && refund.amount == 200
+                && refund.provider_reference.is_some()));
+    }
+
+    #[actix_web::test]
+    async fn test_failed_refunds_are_kept_apart() {
+        let db = connect_test_db().await;
+        let start_date = add_days(Utc::now().date_naive(), 30).unwrap();
+        let booking = insert_room_booking(
+            &db,
+            new_booking(
+                BookingStatus::Paid,
+                start_date,
+                add_days(start_date, 1).unwrap(),
+            ),
+        )
+        .await;
+        insert_payment(&db, booking.id, PaymentMethod::Cash, 100).await;
+        insert_payment(&db, booking.id, PaymentMethod::Card, 200).await;
+
+        let transaction = db.begin().await.unwrap();
+        let refunds = refund_all_payments(&transaction, booking.id, None)
+            .await
+            .unwrap();
+        assert_eq!(refunds.amount, 300);
+        transaction.commit().await.unwrap();
+        assert_eq!(
+            send_refunds(&db, &FailingPaymentProvider, refunds).await,
+            100
+        );
+
+        let refunds = find_refunds(&db, booking.id).await;
+        assert_eq!(refunds.len(), 2);
+        assert!(refunds
+            .iter()
+            .any(|refund| refund.method == PaymentMethod::Cash
+                && refund.status == PaymentStatus::Completed));
+        assert!(refunds
+            .iter()
+            .any(|refund| refund.method == PaymentMethod::Card
+                && refund.status == PaymentStatus::Failed
+                && refund.provider_reference.is_none()));
+        assert_eq!(find_paid_amount(&db, booking.id).await.unwrap(), 200);
+    }
+
+    async fn find_refunds(db: &DatabaseConnection, booking_id: Uuid) -> Vec<payment::Model> {
+        payment::find_payments_for_booking(db, booking_id)
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|payment| payment.payment_type == PaymentType::Refund)
+            .collect()
+    }
+}
@@ -0,0 +1,108 @@
+use std::{future::Future, pin::Pin};
+
+use actix_web::http::StatusCode;
+use log::info;
+use uuid::Uuid;
+
+use crate::{
+    api::error_response::ErrorResponse, app_state::EnvironmentVariables,
+    constants::ENV_PAYMENT_PROVIDER,
+};
+
+pub type PaymentFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<String, ErrorResponse>> + Send + 'a>>;
+
+pub const MOCK_DECLINED_CARD_TOKEN: &str = "tok_declined";
+
+/// The idempotency key is the id of the pending payment, so a retried request is not charged twice
+pub struct ChargeRequest<'a> {
+    pub idempotency_key: Uuid,
+    pub booking_id: Uuid,
+    pub amount: i64,
+    pub currency: &'a str,
+    pub card_token: &'a str,
+}
+
+/// The idempotency key is the id of the pending refund
+pub struct RefundRequest<'a> {
+    pub idempotency_key: Uuid,
+    /// Reference of the refunded charge
+    pub provider_reference: &'a str,
+    pub amount: i64,
+}
+
+/// External processor for card payments. Returns the provider's reference for the transaction.
+/// Never called inside a database transaction, the money moves even if the transaction fails.
+pub trait PaymentProvider: Send + Sync {
+    fn charge<'a>(&'a self, request: ChargeRequest<'a>) -> PaymentFuture<'a>;
+
+    fn refund<'a>(&'a self, request: RefundRequest<'a>) -> PaymentFuture<'a>;
+}
+
+/// Accepts every card except `tok_declined` without contacting any external service. The
+/// references are derived from the idempotency keys, so retries return the same reference.
+pub struct MockPaymentProvider;
+impl PaymentProvider for MockPaymentProvider {
+    fn charge<'a>(&'a self, request: ChargeRequest<'a>) -> PaymentFuture<'a> {
+        Box::pin(async move {
+            if request.card_token == MOCK_DECLINED_CARD_TOKEN {
+                return Err(ErrorResponse::new(
+                    "Card payment declined".to_owned(),
+                    StatusCode::PAYMENT_REQUIRED,
+                ));
+            }
+
+            let reference = format!("mock-charge-{}", request.idempotency_key);
+            info!(
+                "Mock charge '{}' of {} {} for booking '{}'",
+                reference, request.amount, request.currency, request.booking_id
+            );
+            Ok(reference)
+        })
+    }
+
+    fn refund<'a>(&'a self, request: RefundRequest<'a>) -> PaymentFuture<'a> {
+        Box::pin(async move {
+            let reference = format!("mock-refund-{}", request.idempotency_key);
+            info!(
+                "Mock refund '{}' of {} for charge '{}'",
+                reference, request.amount, request.provider_reference
+            );
+            Ok(reference)
+        })
+    }
+}
+
+/// Declines every charge and refund, as if the provider was unreachable
+#[cfg(test)]
+pub struct FailingPaymentProvider;
+#[cfg(test)]
+impl PaymentProvider for FailingPaymentProvider {
+    fn charge<'a>(&'a self, _request: ChargeRequest<'a>) -> PaymentFuture<'a> {
+        Box::pin(async { Err(Self::error()) })
+    }
+
+    fn refund<'a>(&'a self, _request: RefundRequest<'a>) -> PaymentFuture<'a> {
+        Box::pin(async { Err(Self::error()) })
+    }
+}
+#[cfg(test)]
+impl FailingPaymentProvider {
+    fn error() -> ErrorResponse {
+        ErrorResponse::new(
+            "Payment provider unavailable".to_owned(),
+            StatusCode::BAD_GATEWAY,
+        )
+    }
+}
+
+pub fn load_payment_provider(env: &EnvironmentVariables) -> Box<dyn PaymentProvider> {
+    let provider = env
+        .get_optional::<String>(ENV_PAYMENT_PROVIDER)
+        .unwrap_or_else(|| "mock".to_owned());
+
+    match provider.as_str() {
+        "mock" => Box::new(MockPaymentProvider),
+        other => panic!("Unknown payment provider '{other}'"),
+    }
+}
@@ -0,0 +1,170 @@
+use std::cmp::Reverse;
+
+use sea_orm::{prelude::Date, ConnectionTrait};
+
+use crate::{
+    api::{
+        error_response::ErrorResponse,
+        room::{DayOfWeek, NightPrice},
+    },
+    persistence::{room, room_price_rule},
+};
+
+/// Number of nights between arrival and departure, the departure day is not charged
+pub fn count_nights(start_date: Date, end_date: Date) -> i64 {
+    (end_date - start_date).num_days()
+}
+
+pub fn weekdays_to_mask<'a>(weekdays: impl IntoIterator<Item = &'a DayOfWeek>) -> i16 {
+    weekdays
+        .into_iter()
+        .fold(0, |mask, day| mask | weekday_bit(*day))
+}
+
+pub fn mask_to_weekdays(mask: i16) -> Vec<DayOfWeek> {
+    DayOfWeek::ALL
+        .into_iter()
+        .filter(|day| mask & weekday_bit(*day) != 0)
+        .collect()
+}
+
+fn weekday_bit(day: DayOfWeek) -> i16 {
+    1 << day as i16
+}
+
+fn rule_applies(rule: &room_price_rule::Model, date: Date) -> bool {
+    let in_range = rule.start_date.is_none_or(|start_date| start_date <= date)
+        && rule.end_date.is_none_or(|end_date| date <= end_date);
+    let on_weekday = rule
+        .weekdays
+        .is_none_or(|mask| mask & weekday_bit(DayOfWeek::from_date(date)) != 0);
+
+    in_range && on_weekday
+}
+
+/// Picks the most specific matching rule: dates over seasons over weekdays, weekday restricted
+/// and shorter seasons first, the newest rule on ties.
+fn price_for_night(base_price: i64, rules: &[room_price_rule::Model], date: Date) -> i64 {
+    rules
+        .iter()
+        .filter(|rule| rule_applies(rule, date))
+        .max_by_key(|rule| {
+            let range_days = match (rule.start_date, rule.end_date) {
+                (Some(start_date), Some(end_date)) => (end_date - start_date).num_days(),
+                _ => i64::MAX,
+            };
+            (
+                rule.rule_type.clone(),
+                rule.weekdays.is_some(),
+                Reverse(range_days),
+                rule.created_time,
+            )
+        })
+        .map_or(base_price, |rule| rule.price)
+}
+
+pub async fn calculate_night_prices<T>(
+    db: &T,
+    room: &room::Model,
+    start_date: Date,
+    end_date: Date,
+) -> Result<Vec<NightPrice>, ErrorResponse>
+where
+    T: ConnectionTrait,
+{
+    let nights = count_nights(start_date, end_date).max(0) as usize;
+    let dates: Vec<_> = start_date.iter_days().take(nights).collect();
+    let last_night = dates.last().copied().unwrap_or(start_date);
+    let rules =
+        room_price_rule::find_rules_for_room_in_range(db, room.id, start_date, last_night).await?;
+
+    Ok(dates
+        .into_iter()
+        .map(|date| NightPrice {
+            date,
+            price: price_for_night(room.price, &rules, date),
+        })
+        .collect())
+}
+
+pub fn sum_night_prices(nights: &[NightPrice]) -> i64 {
+    nights.iter().map(|night| night.price).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::sqlx::types::chrono::{NaiveDate, Utc};
+    use uuid::Uuid;
+
+    use crate::persistence::room_price_rule::PriceRuleType;
+
+    use super::*;
+
+    fn rule(
+        rule_type: PriceRuleType,
+        dates: Option<(Date, Date)>,
+        weekdays: Option<&[DayOfWeek]>,
+        price: i64,
+    ) -> room_price_rule::Model {
+        room_price_rule::Model {
+            id: Uuid::new_v4(),
+            room_id: Uuid::new_v4(),
+            rule_type,
+            start_date: dates.map(|dates| dates.0),
+            end_date: dates.map(|dates| dates.1),
+            weekdays: weekdays.map(weekdays_to_mask),
+            price,
+            created_time: Utc::now().naive_utc(),
+        }
+    }
+
+    #[test]
+    fn test_price_for_night_uses_most_specific_rule() {
+        let date = |day| NaiveDate::from_ymd_opt(2025, 7, day).unwrap();
+        let weekend = [DayOfWeek::Saturday, DayOfWeek::Sunday];
+        let rules = vec![
+            rule(PriceRuleType::Weekday, None, Some(&weekend), 150),
+            rule(PriceRuleType::Season, Some((date(1), date(31))), None, 200),
+            rule(
+                PriceRuleType::Season,
+                Some((date(1), date(31))),
+                Some(&weekend),
+                250,
+            ),
+            rule(PriceRuleType::Season, Some((date(10), date(12))), None, 300),
+            rule(PriceRuleType::Date, Some((date(11), date(11))), None, 400),
+        ];
+
+        // 2025-07-04 is a Friday
+        assert_eq!(price_for_night(100, &rules, date(4)), 200);
+        assert_eq!(price_for_night(100, &rules, date(5)), 250);
+        assert_eq!(price_for_night(100, &rules, date(10)), 300);
+        assert_eq!(price_for_night(100, &rules, date(11)), 400);
+        assert_eq!(
+            price_for_night(100, &rules, NaiveDate::from_ymd_opt(2025, 8, 2).unwrap()),
+            150
+        );
+        assert_eq!(
+            price_for_night(100, &rules, NaiveDate::from_ymd_opt(2025, 8, 4).unwrap()),
+            100
+        );
+    }
+
+    #[test]
+    fn test_day_of_week_from_date() {
+        let date = |day| NaiveDate::from_ymd_opt(2025, 7, day).unwrap();
+        assert_eq!(DayOfWeek::from_date(date(4)), DayOfWeek::Friday);
+        assert_eq!(DayOfWeek::from_date(date(6)), DayOfWeek::Sunday);
+        assert_eq!(DayOfWeek::from_date(date(7)), DayOfWeek::Monday);
+        assert_eq!(
+            DayOfWeek::from_date(NaiveDate::from_ymd_opt(1969, 12, 31).unwrap()),
+            DayOfWeek::Wednesday
+        );
+    }
+
+    #[test]
+    fn test_weekday_mask_round_trip() {
+        let weekdays = [DayOfWeek::Monday, DayOfWeek::Friday, DayOfWeek::Sunday];
+        assert_eq!(mask_to_weekdays(weekdays_to_mask(&weekdays)), weekdays);
+    }
+}
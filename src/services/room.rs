@@ -1,3 +1,70 @@
+use std::collections::HashMap;
+
+use actix_web::http::StatusCode;
+use sea_orm::{ActiveModelTrait, ActiveValue, DatabaseTransaction, DbErr, SqlErr};
+use uuid::Uuid;
+
+use crate::{
+    api::{error_response::ErrorResponse, room::Bed},
+    app_state::AppState,
+    persistence::{bed, handle_db_error, room::RoomWithCapacity},
+};
+
+pub mod add_price_rule;
 pub mod add_room;
+pub mod delete_price_rule;
 pub mod delete_room;
+pub mod get_price_rules;
 pub mod get_room;
+pub mod get_room_quote;
+pub mod get_rooms;
+pub mod restore_room;
+pub mod update_room;
+
+/// Saving a room fails on the unique room number index when a concurrent request took the number
+/// after it was checked
+fn handle_room_number_error(error: DbErr, room_number: &str) -> ErrorResponse {
+    match error.sql_err() {
+        Some(SqlErr::UniqueConstraintViolation(_)) => ErrorResponse::new(
+            format!("Room number '{}' is already in use", room_number),
+            StatusCode::CONFLICT,
+        ),
+        _ => handle_db_error(error),
+    }
+}
+
+async fn insert_bed(
+    transaction: &DatabaseTransaction,
+    input: &Bed,
+    room_id: &Uuid,
+) -> Result<(), ErrorResponse> {
+    let total_capacity = input.bed_size.get_size() * input.count;
+    let bed_to_save = bed::ActiveModel {
+        id: ActiveValue::Set(Uuid::new_v4()),
+        room_id: ActiveValue::Set(*room_id),
+        bed_size: ActiveValue::Set(input.bed_size.clone()),
+        count: ActiveValue::Set(input.count),
+        total_capacity: ActiveValue::Set(total_capacity),
+    };
+    bed_to_save.insert(transaction).await?;
+
+    Ok(())
+}
+
+pub async fn find_beds_by_room(
+    app_state: &AppState,
+    rooms: &[RoomWithCapacity],
+) -> Result<HashMap<Uuid, Vec<Bed>>, ErrorResponse> {
+    let room_ids: Vec<_> = rooms.iter().map(|room| room.id).collect();
+    let beds = bed::find_beds_for_rooms(app_state.db.as_ref(), &room_ids).await?;
+
+    let mut beds_by_room: HashMap<Uuid, Vec<Bed>> = HashMap::with_capacity(rooms.len());
+    for bed in beds {
+        beds_by_room.entry(bed.room_id).or_default().push(Bed {
+            bed_size: bed.bed_size,
+            count: bed.count,
+        });
+    }
+
+    Ok(beds_by_room)
+}
@@ -0,0 +1,55 @@
+use actix_web::http::StatusCode;
+use sea_orm::{sqlx::types::chrono::Utc, ActiveModelTrait, IntoActiveModel};
+use uuid::Uuid;
+
+use crate::{
+    api::{
+        error_response::ErrorResponse,
+        room::add_price_rule::{AddPriceRuleInput, AddPriceRuleOutput},
+    },
+    app_state::AppState,
+    persistence::{room, room_price_rule},
+    services::pricing::weekdays_to_mask,
+    util::require_some,
+};
+
+pub async fn add_price_rule_service(
+    app_state: &AppState,
+    input: AddPriceRuleInput,
+) -> Result<AddPriceRuleOutput, ErrorResponse> {
+    let room_id = input.room_id.unwrap();
+    check_room_exists(app_state, room_id).await?;
+
+    let end_date = match input.rule_type {
+        room_price_rule::PriceRuleType::Date => input.start_date,
+        _ => input.end_date,
+    };
+    let rule = room_price_rule::Model {
+        id: Uuid::new_v4(),
+        room_id,
+        rule_type: input.rule_type,
+        start_date: input.start_date,
+        end_date,
+        weekdays: input.weekdays.as_ref().map(weekdays_to_mask),
+        price: input.price,
+        created_time: Utc::now().naive_utc(),
+    }
+    .into_active_model()
+    .insert(app_state.db.as_ref())
+    .await?;
+
+    Ok(AddPriceRuleOutput {
+        price_rule_id: rule.id,
+    })
+}
+
+async fn check_room_exists(app_state: &AppState, room_id: Uuid) -> Result<(), ErrorResponse> {
+    let room_option = room::find_room_by_id(app_state.db.as_ref(), room_id).await?;
+    let message = || format!("Room with id '{}' not found", room_id);
+    let (room, _beds) = require_some(room_option, message, StatusCode::NOT_FOUND)?;
+    if room.is_deleted {
+        return Err(ErrorResponse::new(message(), StatusCode::NOT_FOUND));
+    }
+
+    Ok(())
+}
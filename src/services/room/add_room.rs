@@ -7,23 +7,26 @@ use uuid::Uuid;
 use crate::{
     api::{
         error_response::ErrorResponse,
-        room::{
-            add_room::{AddRoomInput, AddRoomOutput},
-            Bed,
-        },
+        room::add_room::{AddRoomInput, AddRoomOutput},
     },
     app_state::AppState,
     persistence::{
-        bed, handle_db_error,
+        handle_db_error,
         room::{self},
     },
+    services::cancellation_policy::find_cancellation_policy,
 };
 
+use super::{handle_room_number_error, insert_bed};
+
 pub async fn add_room_service(
     app_state: &AppState,
     input: AddRoomInput,
 ) -> Result<AddRoomOutput, ErrorResponse> {
     check_room_number_not_used(&app_state.db, &input).await?;
+    if let Some(policy_id) = input.cancellation_policy_id {
+        find_cancellation_policy(app_state.db.as_ref(), policy_id).await?;
+    }
 
     let transaction = app_state.db.begin().await?;
 
@@ -69,28 +72,11 @@ async fn insert_room(
         room_number: ActiveValue::Set(input.room_number.clone()),
         bathroom_type: ActiveValue::Set(input.bathroom_type.clone()),
         is_deleted: ActiveValue::Set(false),
+        cancellation_policy_id: ActiveValue::Set(input.cancellation_policy_id),
     };
     if let Err(err) = room_to_save.insert(transaction).await {
-        return Err(handle_db_error(err));
+        return Err(handle_room_number_error(err, &input.room_number));
     }
 
     Ok(id)
 }
-
-async fn insert_bed(
-    transaction: &DatabaseTransaction,
-    input: &Bed,
-    room_id: &Uuid,
-) -> Result<(), ErrorResponse> {
-    let total_capacity = input.bed_size.get_size() * input.count;
-    let bed_to_save = bed::ActiveModel {
-        id: ActiveValue::Set(Uuid::new_v4()),
-        room_id: ActiveValue::Set(*room_id),
-        bed_size: ActiveValue::Set(input.bed_size.clone()),
-        count: ActiveValue::Set(input.count),
-        total_capacity: ActiveValue::Set(total_capacity),
-    };
-    bed_to_save.insert(transaction).await?;
-
-    Ok(())
-}
@@ -0,0 +1,29 @@
+use actix_web::http::StatusCode;
+use sea_orm::EntityTrait;
+
+use crate::{
+    api::{
+        error_response::ErrorResponse,
+        room::delete_price_rule::{DeletePriceRuleInput, DeletePriceRuleOutput},
+    },
+    app_state::AppState,
+    persistence::room_price_rule,
+};
+
+pub async fn delete_price_rule_service(
+    app_state: &AppState,
+    input: DeletePriceRuleInput,
+) -> Result<DeletePriceRuleOutput, ErrorResponse> {
+    let result = room_price_rule::Entity::delete_by_id(input.price_rule_id)
+        .exec(app_state.db.as_ref())
+        .await?;
+
+    if result.rows_affected == 0 {
+        return Err(ErrorResponse::new(
+            format!("Price rule with id '{}' not found", input.price_rule_id),
+            StatusCode::NOT_FOUND,
+        ));
+    }
+
+    Ok(DeletePriceRuleOutput)
+}
@@ -1,5 +1,9 @@
 use actix_web::http::StatusCode;
-use sea_orm::{ActiveModelTrait, ActiveValue, EntityTrait, IntoActiveModel};
+use sea_orm::{
+    sqlx::types::chrono::Utc, ActiveModelTrait, ActiveValue, DatabaseConnection,
+    DatabaseTransaction, IntoActiveModel, TransactionTrait,
+};
+use uuid::Uuid;
 
 use crate::{
     api::{
@@ -7,7 +11,15 @@ use crate::{
         room::delete_room::{DeleteRoomInput, DeleteRoomOutput},
     },
     app_state::AppState,
-    persistence::room,
+    persistence::{
+        booking::{self, BookingStatus},
+        room,
+    },
+    services::{
+        booking_email::{queue_booking_email, BookingEmail},
+        payment::{refund_all_payments, send_refunds, Refunds},
+        payment_provider::PaymentProvider,
+    },
     util::require_some,
 };
 
@@ -15,28 +27,59 @@ pub async fn delete_room_service(
     app_state: &AppState,
     input: DeleteRoomInput,
 ) -> Result<DeleteRoomOutput, ErrorResponse> {
-    let room = find_room(app_state, &input).await?;
+    let canceled_bookings = delete_room(
+        app_state.db.as_ref(),
+        app_state.payment_provider.as_ref(),
+        &input,
+    )
+    .await?;
 
-    Ok(set_delete_flag_for_room(app_state, room).await?)
+    Ok(DeleteRoomOutput {
+        canceled_bookings: canceled_bookings.iter().map(|booking| booking.id).collect(),
+    })
 }
 
-async fn find_room(
-    app_state: &AppState,
+async fn delete_room(
+    db: &DatabaseConnection,
+    payment_provider: &dyn PaymentProvider,
     input: &DeleteRoomInput,
+) -> Result<Vec<booking::Model>, ErrorResponse> {
+    let transaction = db.begin().await?;
+    let room = lock_room(&transaction, input.room_id).await?;
+    let bookings = find_active_bookings(&transaction, input).await?;
+    let mut refunds = Refunds::default();
+    if !bookings.is_empty() {
+        for booking in &bookings {
+            refunds.append(refund_all_payments(&transaction, booking.id, input.deleted_by).await?);
+        }
+        let booking_ids = bookings.iter().map(|booking| booking.id).collect();
+        booking::cancel_bookings(&transaction, booking_ids).await?;
+        for booking in &bookings {
+            queue_booking_email(&transaction, booking.id, BookingEmail::RoomUnavailable).await?;
+        }
+    }
+    set_delete_flag_for_room(&transaction, room).await?;
+    transaction.commit().await?;
+    send_refunds(db, payment_provider, refunds).await;
+
+    Ok(bookings)
+}
+
+async fn lock_room(
+    transaction: &DatabaseTransaction,
+    room_id: Uuid,
 ) -> Result<room::Model, ErrorResponse> {
-    let room_option = room::Entity::find_by_id(input.room_id)
-        .one(app_state.db.as_ref())
-        .await?;
+    let room_option = room::lock_room_by_id(transaction, room_id).await?;
 
     let room = require_some(
         room_option,
-        || format!("Room with id '{}' not found", input.room_id),
+        || format!("Room with id '{}' not found", room_id),
         StatusCode::NOT_FOUND,
     )?;
 
     if room.is_deleted {
         return Err(ErrorResponse::new(
-            format!("Room with id '{}' already deleted", input.room_id),
+            format!("Room with id '{}' already deleted", room_id),
             StatusCode::NOT_FOUND,
         ));
     }
@@ -44,16 +87,242 @@ async fn find_room(
     Ok(room)
 }
 
+async fn find_active_bookings(
+    transaction: &DatabaseTransaction,
+    input: &DeleteRoomInput,
+) -> Result<Vec<booking::Model>, ErrorResponse> {
+    let bookings = booking::find_active_bookings_for_room_from(
+        transaction,
+        input.room_id,
+        Utc::now().date_naive(),
+    )
+    .await?;
+
+    check_no_guests_checked_in(&bookings)?;
+    if bookings.is_empty() || input.force {
+        return Ok(bookings);
+    }
+
+    Err(ErrorResponse::new(
+        format!(
+            "Room has active bookings: {}. Use force to cancel them",
+            join_booking_ids(bookings.iter())
+        ),
+        StatusCode::CONFLICT,
+    ))
+}
+
+/// Guests staying in the room can't be canceled, not even with force
+fn check_no_guests_checked_in(bookings: &[booking::Model]) -> Result<(), ErrorResponse> {
+    let mut checked_in = bookings
+        .iter()
+        .filter(|booking| booking.status == BookingStatus::CheckedIn)
+        .peekable();
+
+    if checked_in.peek().is_none() {
+        return Ok(());
+    }
+
+    Err(ErrorResponse::new(
+        format!(
+            "Room has checked in bookings: {}. They need to check out first",
+            join_booking_ids(checked_in)
+        ),
+        StatusCode::CONFLICT,
+    ))
+}
+
+fn join_booking_ids<'a>(bookings: impl Iterator<Item = &'a booking::Model>) -> String {
+    bookings
+        .map(|booking| booking.id.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 async fn set_delete_flag_for_room(
-    app_state: &AppState,
+    transaction: &DatabaseTransaction,
     room: room::Model,
-) -> Result<DeleteRoomOutput, ErrorResponse> {
-    let active_model_room = room::ActiveModel {
+) -> Result<(), ErrorResponse> {
+    room::ActiveModel {
         is_deleted: ActiveValue::Set(true),
         ..room.into_active_model()
+    }
+    .update(transaction)
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::EntityTrait;
+
+    use crate::{
+        persistence::{
+            connect_test_db,
+            payment::{self, PaymentMethod, PaymentStatus, PaymentType},
+            test_data::{insert_payment, insert_room_booking, new_booking},
+        },
+        services::payment_provider::{FailingPaymentProvider, MockPaymentProvider},
+        util::{add_days, sub_days},
     };
 
-    active_model_room.update(app_state.db.as_ref()).await?;
+    use super::*;
+
+    #[actix_web::test]
+    async fn test_delete_room_with_active_booking_requires_force() {
+        let db = connect_test_db().await;
+        let start_date = add_days(Utc::now().date_naive(), 30).unwrap();
+        let booking = insert_room_booking(
+            &db,
+            new_booking(
+                BookingStatus::Unpaid,
+                start_date,
+                add_days(start_date, 2).unwrap(),
+            ),
+        )
+        .await;
+        let (room_id, booking_id) = (booking.room_id, booking.id);
+
+        let mut input = DeleteRoomInput {
+            room_id,
+            deleted_by: None,
+            force: false,
+        };
+        let err = delete_room(&db, &MockPaymentProvider, &input)
+            .await
+            .unwrap_err();
+        assert_eq!(err.status, StatusCode::CONFLICT);
+        assert!(err.error.contains(&booking_id.to_string()));
+        let room = room::Entity::find_by_id(room_id).one(&db).await.unwrap();
+        assert!(!room.unwrap().is_deleted);
+
+        input.force = true;
+        let canceled = delete_room(&db, &MockPaymentProvider, &input)
+            .await
+            .unwrap();
+        assert_eq!(canceled.len(), 1);
+        assert_eq!(canceled[0].id, booking_id);
+        let room = room::Entity::find_by_id(room_id).one(&db).await.unwrap();
+        assert!(room.unwrap().is_deleted);
+        let booking = booking::Entity::find_by_id(booking_id)
+            .one(&db)
+            .await
+            .unwrap();
+        assert_eq!(booking.unwrap().status, BookingStatus::Canceled);
+    }
+
+    #[actix_web::test]
+    async fn test_delete_room_never_cancels_checked_in_booking() {
+        let db = connect_test_db().await;
+        let start_date = Utc::now().date_naive();
+        let booking = insert_room_booking(
+            &db,
+            new_booking(
+                BookingStatus::CheckedIn,
+                start_date,
+                add_days(start_date, 2).unwrap(),
+            ),
+        )
+        .await;
 
-    Ok(DeleteRoomOutput)
+        let input = DeleteRoomInput {
+            room_id: booking.room_id,
+            deleted_by: None,
+            force: true,
+        };
+        let err = delete_room(&db, &MockPaymentProvider, &input)
+            .await
+            .unwrap_err();
+        assert_eq!(err.status, StatusCode::CONFLICT);
+        assert!(err.error.contains(&booking.id.to_string()));
+
+        let room = room::Entity::find_by_id(booking.room_id)
+            .one(&db)
+            .await
+            .unwrap();
+        assert!(!room.unwrap().is_deleted);
+        let booking = booking::Entity::find_by_id(booking.id)
+            .one(&db)
+            .await
+            .unwrap();
+        assert_eq!(booking.unwrap().status, BookingStatus::CheckedIn);
+    }
+
+    #[actix_web::test]
+    async fn test_delete_room_ignores_booking_ending_today() {
+        let db = connect_test_db().await;
+        let today = Utc::now().date_naive();
+        let booking = insert_room_booking(
+            &db,
+            new_booking(BookingStatus::Unpaid, sub_days(today, 2).unwrap(), today),
+        )
+        .await;
+
+        let input = DeleteRoomInput {
+            room_id: booking.room_id,
+            deleted_by: None,
+            force: false,
+        };
+        let canceled = delete_room(&db, &MockPaymentProvider, &input)
+            .await
+            .unwrap();
+        assert!(canceled.is_empty());
+
+        let room = room::Entity::find_by_id(booking.room_id)
+            .one(&db)
+            .await
+            .unwrap();
+        assert!(room.unwrap().is_deleted);
+        let booking = booking::Entity::find_by_id(booking.id)
+            .one(&db)
+            .await
+            .unwrap();
+        assert_eq!(booking.unwrap().status, BookingStatus::Unpaid);
+    }
+
+    #[actix_web::test]
+    async fn test_delete_room_when_refunds_fail() {
+        let db = connect_test_db().await;
+        let start_date = add_days(Utc::now().date_naive(), 30).unwrap();
+        let booking = insert_room_booking(
+            &db,
+            new_booking(
+                BookingStatus::Paid,
+                start_date,
+                add_days(start_date, 1).unwrap(),
+            ),
+        )
+        .await;
+        insert_payment(&db, booking.id, PaymentMethod::Card, 100).await;
+
+        let input = DeleteRoomInput {
+            room_id: booking.room_id,
+            deleted_by: None,
+            force: true,
+        };
+        let canceled = delete_room(&db, &FailingPaymentProvider, &input)
+            .await
+            .unwrap();
+        assert_eq!(canceled.len(), 1);
+
+        let room = room::Entity::find_by_id(booking.room_id)
+            .one(&db)
+            .await
+            .unwrap();
+        assert!(room.unwrap().is_deleted);
+        let booking = booking::Entity::find_by_id(booking.id)
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(booking.status, BookingStatus::Canceled);
+        let refund = payment::find_payments_for_booking(&db, booking.id)
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|payment| payment.payment_type == PaymentType::Refund)
+            .unwrap();
+        assert_eq!(refund.status, PaymentStatus::Failed);
+    }
 }
@@ -0,0 +1,29 @@
+use crate::{
+    api::{
+        error_response::ErrorResponse,
+        room::get_price_rules::{GetPriceRulesInput, GetPriceRulesOutput, PriceRule},
+    },
+    app_state::AppState,
+    persistence::room_price_rule,
+    services::pricing::mask_to_weekdays,
+};
+
+pub async fn get_price_rules_service(
+    app_state: &AppState,
+    input: GetPriceRulesInput,
+) -> Result<GetPriceRulesOutput, ErrorResponse> {
+    let rules = room_price_rule::find_rules_for_room(app_state.db.as_ref(), input.room_id)
+        .await?
+        .into_iter()
+        .map(|rule| PriceRule {
+            id: rule.id,
+            rule_type: rule.rule_type,
+            start_date: rule.start_date,
+            end_date: rule.end_date,
+            weekdays: rule.weekdays.map(mask_to_weekdays),
+            price: rule.price,
+        })
+        .collect();
+
+    Ok(GetPriceRulesOutput { rules })
+}
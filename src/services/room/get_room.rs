@@ -37,16 +37,13 @@ async fn find_room(
         StatusCode::NOT_FOUND,
     );
 
-    match &room_beds {
-        Ok((room, _beds)) => {
-            if room.is_deleted {
-                return Err(ErrorResponse::new(
-                    format!("Room with id '{}' not found", input.room_id),
-                    StatusCode::NOT_FOUND,
-                ));
-            }
+    if let Ok((room, _beds)) = &room_beds {
+        if room.is_deleted {
+            return Err(ErrorResponse::new(
+                format!("Room with id '{}' not found", input.room_id),
+                StatusCode::NOT_FOUND,
+            ));
         }
-        _ => {}
     }
 
     room_beds
@@ -68,6 +65,8 @@ fn convert_room_to_output(room_beds: (room::Model, Vec<bed::Model>)) -> GetRoomO
         floor: room_beds.0.floor,
         room_number: room_beds.0.room_number,
         bathroom_type: room_beds.0.bathroom_type,
+        is_deleted: room_beds.0.is_deleted,
+        cancellation_policy_id: room_beds.0.cancellation_policy_id,
         beds,
     }
 }
@@ -0,0 +1,39 @@
+use actix_web::http::StatusCode;
+
+use crate::{
+    api::{
+        error_response::ErrorResponse,
+        room::get_room_quote::{GetRoomQuoteInput, GetRoomQuoteOutput},
+    },
+    app_state::AppState,
+    persistence::room,
+    services::pricing::{calculate_night_prices, sum_night_prices},
+    util::require_some,
+};
+
+pub async fn get_room_quote_service(
+    app_state: &AppState,
+    input: GetRoomQuoteInput,
+) -> Result<GetRoomQuoteOutput, ErrorResponse> {
+    let room_id = input.room_id.unwrap();
+    let room_option = room::find_room_by_id(app_state.db.as_ref(), room_id).await?;
+    let message = || format!("Room with id '{}' not found", room_id);
+    let (room, _beds) = require_some(room_option, message, StatusCode::NOT_FOUND)?;
+    if room.is_deleted {
+        return Err(ErrorResponse::new(message(), StatusCode::NOT_FOUND));
+    }
+
+    let nights = calculate_night_prices(
+        app_state.db.as_ref(),
+        &room,
+        input.start_date,
+        input.end_date,
+    )
+    .await?;
+
+    Ok(GetRoomQuoteOutput {
+        room_id,
+        total_price: sum_night_prices(&nights),
+        nights,
+    })
+}
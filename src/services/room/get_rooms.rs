@@ -0,0 +1,60 @@
+use crate::{
+    api::{
+        error_response::ErrorResponse,
+        room::{
+            get_room::GetRoomOutput,
+            get_rooms::{GetRoomsInput, GetRoomsOutput},
+        },
+    },
+    app_state::AppState,
+    persistence::room::{self, RoomFilter, SortOrder},
+};
+
+use super::find_beds_by_room;
+
+pub async fn get_rooms_service(
+    app_state: &AppState,
+    input: GetRoomsInput,
+) -> Result<GetRoomsOutput, ErrorResponse> {
+    let filter = create_filter(&input);
+    let (total_size, rooms) = room::find_paged_rooms(
+        app_state.db.as_ref(),
+        &filter,
+        None,
+        SortOrder::Ascending,
+        input.page,
+        input.size,
+    )
+    .await?;
+
+    let mut beds_by_room = find_beds_by_room(app_state, &rooms).await?;
+    let rooms = rooms
+        .into_iter()
+        .map(|room| GetRoomOutput {
+            beds: beds_by_room.remove(&room.id).unwrap_or_default(),
+            id: room.id,
+            price: room.price,
+            floor: room.floor,
+            room_number: room.room_number,
+            bathroom_type: room.bathroom_type,
+            is_deleted: room.is_deleted,
+            cancellation_policy_id: room.cancellation_policy_id,
+        })
+        .collect();
+
+    Ok(GetRoomsOutput { total_size, rooms })
+}
+
+fn create_filter(input: &GetRoomsInput) -> RoomFilter {
+    RoomFilter {
+        include_deleted: input.include_deleted,
+        room_number_prefix: input.room_number_prefix.clone(),
+        bathroom_type: input.bathroom_type.clone(),
+        bed_sizes: input.bed_sizes.clone(),
+        minimum_floor: input.floor,
+        maximum_floor: input.floor,
+        minimum_price: input.minimum_price,
+        maximum_price: input.maximum_price,
+        ..Default::default()
+    }
+}
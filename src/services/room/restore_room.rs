@@ -0,0 +1,85 @@
+use actix_web::http::StatusCode;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, DatabaseTransaction, IntoActiveModel, TransactionTrait,
+};
+use uuid::Uuid;
+
+use crate::{
+    api::{
+        error_response::ErrorResponse,
+        room::restore_room::{RestoreRoomInput, RestoreRoomOutput},
+    },
+    app_state::AppState,
+    persistence::room,
+    util::require_some,
+};
+
+use super::handle_room_number_error;
+
+pub async fn restore_room_service(
+    app_state: &AppState,
+    input: RestoreRoomInput,
+) -> Result<RestoreRoomOutput, ErrorResponse> {
+    let transaction = app_state.db.begin().await?;
+    let room = lock_deleted_room(&transaction, input.room_id).await?;
+    check_room_number_not_used(&transaction, &room).await?;
+    clear_delete_flag_for_room(&transaction, room).await?;
+    transaction.commit().await?;
+
+    Ok(RestoreRoomOutput)
+}
+
+async fn lock_deleted_room(
+    transaction: &DatabaseTransaction,
+    room_id: Uuid,
+) -> Result<room::Model, ErrorResponse> {
+    let room_option = room::lock_room_by_id(transaction, room_id).await?;
+
+    let room = require_some(
+        room_option,
+        || format!("Room with id '{}' not found", room_id),
+        StatusCode::NOT_FOUND,
+    )?;
+
+    if !room.is_deleted {
+        return Err(ErrorResponse::new(
+            format!("Room with id '{}' is not deleted", room_id),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    Ok(room)
+}
+
+async fn check_room_number_not_used(
+    transaction: &DatabaseTransaction,
+    room: &room::Model,
+) -> Result<(), ErrorResponse> {
+    let room_option =
+        room::find_first_by_room_number_not_deleted(transaction, &room.room_number).await?;
+
+    if room_option.is_some() {
+        return Err(ErrorResponse::new(
+            format!("Room number '{}' is already in use", room.room_number),
+            StatusCode::CONFLICT,
+        ));
+    }
+
+    Ok(())
+}
+
+async fn clear_delete_flag_for_room(
+    transaction: &DatabaseTransaction,
+    room: room::Model,
+) -> Result<(), ErrorResponse> {
+    let room_number = room.room_number.clone();
+    room::ActiveModel {
+        is_deleted: ActiveValue::Set(false),
+        ..room.into_active_model()
+    }
+    .update(transaction)
+    .await
+    .map_err(|err| handle_room_number_error(err, &room_number))?;
+
+    Ok(())
+}
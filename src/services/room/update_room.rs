@@ -0,0 +1,252 @@
+use actix_web::http::StatusCode;
+use sea_orm::{
+    sqlx::types::chrono::Utc, ActiveModelTrait, ActiveValue, DatabaseTransaction, IntoActiveModel,
+    TransactionTrait,
+};
+use uuid::Uuid;
+
+use crate::{
+    api::{
+        error_response::ErrorResponse,
+        room::update_room::{UpdateRoomInput, UpdateRoomOutput},
+    },
+    app_state::AppState,
+    persistence::{bed, booking, room},
+    services::cancellation_policy::find_cancellation_policy,
+    util::require_some,
+};
+
+use super::{handle_room_number_error, insert_bed};
+
+pub async fn update_room_service(
+    app_state: &AppState,
+    input: UpdateRoomInput,
+) -> Result<UpdateRoomOutput, ErrorResponse> {
+    let room_id = input.room_id.unwrap();
+    if let Some(policy_id) = input.cancellation_policy_id {
+        find_cancellation_policy(app_state.db.as_ref(), policy_id).await?;
+    }
+
+    let transaction = app_state.db.begin().await?;
+    let room = lock_room(&transaction, room_id).await?;
+    check_room_number_not_used(&transaction, room_id, &input).await?;
+    check_capacity_fits_future_bookings(&transaction, room_id, &input).await?;
+    update_room(&transaction, room, &input).await?;
+    replace_beds(&transaction, room_id, &input).await?;
+    transaction.commit().await?;
+
+    Ok(UpdateRoomOutput)
+}
+
+async fn check_room_number_not_used(
+    transaction: &DatabaseTransaction,
+    room_id: Uuid,
+    input: &UpdateRoomInput,
+) -> Result<(), ErrorResponse> {
+    let room_option =
+        room::find_first_by_room_number_not_deleted(transaction, &input.room_number).await?;
+
+    match room_option {
+        Some(found) if found.id != room_id => Err(ErrorResponse::new(
+            format!("Room number '{}' is already in use", input.room_number),
+            StatusCode::CONFLICT,
+        )),
+        _ => Ok(()),
+    }
+}
+
+async fn lock_room(
+    transaction: &DatabaseTransaction,
+    room_id: Uuid,
+) -> Result<room::Model, ErrorResponse> {
+    let room_option = room::lock_room_by_id(transaction, room_id).await?;
+    let message = || format!("Room with id '{}' not found", room_id);
+    let room = require_some(room_option, message, StatusCode::NOT_FOUND)?;
+
+    if room.is_deleted {
+        return Err(ErrorResponse::new(message(), StatusCode::NOT_FOUND));
+    }
+
+    Ok(room)
+}
+
+async fn check_capacity_fits_future_bookings(
+    transaction: &DatabaseTransaction,
+    room_id: Uuid,
+    input: &UpdateRoomInput,
+) -> Result<(), ErrorResponse> {
+    let new_capacity: i64 = input
+        .beds
+        .iter()
+        .map(|bed| (bed.bed_size.get_size() * bed.count) as i64)
+        .sum();
+
+    let max_guests =
+        booking::find_max_guest_count_for_room_from(transaction, room_id, Utc::now().date_naive())
+            .await?
+            .unwrap_or(0);
+
+    if new_capacity < max_guests {
+        Err(ErrorResponse::new(
+            format!(
+                "Room capacity '{}' is less than the '{}' guests of an existing booking",
+                new_capacity, max_guests
+            ),
+            StatusCode::CONFLICT,
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+async fn update_room(
+    transaction: &DatabaseTransaction,
+    room: room::Model,
+    input: &UpdateRoomInput,
+) -> Result<(), ErrorResponse> {
+    room::ActiveModel {
+        price: ActiveValue::Set(input.price),
+        floor: ActiveValue::Set(input.floor),
+        room_number: ActiveValue::Set(input.room_number.clone()),
+        bathroom_type: ActiveValue::Set(input.bathroom_type.clone()),
+        cancellation_policy_id: ActiveValue::Set(input.cancellation_policy_id),
+        ..room.into_active_model()
+    }
+    .update(transaction)
+    .await
+    .map_err(|err| handle_room_number_error(err, &input.room_number))?;
+
+    Ok(())
+}
+
+async fn replace_beds(
+    transaction: &DatabaseTransaction,
+    room_id: Uuid,
+    input: &UpdateRoomInput,
+) -> Result<(), ErrorResponse> {
+    bed::delete_beds_for_room(transaction, room_id).await?;
+    for bed in &input.beds {
+        insert_bed(transaction, bed, &room_id).await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::DatabaseConnection;
+
+    use crate::{
+        api::room::Bed,
+        persistence::{
+            bed::BedSize,
+            booking::BookingStatus,
+            booking_guest, connect_test_db,
+            room::BathroomType,
+            test_data::{insert_booking, insert_guest, insert_room, new_booking},
+        },
+        util::{add_days, sub_days},
+    };
+
+    use super::*;
+
+    async fn insert_booking_for_two(
+        db: &DatabaseConnection,
+        booking: booking::Model,
+    ) -> booking::Model {
+        let booking = insert_booking(db, booking).await;
+        let guest = insert_guest(db, Uuid::new_v4()).await;
+        booking_guest::Model {
+            guest_id: guest.id,
+            booking_id: booking.id,
+        }
+        .into_active_model()
+        .insert(db)
+        .await
+        .unwrap();
+
+        booking
+    }
+
+    #[actix_web::test]
+    async fn test_capacity_ignores_bookings_ending_today() {
+        let db = connect_test_db().await;
+        let today = Utc::now().date_naive();
+        let room = insert_room(&db, Uuid::new_v4()).await;
+        insert_booking_for_two(
+            &db,
+            booking::Model {
+                room_id: room.id,
+                ..new_booking(BookingStatus::Paid, sub_days(today, 2).unwrap(), today)
+            },
+        )
+        .await;
+        let input = UpdateRoomInput {
+            room_id: Some(room.id),
+            beds: vec![Bed {
+                bed_size: BedSize::Single,
+                count: 1,
+            }],
+            price: 100,
+            floor: 1,
+            room_number: "1".to_owned(),
+            bathroom_type: BathroomType::Private,
+            cancellation_policy_id: None,
+        };
+
+        let transaction = db.begin().await.unwrap();
+        check_capacity_fits_future_bookings(&transaction, room.id, &input)
+            .await
+            .unwrap();
+        transaction.commit().await.unwrap();
+
+        insert_booking_for_two(
+            &db,
+            booking::Model {
+                room_id: room.id,
+                ..new_booking(BookingStatus::Paid, today, add_days(today, 2).unwrap())
+            },
+        )
+        .await;
+        let transaction = db.begin().await.unwrap();
+        let err = check_capacity_fits_future_bookings(&transaction, room.id, &input)
+            .await
+            .unwrap_err();
+        assert_eq!(err.status, StatusCode::CONFLICT);
+    }
+
+    #[actix_web::test]
+    async fn test_room_number_of_another_room_is_a_conflict() {
+        let db = connect_test_db().await;
+        let room = insert_room(&db, Uuid::new_v4()).await;
+        let other_room = insert_room(&db, Uuid::new_v4()).await;
+        let input = UpdateRoomInput {
+            room_id: Some(room.id),
+            beds: vec![],
+            price: 100,
+            floor: 1,
+            room_number: other_room.room_number.clone(),
+            bathroom_type: BathroomType::Private,
+            cancellation_policy_id: None,
+        };
+
+        // Skips the room number check like a concurrent update would
+        let transaction = db.begin().await.unwrap();
+        let err = update_room(&transaction, room.clone(), &input)
+            .await
+            .unwrap_err();
+        assert_eq!(err.status, StatusCode::CONFLICT);
+        transaction.rollback().await.unwrap();
+
+        room::ActiveModel {
+            is_deleted: ActiveValue::Set(true),
+            ..other_room.into_active_model()
+        }
+        .update(&db)
+        .await
+        .unwrap();
+        let transaction = db.begin().await.unwrap();
+        update_room(&transaction, room, &input).await.unwrap();
+        transaction.commit().await.unwrap();
+    }
+}
@@ -5,9 +5,14 @@ use actix_web::{
     http::{header::ContentType, StatusCode},
     HttpRequest, HttpResponse,
 };
+use chrono::Days;
 use jsonwebtoken::get_current_timestamp;
 use log::error;
-use serde::Serialize;
+use sea_orm::prelude::Date;
+use serde::{
+    de::{self, IntoDeserializer},
+    Deserialize, Deserializer, Serialize,
+};
 
 use crate::{
     api::error_response::ErrorResponse,
@@ -152,3 +157,41 @@ where
         Err(ErrorResponse::new(message_provider(), status))
     }
 }
+
+pub fn deserialize_comma_separated<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    let option: Option<String> = Option::deserialize(deserializer)?;
+    let Some(values) = option else {
+        return Ok(vec![]);
+    };
+
+    values
+        .split(',')
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|value| {
+            T::deserialize(value.to_owned().into_deserializer())
+                .map_err(|err: de::value::Error| de::Error::custom(err))
+        })
+        .collect()
+}
+
+pub fn add_days(date: Date, days: u64) -> Result<Date, ErrorResponse> {
+    require_some(
+        date.checked_add_days(Days::new(days)),
+        || format!("Date {date} plus {days} days is out of range"),
+        StatusCode::BAD_REQUEST,
+    )
+}
+
+#[cfg(test)]
+pub fn sub_days(date: Date, days: u64) -> Result<Date, ErrorResponse> {
+    require_some(
+        date.checked_sub_days(Days::new(days)),
+        || format!("Date {date} minus {days} days is out of range"),
+        StatusCode::BAD_REQUEST,
+    )
+}
@@ -19,6 +19,7 @@ pub struct Validator {
     phone_number_regex: Regex,
     id_card_issue_authority_regex: Regex,
     comment_contents_regex: Regex,
+    language_regex: Regex,
 }
 impl Validator {
     pub fn new() -> Self {
@@ -40,6 +41,8 @@ impl Validator {
                 .expect("Error creating id card issue authority regex"),
             comment_contents_regex: Regex::new("^(\\s*[^ \\t\\r\\n].{0,255})$")
                 .expect("Error creating comment contents regex"),
+            language_regex: Regex::new("^[a-z]{2}(?:-[A-Z]{2})?$")
+                .expect("Error creating language regex"),
         }
     }
 
@@ -69,6 +72,12 @@ impl Validator {
         })
     }
 
+    pub fn validate_language(&self, language: &str) -> Result<(), ErrorResponse> {
+        Self::validate(&self.language_regex, language, || {
+            format!("Invalid language: {}", language)
+        })
+    }
+
     pub fn validate_room_number(&self, room_number: &str) -> Result<(), ErrorResponse> {
         Self::validate(&self.room_number_regex, room_number, || {
             "Invalid room number: Needs to be numbers optionally followed by an upper case letter"